@@ -498,7 +498,15 @@ async fn queue(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
             },
         };
 
-        handler.enqueue_source(source.into());
+        if let Err(why) = handler.enqueue_source(source.into()) {
+            check_msg(
+                msg.channel_id
+                    .say(&ctx.http, format!("Couldn't queue song: {}", why))
+                    .await,
+            );
+
+            return Ok(());
+        }
 
         check_msg(
             msg.channel_id
@@ -1,5 +1,15 @@
 #[cfg(feature = "driver-core")]
-use super::driver::{retry::Retry, CryptoMode, DecodeMode};
+use super::constants::UDP_KEEPALIVE_GAP;
+#[cfg(feature = "driver-core")]
+use super::driver::{
+    opus::Application,
+    retry::Retry,
+    CryptoMode,
+    DecodeMode,
+    EncoderResetPolicy,
+    SendPolicy,
+    SilenceMode,
+};
 
 use std::time::Duration;
 
@@ -34,6 +44,43 @@ pub struct Config {
     /// [`DecodeMode::Pass`]: DecodeMode::Pass
     /// [user speaking events]: crate::events::CoreEvent::SpeakingUpdate
     pub decode_mode: DecodeMode,
+    #[cfg(feature = "driver-core")]
+    /// Whether a track which ends before producing any audio is treated as having
+    /// failed, rather than having finished normally.
+    ///
+    /// Empty files or failed fetches often yield no data at all. When this is set,
+    /// such tracks end as [`PlayMode::Errored`], firing [`TrackEvent::Error`] rather
+    /// than only [`TrackEvent::End`], and are subject to any configured retries.
+    ///
+    /// Defaults to `false`.
+    ///
+    /// [`PlayMode::Errored`]: crate::tracks::PlayMode::Errored
+    /// [`TrackEvent::Error`]: crate::events::TrackEvent::Error
+    /// [`TrackEvent::End`]: crate::events::TrackEvent::End
+    pub empty_input_error: bool,
+    #[cfg(feature = "driver-core")]
+    /// When the mixer should automatically clear the state of its Opus encoder.
+    ///
+    /// Defaults to [`EncoderResetPolicy::Manual`].
+    ///
+    /// [`EncoderResetPolicy::Manual`]: EncoderResetPolicy::Manual
+    pub encoder_reset: EncoderResetPolicy,
+    #[cfg(feature = "driver-core")]
+    /// Maximum number of event handlers which may run at once for any single event.
+    ///
+    /// Handlers never block the mixer, and so cannot stall playback. However, the driver
+    /// dispatches each event only after all handlers for the previous event have finished,
+    /// so a slow handler delays every later event (and the track state they report).
+    /// Raising this lets other handlers registered for the same event (e.g., on many
+    /// tracks, or several global handlers) run alongside a slow one.
+    ///
+    /// Events are always dispatched in order. Within one event, handlers *begin* in
+    /// registration order and their results are applied in that order, but when this
+    /// exceeds `1` their bodies may interleave and complete out of order.
+    ///
+    /// Defaults to `1`, running each handler to completion before the next begins.
+    /// A value of `0` is treated as `1`.
+    pub event_handler_concurrency: usize,
     #[cfg(feature = "gateway-core")]
     /// Configures the amount of time to wait for Discord to reply with connection information
     /// if [`Call::join`]/[`join_gateway`] are used.
@@ -48,6 +95,80 @@ pub struct Config {
     /// [`join_gateway`]: crate::Call::join_gateway
     pub gateway_timeout: Option<Duration>,
     #[cfg(feature = "driver-core")]
+    /// Delay between UDP keepalive packets sent to Discord's voice server.
+    ///
+    /// Lowering this can help hold open the mappings of restrictive NATs or
+    /// firewalls. Values shorter than [`UDP_KEEPALIVE_MIN_GAP`] are raised to it.
+    ///
+    /// Defaults to 5 seconds. Changes to this field will not immediately apply
+    /// if the driver is actively connected, but will apply to subsequent sessions.
+    ///
+    /// [`UDP_KEEPALIVE_MIN_GAP`]: crate::constants::UDP_KEEPALIVE_MIN_GAP
+    pub keepalive_interval: Duration,
+    #[cfg(feature = "driver-core")]
+    /// Whether the mixer should measure how long it spends mixing each frame,
+    /// as reported by [`Driver::mix_timing`].
+    ///
+    /// Defaults to `false`, as measurement adds a small per-frame overhead.
+    ///
+    /// [`Driver::mix_timing`]: crate::driver::Driver::mix_timing
+    pub mix_timing: bool,
+    #[cfg(feature = "driver-core")]
+    /// Reports [`CoreEvent::MixerStall`] if the mixer goes longer than this without
+    /// producing an audio frame while connected, or `None` to disable monitoring.
+    ///
+    /// Values shorter than 100ms are raised to 100ms.
+    ///
+    /// Defaults to `None`. This is read when the driver is created: changes made
+    /// via [`Driver::set_config`] will not start or stop monitoring.
+    ///
+    /// [`CoreEvent::MixerStall`]: crate::events::CoreEvent::MixerStall
+    /// [`Driver::set_config`]: crate::driver::Driver::set_config
+    pub mixer_watchdog: Option<Duration>,
+    #[cfg(feature = "driver-core")]
+    /// Application mode used to tune the Opus encoder for sent audio.
+    ///
+    /// [`Application::Audio`] suits music, [`Application::Voip`] favours speech
+    /// intelligibility, and [`Application::LowDelay`] minimises encoder latency.
+    ///
+    /// Defaults to [`Application::Audio`].
+    ///
+    /// Changes to this field will not immediately apply if the
+    /// driver is actively connected, as the encoder must be recreated,
+    /// but will apply to subsequent sessions.
+    ///
+    /// [`Application::Audio`]: Application::Audio
+    /// [`Application::Voip`]: Application::Voip
+    /// [`Application::LowDelay`]: Application::LowDelay
+    pub opus_application: Application,
+    #[cfg(feature = "driver-core")]
+    /// Whether the Opus encoder for sent audio uses a variable bitrate (VBR).
+    ///
+    /// VBR typically improves quality for music, while a constant bitrate (CBR)
+    /// gives predictable bandwidth use for live streams.
+    ///
+    /// Defaults to `true`.
+    ///
+    /// Changes to this field will not immediately apply if the
+    /// driver is actively connected, as the encoder must be recreated,
+    /// but will apply to subsequent sessions.
+    pub opus_vbr: bool,
+    #[cfg(feature = "driver-core")]
+    /// Whether variable bitrate encoding is constrained, which limits how far
+    /// the bitrate of each frame may differ from the target [bitrate].
+    ///
+    /// This has no effect unless [`opus_vbr`] is set.
+    ///
+    /// Defaults to `true`.
+    ///
+    /// Changes to this field will not immediately apply if the
+    /// driver is actively connected, as the encoder must be recreated,
+    /// but will apply to subsequent sessions.
+    ///
+    /// [bitrate]: crate::driver::Driver::set_bitrate
+    /// [`opus_vbr`]: Config::opus_vbr
+    pub opus_vbr_constraint: bool,
+    #[cfg(feature = "driver-core")]
     /// Number of concurrently active tracks to allocate memory for.
     ///
     /// This should be set at, or just above, the maximum number of tracks
@@ -61,12 +182,46 @@ pub struct Config {
     /// the capacity of the track store.
     pub preallocated_tracks: usize,
     #[cfg(feature = "driver-core")]
+    /// Length of the fade applied on either side of a seek, including the seek
+    /// made when a looping track restarts.
+    ///
+    /// Audio is faded out before each seek and faded back in afterwards, which
+    /// avoids audible clicks from the discontinuity. This is capped at one frame (20ms),
+    /// and setting this to [`Duration::ZERO`] disables seek fades entirely.
+    ///
+    /// Defaults to 10ms.
+    ///
+    /// [`Duration::ZERO`]: Duration::ZERO
+    pub seek_crossfade: Duration,
+    #[cfg(feature = "driver-core")]
+    /// Pacing used when sending voice packets to Discord.
+    ///
+    /// On hosts with high scheduling jitter (e.g., heavily loaded VPSes),
+    /// [`SendPolicy::BurstAhead`] can prevent late packets from causing choppy audio.
+    ///
+    /// Defaults to [`SendPolicy::Steady`].
+    ///
+    /// [`SendPolicy::BurstAhead`]: SendPolicy::BurstAhead
+    /// [`SendPolicy::Steady`]: SendPolicy::Steady
+    pub send_policy: SendPolicy,
+    #[cfg(feature = "driver-core")]
+    /// What the mixer sends while its tracks play silent audio.
+    ///
+    /// Defaults to [`SilenceMode::Silence`]. Changes to this field apply immediately.
+    ///
+    /// [`SilenceMode::Silence`]: SilenceMode::Silence
+    pub silence_mode: SilenceMode,
+    #[cfg(feature = "driver-core")]
     /// Connection retry logic for the [`Driver`].
     ///
     /// This controls how many times the [`Driver`] should retry any connections,
-    /// as well as how long to wait between attempts.
+    /// as well as how long to wait between attempts. This applies both to initial
+    /// connections and to reconnections after the voice connection drops; the
+    /// attempt count is reported in [`ConnectData`] and [`DisconnectData`].
     ///
     /// [`Driver`]: crate::driver::Driver
+    /// [`ConnectData`]: crate::events::context_data::ConnectData
+    /// [`DisconnectData`]: crate::events::context_data::DisconnectData
     pub driver_retry: Retry,
     #[cfg(feature = "driver-core")]
     /// Configures the maximum amount of time to wait for an attempted voice
@@ -83,11 +238,35 @@ impl Default for Config {
             crypto_mode: CryptoMode::Normal,
             #[cfg(feature = "driver-core")]
             decode_mode: DecodeMode::Decrypt,
+            #[cfg(feature = "driver-core")]
+            empty_input_error: false,
+            #[cfg(feature = "driver-core")]
+            encoder_reset: EncoderResetPolicy::Manual,
+            #[cfg(feature = "driver-core")]
+            event_handler_concurrency: 1,
             #[cfg(feature = "gateway-core")]
             gateway_timeout: Some(Duration::from_secs(10)),
             #[cfg(feature = "driver-core")]
+            keepalive_interval: UDP_KEEPALIVE_GAP,
+            #[cfg(feature = "driver-core")]
+            mix_timing: false,
+            #[cfg(feature = "driver-core")]
+            mixer_watchdog: None,
+            #[cfg(feature = "driver-core")]
+            opus_application: Application::Audio,
+            #[cfg(feature = "driver-core")]
+            opus_vbr: true,
+            #[cfg(feature = "driver-core")]
+            opus_vbr_constraint: true,
+            #[cfg(feature = "driver-core")]
             preallocated_tracks: 1,
             #[cfg(feature = "driver-core")]
+            seek_crossfade: Duration::from_millis(10),
+            #[cfg(feature = "driver-core")]
+            send_policy: SendPolicy::Steady,
+            #[cfg(feature = "driver-core")]
+            silence_mode: SilenceMode::Silence,
+            #[cfg(feature = "driver-core")]
             driver_retry: Default::default(),
             #[cfg(feature = "driver-core")]
             driver_timeout: Some(Duration::from_secs(10)),
@@ -109,12 +288,84 @@ impl Config {
         self
     }
 
+    /// Sets whether this `Config` treats tracks which produce no audio as errored.
+    pub fn empty_input_error(mut self, empty_input_error: bool) -> Self {
+        self.empty_input_error = empty_input_error;
+        self
+    }
+
+    /// Sets this `Config`'s policy for automatically resetting the Opus encoder.
+    pub fn encoder_reset(mut self, encoder_reset: EncoderResetPolicy) -> Self {
+        self.encoder_reset = encoder_reset;
+        self
+    }
+
+    /// Sets this `Config`'s maximum number of concurrently running handlers per event.
+    pub fn event_handler_concurrency(mut self, event_handler_concurrency: usize) -> Self {
+        self.event_handler_concurrency = event_handler_concurrency;
+        self
+    }
+
+    /// Sets this `Config`'s delay between UDP keepalive packets.
+    pub fn keepalive_interval(mut self, keepalive_interval: Duration) -> Self {
+        self.keepalive_interval = keepalive_interval;
+        self
+    }
+
+    /// Sets whether this `Config` measures per-frame mix timing.
+    pub fn mix_timing(mut self, mix_timing: bool) -> Self {
+        self.mix_timing = mix_timing;
+        self
+    }
+
+    /// Sets this `Config`'s mixer stall detection window.
+    pub fn mixer_watchdog(mut self, mixer_watchdog: Option<Duration>) -> Self {
+        self.mixer_watchdog = mixer_watchdog;
+        self
+    }
+
+    /// Sets this `Config`'s Opus encoder application mode.
+    pub fn opus_application(mut self, opus_application: Application) -> Self {
+        self.opus_application = opus_application;
+        self
+    }
+
+    /// Sets whether this `Config`'s Opus encoder uses a variable bitrate.
+    pub fn opus_vbr(mut self, opus_vbr: bool) -> Self {
+        self.opus_vbr = opus_vbr;
+        self
+    }
+
+    /// Sets whether this `Config`'s Opus encoder constrains its variable bitrate.
+    pub fn opus_vbr_constraint(mut self, opus_vbr_constraint: bool) -> Self {
+        self.opus_vbr_constraint = opus_vbr_constraint;
+        self
+    }
+
     /// Sets this `Config`'s number of tracks to preallocate.
     pub fn preallocated_tracks(mut self, preallocated_tracks: usize) -> Self {
         self.preallocated_tracks = preallocated_tracks;
         self
     }
 
+    /// Sets this `Config`'s fade length around seeks.
+    pub fn seek_crossfade(mut self, seek_crossfade: Duration) -> Self {
+        self.seek_crossfade = seek_crossfade;
+        self
+    }
+
+    /// Sets this `Config`'s voice packet send pacing.
+    pub fn send_policy(mut self, send_policy: SendPolicy) -> Self {
+        self.send_policy = send_policy;
+        self
+    }
+
+    /// Sets what this `Config`'s mixer sends while its tracks play silent audio.
+    pub fn silence_mode(mut self, silence_mode: SilenceMode) -> Self {
+        self.silence_mode = silence_mode;
+        self
+    }
+
     /// Sets this `Config`'s timeout for establishing a voice connection.
     pub fn driver_timeout(mut self, driver_timeout: Option<Duration>) -> Self {
         self.driver_timeout = driver_timeout;
@@ -64,6 +64,13 @@ pub const UDP_KEEPALIVE_GAP_MS: u64 = 5_000;
 /// irrespective of outgoing UDP traffic.
 pub const UDP_KEEPALIVE_GAP: Duration = Duration::from_millis(UDP_KEEPALIVE_GAP_MS);
 
+/// Shortest delay between UDP keepalive frames which the driver will use.
+///
+/// Shorter intervals configured via [`Config::keepalive_interval`] are raised to this value.
+///
+/// [`Config::keepalive_interval`]: crate::Config::keepalive_interval
+pub const UDP_KEEPALIVE_MIN_GAP: Duration = Duration::from_millis(500);
+
 /// Opus silent frame, used to signal speech start and end (and prevent audio glitching).
 pub const SILENT_FRAME: [u8; 3] = [0xf8, 0xff, 0xfe];
 
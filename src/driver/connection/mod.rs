@@ -7,6 +7,10 @@ use super::{
 };
 use crate::{
     constants::*,
+    events::{
+        context_data::{ConnectProgressData, ConnectStage},
+        CoreContext,
+    },
     model::{
         payload::{Identify, Resume, SelectProtocol},
         Event as GatewayEvent,
@@ -31,6 +35,7 @@ use ws::create_rustls_client;
 use ws::create_native_tls_client;
 
 pub(crate) struct Connection {
+    pub(crate) crypto_mode: CryptoMode,
     pub(crate) info: ConnectionInfo,
     pub(crate) ssrc: u32,
     pub(crate) ws: Sender<WsMessage>,
@@ -58,12 +63,16 @@ impl Connection {
     ) -> Result<Connection> {
         let url = generate_url(&mut info.endpoint)?;
 
+        report_progress(interconnect, &info, ConnectStage::Gateway);
+
         #[cfg(all(feature = "rustls-marker", not(feature = "native-marker")))]
         let mut client = create_rustls_client(url).await?;
 
         #[cfg(feature = "native-marker")]
         let mut client = create_native_tls_client(url).await?;
 
+        report_progress(interconnect, &info, ConnectStage::Identify);
+
         let mut hello = None;
         let mut ready = None;
 
@@ -112,6 +121,8 @@ impl Connection {
             return Err(Error::CryptoModeUnavailable);
         }
 
+        report_progress(interconnect, &info, ConnectStage::UdpDiscovery);
+
         let udp = UdpSocket::bind("0.0.0.0:0").await?;
         udp.connect((ready.ip, ready.port)).await?;
 
@@ -155,6 +166,8 @@ impl Connection {
                 Error::IllegalIp
             })?;
 
+            report_progress(interconnect, &info, ConnectStage::SelectProtocol);
+
             client
                 .send_json(&GatewayEvent::from(SelectProtocol {
                     protocol: "udp".into(),
@@ -217,9 +230,16 @@ impl Connection {
             config.clone(),
             udp_rx,
         ));
-        spawn(udp_tx::runner(udp_sender_msg_rx, ssrc, udp_tx));
+        spawn(udp_tx::runner(
+            udp_sender_msg_rx,
+            interconnect.core.clone(),
+            ssrc,
+            config.keepalive_interval,
+            udp_tx,
+        ));
 
         Ok(Connection {
+            crypto_mode: config.crypto_mode,
             info,
             ssrc,
             ws: ws_msg_tx,
@@ -304,6 +324,19 @@ impl Drop for Connection {
     }
 }
 
+fn report_progress(interconnect: &Interconnect, info: &ConnectionInfo, stage: ConnectStage) {
+    // Event handlers are not required for connection to succeed.
+    let _ = interconnect
+        .events
+        .send(EventMessage::FireCoreEvent(CoreContext::ConnectProgress(
+            ConnectProgressData {
+                channel_id: info.channel_id,
+                guild_id: info.guild_id,
+                stage,
+            },
+        )));
+}
+
 fn generate_url(endpoint: &mut String) -> Result<Url> {
     if endpoint.ends_with(":80") {
         let len = endpoint.len();
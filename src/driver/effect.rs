@@ -0,0 +1,45 @@
+use crate::{
+    events::{EventContext, EventData, TrackEvent},
+    input::Input,
+    tracks::{self, Track},
+    Event,
+    EventHandler,
+};
+use async_trait::async_trait;
+use uuid::Uuid;
+
+/// Creates a track for [`Driver::play_effect`], which cleans up after itself.
+///
+/// [`Driver::play_effect`]: super::Driver::play_effect
+pub(crate) fn create_effect(source: Input) -> (Track, Uuid) {
+    let (mut track, handle) = tracks::create_player(source);
+
+    track
+        .events
+        .as_mut()
+        .expect("Effect inspecting EventStore on new Track: did not exist.")
+        .add_event(
+            EventData::new(Event::Track(TrackEvent::End), EffectCleanup),
+            track.position,
+        );
+
+    (track, handle.uuid())
+}
+
+/// Stops an effect once it has ended, so that the mixer is guaranteed to drop
+/// it, before removing itself.
+struct EffectCleanup;
+
+#[async_trait]
+impl EventHandler for EffectCleanup {
+    async fn act(&self, ctx: &EventContext<'_>) -> Option<Event> {
+        if let EventContext::Track(ts) = ctx {
+            for (_state, handle) in ts.iter() {
+                // The track has usually been removed already, closing its handle.
+                let _ = handle.stop();
+            }
+        }
+
+        Some(Event::Cancel)
+    }
+}
@@ -0,0 +1,43 @@
+use std::time::Duration;
+
+/// Strategy used by the mixer to clear its Opus encoder's state.
+///
+/// Resetting the encoder discards predictor state carried over from earlier audio,
+/// which can otherwise cause a brief artifact when audio resumes after a long pause,
+/// a seek, or a mixer stall. The encoder's bitrate and other settings are kept.
+///
+/// The encoder may always be reset manually using [`Driver::reset_encoder`].
+///
+/// [`Driver::reset_encoder`]: super::Driver::reset_encoder
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum EncoderResetPolicy {
+    /// The encoder is only reset by [`Driver::reset_encoder`], or on reconnection.
+    ///
+    /// This is the default.
+    ///
+    /// [`Driver::reset_encoder`]: super::Driver::reset_encoder
+    Manual,
+    /// The encoder is reset before encoding any frame which follows a gap of at
+    /// least the given length since the last encoded frame.
+    ///
+    /// Gaps include silence from paused or finished tracks, muting, and periods where
+    /// the mixer fell behind real time.
+    AfterGap(Duration),
+}
+
+impl EncoderResetPolicy {
+    /// Returns whether the encoder should be reset, given the time since it last encoded audio.
+    pub(crate) fn should_reset(self, gap: Duration) -> bool {
+        match self {
+            Self::Manual => false,
+            Self::AfterGap(min_gap) => gap >= min_gap,
+        }
+    }
+}
+
+impl Default for EncoderResetPolicy {
+    fn default() -> Self {
+        Self::Manual
+    }
+}
@@ -0,0 +1,18 @@
+/// What the driver sent to Discord for a single 20ms frame.
+///
+/// Reported alongside each frame of a [`Recording`], allowing trailing silence
+/// to be trimmed.
+///
+/// [`Recording`]: super::Recording
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum FrameKind {
+    /// A packet of mixed or passthrough audio was sent.
+    Audio,
+    /// One of the explicit silent packets sent after audio stops, or while muted.
+    ///
+    /// Five of these are sent each time the driver stops speaking.
+    Silence,
+    /// No packet was sent, as there was no audio to play.
+    Idle,
+}
@@ -0,0 +1,58 @@
+use std::{collections::VecDeque, time::Duration};
+
+/// Number of frames (5 seconds of audio) summarised by [`MixTiming`].
+const WINDOW_FRAMES: usize = 250;
+
+/// Summary of the time the mixer has recently spent mixing each 20ms frame.
+///
+/// If [`max`] approaches the 20ms frame budget, the mixer is at risk
+/// of sending packets late, causing audible glitches.
+///
+/// [`max`]: MixTiming::max
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct MixTiming {
+    /// Mean time taken to mix one frame.
+    pub average: Duration,
+    /// Longest time taken to mix one frame.
+    pub max: Duration,
+    /// Number of frames covered by this summary.
+    pub frames: usize,
+    /// Number of tracks held by the mixer when the latest frame was mixed.
+    pub track_count: usize,
+}
+
+/// Rolling window of per-frame mix times, shared between a driver and its mixer.
+#[derive(Debug, Default)]
+pub(crate) struct MixTimingStats {
+    samples: VecDeque<Duration>,
+    track_count: usize,
+}
+
+impl MixTimingStats {
+    pub(crate) fn record(&mut self, time: Duration, track_count: usize) {
+        if self.samples.len() == WINDOW_FRAMES {
+            self.samples.pop_front();
+        }
+
+        self.samples.push_back(time);
+        self.track_count = track_count;
+    }
+
+    pub(crate) fn summary(&self) -> Option<MixTiming> {
+        let frames = self.samples.len();
+
+        if frames == 0 {
+            return None;
+        }
+
+        let total: Duration = self.samples.iter().sum();
+
+        Some(MixTiming {
+            average: total / frames as u32,
+            max: self.samples.iter().copied().max().unwrap_or_default(),
+            frames,
+            track_count: self.track_count,
+        })
+    }
+}
@@ -0,0 +1,393 @@
+//! An in-process driver for testing playback logic without a voice connection.
+//!
+//! Included if using the `"test-util"` feature flag.
+
+use super::{
+    tasks::{
+        events,
+        message::{
+            CoreMessage,
+            DisposalMessage,
+            EventMessage,
+            Interconnect,
+            MixerConnection,
+            MixerMessage,
+            UdpRxMessage,
+            UdpTxMessage,
+        },
+        mixer::Mixer,
+    },
+    CryptoState,
+};
+#[cfg(feature = "builtin-queue")]
+use crate::tracks::{QueueResult, TrackQueue};
+use crate::{
+    constants::STEREO_FRAME_SIZE,
+    driver::FrameKind,
+    events::EventData,
+    input::Input,
+    tracks::{self, Track, TrackHandle},
+    Config,
+    Event,
+    EventHandler,
+};
+use flume::{Receiver, Sender};
+use tokio::runtime::Handle;
+use tracing::debug;
+use uuid::Uuid;
+use xsalsa20poly1305::{aead::NewAead, XSalsa20Poly1305 as Cipher, KEY_SIZE};
+
+/// A voice driver which mixes audio on demand, rather than in real time.
+///
+/// Tracks, [`TrackQueue`]s and event handlers behave as they would in a [`Driver`],
+/// but each 20ms frame is only mixed when [`tick`] is called, and is returned
+/// to the caller instead of being sent to Discord. This makes tests of fades, loops,
+/// and queue transitions deterministic.
+///
+/// Frames are always mixed as PCM: Opus passthrough is never used.
+///
+/// Requires the `"test-util"` feature.
+///
+/// # Examples
+/// ```rust,no_run
+/// use songbird::{driver::MockDriver, input::Input};
+///
+/// # async fn example(source: Input) {
+/// let mut driver = MockDriver::new(Default::default());
+/// let handle = driver.play_source(source);
+///
+/// for _ in 0..49 {
+///     let frame = driver.tick().await;
+///     assert_eq!(frame.len(), songbird::constants::STEREO_FRAME_SIZE);
+/// }
+///
+/// // Track commands are only answered by a tick.
+/// let (state, _) = futures::join!(handle.get_info(), driver.tick());
+/// assert_eq!(state.unwrap().play_time, std::time::Duration::from_secs(1));
+/// # }
+/// ```
+///
+/// [`TrackQueue`]: crate::tracks::TrackQueue
+/// [`Driver`]: super::Driver
+/// [`tick`]: MockDriver::tick
+pub struct MockDriver {
+    mixer: Mixer,
+    events: Sender<EventMessage>,
+    frames: Receiver<(FrameKind, Vec<f32>)>,
+    last_kind: Option<FrameKind>,
+    // Held so that the mixer's sends to other tasks do not fail.
+    _core_rx: Receiver<CoreMessage>,
+    _mix_tx: Sender<MixerMessage>,
+    _udp_rx: Receiver<UdpRxMessage>,
+    _udp_tx: Receiver<UdpTxMessage>,
+    #[cfg(feature = "builtin-queue")]
+    queue: TrackQueue,
+}
+
+impl MockDriver {
+    /// Creates a new mock driver, with no tracks.
+    ///
+    /// # Panics
+    /// Panics if called outside of a Tokio runtime.
+    pub fn new(config: Config) -> Self {
+        let (mix_tx, mix_rx) = flume::unbounded();
+        let (core_tx, core_rx) = flume::unbounded();
+        let (event_tx, event_rx) = flume::unbounded();
+        let (udp_tx_tx, udp_tx_rx) = flume::unbounded();
+        let (udp_rx_tx, udp_rx_rx) = flume::unbounded();
+        let (frame_tx, frame_rx) = flume::unbounded();
+
+        let interconnect = Interconnect {
+            core: core_tx,
+            events: event_tx.clone(),
+            mixer: mix_tx.clone(),
+        };
+
+        tokio::spawn(events::runner(interconnect.clone(), event_rx));
+
+        let mut mixer = Mixer::new(mix_rx, Handle::current(), interconnect, config);
+
+        mixer.conn_active = Some(MixerConnection {
+            cipher: Cipher::new_from_slice(&[0u8; KEY_SIZE])
+                .expect("Zeroed key is of a valid length."),
+            crypto_state: CryptoState::Normal,
+            udp_rx: udp_rx_tx,
+            udp_tx: udp_tx_tx,
+        });
+        mixer.frame_sink = Some(frame_tx);
+        mixer.skip_sleep = true;
+
+        Self {
+            mixer,
+            events: event_tx,
+            frames: frame_rx,
+            last_kind: None,
+            _core_rx: core_rx,
+            _mix_tx: mix_tx,
+            _udp_rx: udp_rx_rx,
+            _udp_tx: udp_tx_rx,
+            #[cfg(feature = "builtin-queue")]
+            queue: Default::default(),
+        }
+    }
+
+    /// Plays audio from a source, returning a handle for further control.
+    pub fn play_source(&mut self, source: Input) -> TrackHandle {
+        let (player, handle) = tracks::create_player(source);
+        self.play(player);
+
+        handle
+    }
+
+    /// Plays a short sound alongside any other tracks, as in [`Driver::play_effect`].
+    ///
+    /// [`Driver::play_effect`]: super::Driver::play_effect
+    pub fn play_effect(&mut self, source: Input) -> Uuid {
+        let (player, uuid) = super::create_effect(source);
+        self.play(player);
+
+        uuid
+    }
+
+    /// Plays audio from a [`Track`] object.
+    ///
+    /// [`Track`]: crate::tracks::Track
+    pub fn play(&mut self, track: Track) {
+        self.mixer.add_track(track);
+    }
+
+    /// Stops and removes every track, as in [`Driver::stop_all`].
+    ///
+    /// Tracks are removed without waiting for a [`tick`].
+    ///
+    /// [`Driver::stop_all`]: super::Driver::stop_all
+    /// [`tick`]: MockDriver::tick
+    pub fn stop_all(&mut self) {
+        self.mixer
+            .handle_message(MixerMessage::SetTrack(None, None));
+    }
+
+    /// Attach a global event handler, as in [`Driver::add_global_event`].
+    ///
+    /// [`Driver::add_global_event`]: super::Driver::add_global_event
+    pub fn add_global_event<F: EventHandler + 'static>(&mut self, event: Event, action: F) {
+        let _ = self
+            .events
+            .send(EventMessage::AddGlobalEvent(EventData::new(event, action)));
+    }
+
+    /// Returns the number of tracks currently held by the mixer.
+    pub fn track_count(&self) -> usize {
+        self.mixer.tracks.len()
+    }
+
+    /// Mixes a single 20ms frame of audio, applies any pending track commands,
+    /// and waits for all event handlers triggered by this frame to finish.
+    ///
+    /// Returns the stereo, interleaved frame which would have been encoded and sent.
+    /// Silent or muted frames are returned as zeroes.
+    pub async fn tick(&mut self) -> Vec<f32> {
+        let cycled = match self.mixer.cycle() {
+            Ok(()) => self.mixer.audio_commands_events(),
+            Err(e) => Err(e),
+        };
+
+        if let Err(e) = cycled {
+            debug!("Mock driver cycle: {:?}", e);
+        }
+
+        let (tx, rx) = flume::bounded(1);
+        if self.events.send(EventMessage::Flush(tx)).is_ok() {
+            let _ = rx.recv_async().await;
+        }
+
+        match self.frames.try_iter().last() {
+            Some((kind, frame)) => {
+                self.last_kind = Some(kind);
+                frame
+            },
+            None => {
+                self.last_kind = None;
+                vec![0.0; STEREO_FRAME_SIZE]
+            },
+        }
+    }
+
+    /// Returns what would have been sent to Discord for the frame returned by the
+    /// last call to [`tick`], if a frame was mixed.
+    ///
+    /// [`tick`]: MockDriver::tick
+    pub fn last_frame_kind(&self) -> Option<FrameKind> {
+        self.last_kind
+    }
+}
+
+#[cfg(feature = "builtin-queue")]
+impl MockDriver {
+    /// Returns a reference to this driver's built-in queue.
+    ///
+    /// Requires the `"builtin-queue"` feature.
+    /// Queue additions should be made via [`enqueue`] and
+    /// [`enqueue_source`].
+    ///
+    /// [`enqueue`]: MockDriver::enqueue
+    /// [`enqueue_source`]: MockDriver::enqueue_source
+    pub fn queue(&self) -> &TrackQueue {
+        &self.queue
+    }
+
+    /// Adds an audio [`Input`] to this driver's built-in queue.
+    ///
+    /// Requires the `"builtin-queue"` feature.
+    ///
+    /// [`Input`]: crate::input::Input
+    pub fn enqueue_source(&mut self, source: Input) -> QueueResult<TrackHandle> {
+        let (track, handle) = tracks::create_player(source);
+        self.enqueue(track)?;
+
+        Ok(handle)
+    }
+
+    /// Adds an existing [`Track`] to this driver's built-in queue.
+    ///
+    /// Requires the `"builtin-queue"` feature.
+    ///
+    /// [`Track`]: crate::tracks::Track
+    pub fn enqueue(&mut self, mut track: Track) -> QueueResult<()> {
+        self.queue.add_raw(&mut track, None)?;
+        self.play(track);
+
+        Ok(())
+    }
+}
+
+impl Drop for MockDriver {
+    fn drop(&mut self) {
+        let _ = self.events.send(EventMessage::Poison);
+        let _ = self.mixer.disposer.send(DisposalMessage::Poison);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{EventContext, TrackEvent};
+    use async_trait::async_trait;
+    use std::time::Duration;
+
+    fn pcm_source(frames: usize, value: f32) -> Input {
+        let bytes: Vec<u8> = (0..frames * STEREO_FRAME_SIZE)
+            .flat_map(|_| value.to_le_bytes())
+            .collect();
+
+        Input::float_pcm(true, bytes.into())
+    }
+
+    struct Notify(Sender<()>);
+
+    #[async_trait]
+    impl EventHandler for Notify {
+        async fn act(&self, _ctx: &EventContext<'_>) -> Option<Event> {
+            let _ = self.0.send(());
+
+            None
+        }
+    }
+
+    #[tokio::test]
+    async fn tick_mixes_one_frame_at_a_time() {
+        let mut driver = MockDriver::new(Default::default());
+        let handle = driver.play_source(pcm_source(10, 0.5));
+
+        let frame = driver.tick().await;
+        assert_eq!(frame.len(), STEREO_FRAME_SIZE);
+        assert!(frame.iter().all(|s| (s - 0.5).abs() < 1e-3));
+        assert!(driver.last_frame_kind().is_some());
+
+        for _ in 0..3 {
+            driver.tick().await;
+        }
+
+        let (state, _) = futures::join!(handle.get_info(), driver.tick());
+        assert_eq!(state.unwrap().play_time, Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn end_handlers_fire_within_tick() {
+        let mut driver = MockDriver::new(Default::default());
+        let (tx, rx) = flume::unbounded();
+        driver.add_global_event(Event::Track(TrackEvent::End), Notify(tx));
+        driver.play_source(pcm_source(2, 0.5));
+
+        let mut ended_at = None;
+        for i in 0..5 {
+            driver.tick().await;
+            if ended_at.is_none() && rx.try_recv().is_ok() {
+                ended_at = Some(i);
+            }
+        }
+
+        assert!(ended_at.is_some());
+        assert_eq!(driver.track_count(), 0);
+        assert!(driver.tick().await.iter().all(|s| *s == 0.0));
+    }
+
+    #[tokio::test]
+    async fn stop_all_removes_tracks_while_disconnected() {
+        let mut driver = MockDriver::new(Default::default());
+        let (tx, rx) = flume::unbounded();
+        driver.add_global_event(Event::Track(TrackEvent::End), Notify(tx));
+        driver.play_source(pcm_source(50, 0.5));
+        driver.play_source(pcm_source(50, 0.5));
+        driver.tick().await;
+
+        driver.mixer.conn_active = None;
+        driver.stop_all();
+        assert_eq!(driver.track_count(), 0);
+
+        // Global handlers fire once for all tracks which end together.
+        tokio::time::timeout(Duration::from_secs(1), rx.recv_async())
+            .await
+            .expect("End should fire without a tick.")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn effects_mix_alongside_tracks() {
+        let mut driver = MockDriver::new(Default::default());
+        driver.play_source(pcm_source(10, 0.25));
+        driver.play_effect(pcm_source(2, 0.5));
+        assert_eq!(driver.track_count(), 2);
+
+        assert!(driver.tick().await.iter().all(|s| (s - 0.75).abs() < 1e-3));
+
+        for _ in 0..3 {
+            driver.tick().await;
+        }
+
+        assert_eq!(driver.track_count(), 1);
+        assert!(driver.tick().await.iter().all(|s| (s - 0.25).abs() < 1e-3));
+    }
+
+    #[cfg(feature = "builtin-queue")]
+    #[tokio::test]
+    async fn queue_moves_on_when_track_ends() {
+        let mut driver = MockDriver::new(Default::default());
+        driver.enqueue_source(pcm_source(2, 0.25)).unwrap();
+        driver.enqueue_source(pcm_source(2, 0.5)).unwrap();
+        assert_eq!(driver.queue().len(), 2);
+
+        let mut levels = vec![];
+        for _ in 0..10 {
+            let level = driver.tick().await[0];
+            if level != 0.0 && levels.last() != Some(&level) {
+                levels.push(level);
+            }
+        }
+
+        assert_eq!(levels.len(), 2);
+        assert!((levels[0] - 0.25).abs() < 1e-3);
+        assert!((levels[1] - 0.5).abs() < 1e-3);
+        assert!(driver.queue().is_empty());
+    }
+}
@@ -14,20 +14,52 @@ pub mod bench_internals;
 pub(crate) mod connection;
 mod crypto;
 mod decode_mode;
+mod effect;
+mod encoder_reset;
+mod frame_kind;
+mod mix_timing;
+#[cfg(feature = "test-util")]
+mod mock;
+mod occupancy;
+mod recording;
 pub mod retry;
+mod send_errors;
+mod send_policy;
+mod settings;
+mod shared;
+mod silence_mode;
 pub(crate) mod tasks;
+mod volume_curve;
 
 use connection::error::{Error, Result};
 pub use crypto::CryptoMode;
 pub(crate) use crypto::CryptoState;
 pub use decode_mode::DecodeMode;
+pub(crate) use effect::create_effect;
+pub use encoder_reset::EncoderResetPolicy;
+pub use frame_kind::FrameKind;
+pub use mix_timing::MixTiming;
+pub(crate) use mix_timing::MixTimingStats;
+#[cfg(feature = "test-util")]
+pub use mock::MockDriver;
+pub(crate) use recording::RecordingSink;
+pub use recording::{RecordConfig, RecordFormat, RecordedAudio, RecordedFrame, Recording};
+pub(crate) use send_errors::SendErrorLog;
+pub use send_errors::UdpSendError;
+pub use send_policy::{SendPolicy, MAX_BURST_AHEAD};
+pub use settings::DriverSettings;
+pub(crate) use shared::SharedState;
+pub use silence_mode::SilenceMode;
+pub use volume_curve::VolumeCurve;
 
 #[cfg(feature = "builtin-queue")]
-use crate::tracks::TrackQueue;
+use crate::tracks::{QueueError, QueueResult, TrackQueue};
 use crate::{
+    constants::{DEFAULT_BITRATE, TIMESTEP_LENGTH},
     events::EventData,
+    id::{ChannelId, UserId},
     input::Input,
-    tracks::{self, Track, TrackHandle},
+    tracks::{self, Track, TrackHandle, TrackState},
     Config,
     ConnectionInfo,
     Event,
@@ -41,18 +73,48 @@ use core::{
     task::{Context, Poll},
 };
 use flume::{r#async::RecvFut, SendError, Sender};
-use tasks::message::CoreMessage;
-use tracing::instrument;
+use occupancy::Occupancy;
+use std::{
+    fmt::Debug,
+    sync::{atomic::Ordering, Arc},
+    time::{Duration, Instant},
+};
+use tasks::message::{CoreMessage, TrackEndHandler};
+use tokio::runtime::Handle;
+use tracing::{instrument, warn};
+use uuid::Uuid;
 
 /// The control object for a Discord voice connection, handling connection,
 /// mixing, encoding, en/decryption, and event generation.
 ///
 /// When compiled with the `"builtin-queue"` feature, each driver includes a track queue
 /// as a convenience to prevent the additional overhead of per-guild state management.
+///
+/// ## Use from synchronous code
+/// A driver must be created within a Tokio runtime, but is bound to that runtime
+/// from then on: every method taking `&self` or `&mut self` only messages the driver's
+/// background tasks, and neither blocks nor requires an async context.
+/// Synchronous command handlers on other threads can therefore play or enqueue
+/// tracks directly, using [`Driver::block_on`] to create their [`Input`]s.
+///
+/// When using [`Songbird`], each [`Call`] lives behind a Tokio `Mutex`, which
+/// synchronous code can lock via `blocking_lock`.
+///
+/// [`Input`]: crate::input::Input
+/// [`Songbird`]: crate::Songbird
+/// [`Call`]: crate::Call
 #[derive(Clone, Debug)]
 pub struct Driver {
     config: Config,
     self_mute: bool,
+    bitrate: Bitrate,
+    volume_clamp: (f32, f32),
+    volume_curve: VolumeCurve,
+    idle_timeout: Option<Duration>,
+    occupancy: Occupancy,
+    track_end: Option<TrackEndHandler>,
+    shared: Arc<SharedState>,
+    runtime: Handle,
     sender: Sender<CoreMessage>,
     #[cfg(feature = "builtin-queue")]
     queue: TrackQueue,
@@ -62,31 +124,85 @@ impl Driver {
     /// Creates a new voice driver.
     ///
     /// This will create the core voice tasks in the background.
+    ///
+    /// # Panics
+    /// Panics if called outside of a Tokio runtime.
     #[inline]
     pub fn new(config: Config) -> Self {
-        let sender = Self::start_inner(config.clone());
+        let shared: Arc<SharedState> = Default::default();
+        let runtime = Handle::current();
+        let sender = Self::start_inner(config.clone(), shared.clone(), &runtime);
 
         Driver {
             config,
             self_mute: false,
+            bitrate: DEFAULT_BITRATE,
+            volume_clamp: (f32::NEG_INFINITY, f32::INFINITY),
+            volume_curve: Default::default(),
+            idle_timeout: None,
+            occupancy: Default::default(),
+            track_end: None,
+            shared,
+            runtime,
             sender,
             #[cfg(feature = "builtin-queue")]
             queue: Default::default(),
         }
     }
 
-    fn start_inner(config: Config) -> Sender<CoreMessage> {
+    fn start_inner(
+        config: Config,
+        shared: Arc<SharedState>,
+        runtime: &Handle,
+    ) -> Sender<CoreMessage> {
         let (tx, rx) = flume::unbounded();
 
-        tasks::start(config, rx, tx.clone());
+        tasks::start(config, rx, tx.clone(), shared, runtime);
 
         tx
     }
 
     fn restart_inner(&mut self) {
-        self.sender = Self::start_inner(self.config.clone());
+        self.sender = Self::start_inner(self.config.clone(), self.shared.clone(), &self.runtime);
 
         self.mute(self.self_mute);
+        self.set_bitrate(self.bitrate);
+        self.send(CoreMessage::SetVolumeClamp(
+            self.volume_clamp.0,
+            self.volume_clamp.1,
+        ));
+        self.set_volume_curve(self.volume_curve);
+        self.set_idle_timeout(self.idle_timeout);
+        self.send(CoreMessage::SetPausedAlone(self.occupancy.paused));
+        self.send(CoreMessage::SetTrackEndHandler(self.track_end.clone()));
+    }
+
+    /// Runs a future to completion on the runtime this driver was created in,
+    /// blocking the current thread until it finishes.
+    ///
+    /// This allows synchronous code to create [`Input`]s, such as those returned by
+    /// [`ffmpeg`] or [`ytdl`], which rely upon Tokio's process and IO support.
+    ///
+    /// # Panics
+    /// Panics if called from within an async context, as this would block the runtime.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use songbird::{input, Driver};
+    ///
+    /// fn play_sync(driver: &mut Driver) -> input::error::Result<()> {
+    ///     let source = driver.block_on(input::ffmpeg("./some_file.mp3"))?;
+    ///     driver.play_source(source);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`Input`]: crate::input::Input
+    /// [`ffmpeg`]: crate::input::ffmpeg
+    /// [`ytdl`]: crate::input::ytdl
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
     }
 
     /// Connects to a voice channel using the specified server.
@@ -107,9 +223,41 @@ impl Driver {
     /// Connects to a voice channel using the specified server.
     #[instrument(skip(self))]
     pub(crate) fn raw_connect(&mut self, info: ConnectionInfo, tx: Sender<Result<()>>) {
+        self.occupancy.own = Some((info.user_id, info.channel_id));
+        self.update_paused_alone();
+
         self.send(CoreMessage::ConnectWithResult(info, tx));
     }
 
+    /// Moves this driver onto a new voice connection, such as another voice channel,
+    /// without stopping any tracks.
+    ///
+    /// The new connection is established before the old one is closed: audio continues
+    /// over the old connection until the mixer switches over, and every track keeps its
+    /// position. Should the attempt fail and be retried, the old connection is kept
+    /// until a retry succeeds or the driver gives up. On success, this fires
+    /// [`CoreEvent::DriverMove`] rather than [`CoreEvent::DriverConnect`].
+    ///
+    /// When using a [`Call`], joining another channel already moves the driver in this
+    /// way: this method is intended for drivers managed directly.
+    ///
+    /// [`CoreEvent::DriverMove`]: crate::events::CoreEvent::DriverMove
+    /// [`CoreEvent::DriverConnect`]: crate::events::CoreEvent::DriverConnect
+    /// [`Call`]: crate::Call
+    #[instrument(skip(self))]
+    pub fn move_to(&mut self, info: ConnectionInfo) -> Connect {
+        let (tx, rx) = flume::bounded(1);
+
+        self.occupancy.own = Some((info.user_id, info.channel_id));
+        self.update_paused_alone();
+
+        self.send(CoreMessage::MoveTo(info, tx));
+
+        Connect {
+            inner: rx.into_recv_async(),
+        }
+    }
+
     /// Leaves the current voice channel, disconnecting from it.
     ///
     /// This does *not* forget settings, like whether to be self-deafened or
@@ -150,6 +298,24 @@ impl Driver {
         handle
     }
 
+    /// Plays a short sound to completion alongside any other tracks, such as
+    /// for a soundboard, returning only its UUID.
+    ///
+    /// No [`TrackHandle`] is kept, so the sound cannot be controlled once started.
+    /// The track carries its own end handler, which ensures that the mixer drops
+    /// the track and its source as soon as it ends. The track's UUID is still
+    /// passed to any handler set by [`set_global_track_end`].
+    ///
+    /// [`TrackHandle`]: crate::tracks::TrackHandle
+    /// [`set_global_track_end`]: Driver::set_global_track_end
+    #[instrument(skip(self))]
+    pub fn play_effect(&mut self, source: Input) -> Uuid {
+        let (player, uuid) = create_effect(source);
+        self.send(CoreMessage::AddTrack(player));
+
+        uuid
+    }
+
     /// Plays audio from a source, returning a handle for further control.
     ///
     /// Unlike [`play_source`], this stops all other sources attached
@@ -159,7 +325,7 @@ impl Driver {
     #[instrument(skip(self))]
     pub fn play_only_source(&mut self, source: Input) -> TrackHandle {
         let (player, handle) = super::create_player(source);
-        self.send(CoreMessage::SetTrack(Some(player)));
+        self.send(CoreMessage::SetTrack(Some(player), None));
 
         handle
     }
@@ -179,6 +345,24 @@ impl Driver {
         self.send(CoreMessage::AddTrack(track));
     }
 
+    /// Plays audio from a [`Track`] object, beginning on the mixer tick nearest to `at`.
+    ///
+    /// The track is added immediately, but produces no audio until the first
+    /// frame sent within half a frame (10ms) of `at`. This allows several drivers
+    /// to begin playback simultaneously, or announcements to be scheduled in
+    /// advance. If `at` has already passed, the track begins on the next tick.
+    ///
+    /// While held, the track's position does not advance. If the driver is not
+    /// connected at `at`, the track begins once a connection is established.
+    #[instrument(skip(self))]
+    pub fn play_at(&mut self, mut track: Track, at: Instant) -> TrackHandle {
+        let handle = track.handle.clone();
+        track.start_at = Some(at);
+        self.send(CoreMessage::AddTrack(track));
+
+        handle
+    }
+
     /// Exclusively plays audio from a [`Track`] object.
     ///
     /// This will be one half of the return value of [`create_player`].
@@ -186,13 +370,26 @@ impl Driver {
     /// channel. Like [`play`], however, this allows for direct manipulation of the
     /// [`Track`] object before it is passed over to the voice and mixing contexts.
     ///
+    /// The returned future resolves to handles for every track which was stopped
+    /// to make way for `track`, allowing them to be logged or restored later.
+    /// These tracks are removed immediately, firing their usual [`TrackEvent::End`]
+    /// events. The driver is messaged at the time of the call, so this future need
+    /// only be awaited to retrieve the handles.
+    ///
     /// [`create_player`]: crate::tracks::create_player
     /// [`Track`]: crate::tracks::Track
     /// [`play_only_source`]: Driver::play_only_source
     /// [`play`]: Driver::play
+    /// [`TrackEvent::End`]: crate::events::TrackEvent::End
     #[instrument(skip(self))]
-    pub fn play_only(&mut self, track: Track) {
-        self.send(CoreMessage::SetTrack(Some(track)));
+    pub fn play_only(&mut self, track: Track) -> Displaced {
+        let (tx, rx) = flume::bounded(1);
+
+        self.send(CoreMessage::SetTrack(Some(track), Some(tx)));
+
+        Displaced {
+            inner: rx.into_recv_async(),
+        }
     }
 
     /// Sets the bitrate for encoding Opus packets sent along
@@ -204,13 +401,227 @@ impl Driver {
     /// Alternatively, `Auto` and `Max` remain available.
     #[instrument(skip(self))]
     pub fn set_bitrate(&mut self, bitrate: Bitrate) {
+        self.bitrate = bitrate;
         self.send(CoreMessage::SetBitrate(bitrate))
     }
 
+    /// Returns the bitrate most recently requested for encoding Opus packets.
+    pub fn bitrate(&self) -> Bitrate {
+        self.bitrate
+    }
+
+    /// Clears the state of the Opus encoder used for mixed audio, keeping its settings.
+    ///
+    /// This removes any artifacts carried over from earlier audio, such as after a long
+    /// pause or a seek. See [`Config::encoder_reset`] to do this automatically.
+    ///
+    /// [`Config::encoder_reset`]: crate::Config::encoder_reset
+    #[instrument(skip(self))]
+    pub fn reset_encoder(&mut self) {
+        self.send(CoreMessage::ResetEncoder)
+    }
+
+    /// Limits the volume of every track to lie between `min` and `max` when mixing.
+    ///
+    /// Tracks keep their requested volume, which is restricted to this range only
+    /// as their audio is mixed. By default, volumes are unrestricted: infinite
+    /// bounds may be used to restore this, or to limit only one side of the range.
+    ///
+    /// # Panics
+    /// Panics if `min > max`, or if either bound is NaN.
+    #[instrument(skip(self))]
+    pub fn set_volume_clamp(&mut self, min: f32, max: f32) {
+        assert!(min <= max, "Invalid volume clamp: min must not exceed max.");
+
+        self.volume_clamp = (min, max);
+        self.send(CoreMessage::SetVolumeClamp(min, max))
+    }
+
+    /// Returns the minimum and maximum volume applied to any track when mixing.
+    pub fn volume_clamp(&self) -> (f32, f32) {
+        self.volume_clamp
+    }
+
+    /// Sets how each track's volume is mapped to the gain applied when mixing.
+    ///
+    /// The default is [`VolumeCurve::Linear`].
+    ///
+    /// [`VolumeCurve::Linear`]: VolumeCurve::Linear
+    #[instrument(skip(self))]
+    pub fn set_volume_curve(&mut self, curve: VolumeCurve) {
+        self.volume_curve = curve;
+        self.send(CoreMessage::SetVolumeCurve(curve))
+    }
+
+    /// Returns the curve used to map track volumes to mixer gain.
+    pub fn volume_curve(&self) -> VolumeCurve {
+        self.volume_curve
+    }
+
+    /// Sets how long the driver may go without any playing tracks before it
+    /// leaves the current voice channel, or `None` to remain connected indefinitely.
+    ///
+    /// Paused tracks do not count as playing, and the timer resets whenever a track
+    /// starts or resumes. On timeout, the driver fires [`CoreEvent::IdleDisconnect`]
+    /// followed by [`CoreEvent::DriverDisconnect`].
+    ///
+    /// This only closes the driver's voice connection: when using a [`Call`], handlers
+    /// of [`CoreEvent::IdleDisconnect`] should also call [`Call::leave`] so that the
+    /// bot leaves the channel via the gateway.
+    ///
+    /// Defaults to `None`.
+    ///
+    /// [`CoreEvent::IdleDisconnect`]: crate::events::CoreEvent::IdleDisconnect
+    /// [`CoreEvent::DriverDisconnect`]: crate::events::CoreEvent::DriverDisconnect
+    /// [`Call`]: crate::Call
+    /// [`Call::leave`]: crate::Call::leave
+    #[instrument(skip(self))]
+    pub fn set_idle_timeout(&mut self, timeout: Option<Duration>) {
+        self.idle_timeout = timeout;
+        self.send(CoreMessage::SetIdleTimeout(timeout))
+    }
+
+    /// Returns this driver's idle timeout, if one is set.
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        self.idle_timeout
+    }
+
+    /// Sets whether playback is paused while no other users share the bot's
+    /// voice channel, resuming once a user joins.
+    ///
+    /// While alone, every playing track is paused (firing [`TrackEvent::Pause`]), as
+    /// are any tracks which begin playing. Only tracks paused in this way are resumed
+    /// when a user joins. As paused tracks do not count as playing, an [idle timeout]
+    /// may then disconnect the driver.
+    ///
+    /// Occupancy is learned from voice state updates passed to [`update_voice_state`],
+    /// which [`Songbird`] calls for each update it processes. Users who were already
+    /// in the channel when the bot joined are unknown until they next change voice
+    /// state, and should be reported (e.g., from a cache) via [`update_voice_state`].
+    ///
+    /// Defaults to `false`.
+    ///
+    /// [`TrackEvent::Pause`]: crate::events::TrackEvent::Pause
+    /// [idle timeout]: Driver::set_idle_timeout
+    /// [`update_voice_state`]: Driver::update_voice_state
+    /// [`Songbird`]: crate::Songbird
+    #[instrument(skip(self))]
+    pub fn set_pause_when_alone(&mut self, pause_when_alone: bool) {
+        self.occupancy.pause_when_alone = pause_when_alone;
+        self.update_paused_alone();
+    }
+
+    /// Sets whether other bots are ignored when checking if the driver is alone
+    /// in its voice channel.
+    ///
+    /// Defaults to `true`.
+    ///
+    /// See [`set_pause_when_alone`].
+    ///
+    /// [`set_pause_when_alone`]: Driver::set_pause_when_alone
+    #[instrument(skip(self))]
+    pub fn set_alone_ignores_bots(&mut self, ignore_bots: bool) {
+        self.occupancy.ignore_bots = ignore_bots;
+        self.update_paused_alone();
+    }
+
+    /// Records another user's voice state, where `channel_id` is `None` if they have
+    /// left voice entirely.
+    ///
+    /// This is used to decide whether the bot is alone in its voice channel: see
+    /// [`set_pause_when_alone`]. Updates concerning the bot itself are ignored.
+    ///
+    /// [`set_pause_when_alone`]: Driver::set_pause_when_alone
+    #[instrument(skip(self))]
+    pub fn update_voice_state<U, C>(&mut self, user_id: U, channel_id: Option<C>, is_bot: bool)
+    where
+        U: Into<UserId> + Debug,
+        C: Into<ChannelId> + Debug,
+    {
+        self.occupancy
+            .update(user_id.into(), channel_id.map(Into::into), is_bot);
+        self.update_paused_alone();
+    }
+
+    /// Returns whether no other (counted) users are known to share the bot's voice channel.
+    ///
+    /// This is `false` until the driver has been asked to connect to a known channel.
+    pub fn is_alone(&self) -> bool {
+        self.occupancy.alone()
+    }
+
+    fn update_paused_alone(&mut self) {
+        let paused = self.occupancy.should_pause();
+
+        if paused != self.occupancy.paused {
+            self.occupancy.paused = paused;
+            self.send(CoreMessage::SetPausedAlone(paused));
+        }
+    }
+
+    /// Returns this driver's current audio preferences, suitable for persisting.
+    ///
+    /// Infinite volume bounds are stored as `None`.
+    pub fn settings(&self) -> DriverSettings {
+        let (min, max) = self.volume_clamp;
+
+        DriverSettings {
+            bitrate: self.bitrate,
+            volume_curve: self.volume_curve,
+            min_volume: Some(min).filter(|v| v.is_finite()),
+            max_volume: Some(max).filter(|v| v.is_finite()),
+            idle_timeout: self.idle_timeout,
+        }
+    }
+
+    /// Applies a set of audio preferences, such as those previously returned by [`settings`].
+    ///
+    /// This may be called at any time, including while connected and playing: each
+    /// setting takes effect from the next mixed frame, as though set individually.
+    /// Invalid volume bounds (i.e., where the minimum exceeds the maximum) are ignored,
+    /// leaving the current clamp unchanged.
+    ///
+    /// [`settings`]: Driver::settings
+    #[instrument(skip(self))]
+    pub fn apply_settings(&mut self, settings: DriverSettings) {
+        self.set_bitrate(settings.bitrate);
+        self.set_volume_curve(settings.volume_curve);
+
+        match settings.clamp_bounds() {
+            Some((min, max)) => self.set_volume_clamp(min, max),
+            None => warn!("Ignoring invalid volume clamp in driver settings."),
+        }
+
+        self.set_idle_timeout(settings.idle_timeout);
+    }
+
     /// Stops playing audio from all sources, if any are set.
+    ///
+    /// This is equivalent to [`stop_all`].
+    ///
+    /// [`stop_all`]: Driver::stop_all
     #[instrument(skip(self))]
     pub fn stop(&mut self) {
-        self.send(CoreMessage::SetTrack(None))
+        self.send(CoreMessage::SetTrack(None, None))
+    }
+
+    /// Stops every track attached to this driver, without leaving the voice channel.
+    ///
+    /// Unlike [`leave`], this keeps the voice connection open. Every track is moved
+    /// to [`PlayMode::Stop`] and removed by the mixer straight away (even while
+    /// disconnected), firing its usual [`TrackEvent::End`] events, after which the
+    /// mixer sends its trailing silent frames as it would when any track finishes.
+    ///
+    /// This does not clear the built-in queue, which will begin playing its next
+    /// track once the current one ends: use [`TrackQueue::stop`] to do so.
+    ///
+    /// [`leave`]: Driver::leave
+    /// [`PlayMode::Stop`]: crate::tracks::PlayMode::Stop
+    /// [`TrackEvent::End`]: crate::events::TrackEvent::End
+    /// [`TrackQueue::stop`]: crate::tracks::TrackQueue::stop
+    #[instrument(skip(self))]
+    pub fn stop_all(&mut self) {
+        self.send(CoreMessage::SetTrack(None, None))
     }
 
     /// Sets the configuration for this driver (and parent `Call`, if applicable).
@@ -226,6 +637,156 @@ impl Driver {
         &self.config
     }
 
+    /// Returns how long audio read from a track by the mixer takes to be due
+    /// for playback.
+    ///
+    /// The mixer builds and encodes each frame one frame length ahead of its
+    /// send deadline, and [`SendPolicy::BurstAhead`] sends frames earlier still.
+    /// If [`Config::mix_timing`] is enabled and the mixer has recently taken
+    /// longer than a frame to mix, this slowest mix time is used in place of
+    /// the frame length. This does not include any network or client-side
+    /// jitter buffering.
+    ///
+    /// Latency-sensitive applications may use this to offset their own timing.
+    ///
+    /// [`SendPolicy::BurstAhead`]: SendPolicy::BurstAhead
+    /// [`Config::mix_timing`]: crate::Config::mix_timing
+    pub fn output_latency(&self) -> Duration {
+        let mix_time = self.mix_timing().map(|t| t.max).unwrap_or_default();
+
+        TIMESTEP_LENGTH.max(mix_time) + self.config.send_policy.lead()
+    }
+
+    /// Returns how long the mixer has spent mixing each frame over the
+    /// last few seconds, alongside its current track count.
+    ///
+    /// This requires [`Config::mix_timing`] to be enabled, and returns `None`
+    /// otherwise or if no frames have yet been mixed. Mix times regularly
+    /// approaching the 20ms frame length will cause audio to stutter.
+    ///
+    /// [`Config::mix_timing`]: crate::Config::mix_timing
+    pub fn mix_timing(&self) -> Option<MixTiming> {
+        self.shared.mix_timing.lock().summary()
+    }
+
+    /// Returns the [RTP SSRC] assigned to this driver by the voice server,
+    /// or `None` if the driver is not connected.
+    ///
+    /// All audio sent by this driver uses this SSRC, which allows received
+    /// packets and events to be matched against the bot's own audio.
+    ///
+    /// [RTP SSRC]: https://tools.ietf.org/html/rfc3550#section-3
+    pub fn ssrc(&self) -> Option<u32> {
+        *self.shared.ssrc.lock()
+    }
+
+    /// Returns the name of the encryption mode negotiated with the voice server,
+    /// such as `"xsalsa20_poly1305_lite"`, or `None` if the driver is not connected.
+    ///
+    /// The mode is chosen from [`Config::crypto_mode`] during the handshake.
+    ///
+    /// [`Config::crypto_mode`]: crate::Config::crypto_mode
+    pub fn encryption_mode(&self) -> Option<&'static str> {
+        self.shared
+            .crypto_mode
+            .lock()
+            .map(CryptoMode::to_request_str)
+    }
+
+    /// Returns the most recent errors encountered while sending packets over UDP,
+    /// oldest first.
+    ///
+    /// Consecutive identical errors are coalesced into a single entry, and only
+    /// the last 16 distinct errors are kept. Each new error also fires
+    /// [`CoreEvent::SendError`].
+    ///
+    /// [`CoreEvent::SendError`]: crate::events::CoreEvent::SendError
+    pub fn recent_send_errors(&self) -> Vec<UdpSendError> {
+        self.shared.send_errors.lock().recent()
+    }
+
+    /// Returns how long this driver has been connected to its current voice channel,
+    /// or `None` if it is not connected.
+    ///
+    /// This is reset by each new connection (e.g., via [`connect`] or [`move_to`]),
+    /// but is kept across reconnections made by the driver.
+    ///
+    /// [`connect`]: Driver::connect
+    /// [`move_to`]: Driver::move_to
+    pub fn uptime(&self) -> Option<Duration> {
+        self.shared
+            .connected_since
+            .lock()
+            .map(|since| since.elapsed())
+    }
+
+    /// Returns how many times the driver has successfully reconnected since
+    /// it last joined a voice channel.
+    ///
+    /// As with [`uptime`], this is reset by each new connection.
+    ///
+    /// [`uptime`]: Driver::uptime
+    pub fn reconnect_count(&self) -> u32 {
+        self.shared.reconnect_count.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of tracks currently held by the mixer, whether
+    /// playing or paused.
+    ///
+    /// This is updated by the mixer as it processes each frame (or message,
+    /// while disconnected), so it may briefly lag behind recent calls to
+    /// [`play`] or [`stop`].
+    ///
+    /// [`play`]: Driver::play
+    /// [`stop`]: Driver::stop
+    pub fn track_count(&self) -> usize {
+        self.shared.track_count.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of tracks held by the mixer which are currently playing.
+    ///
+    /// As with [`track_count`], this may briefly lag behind recent commands.
+    ///
+    /// [`track_count`]: Driver::track_count
+    pub fn playing_count(&self) -> usize {
+        self.shared.playing_count.load(Ordering::Relaxed)
+    }
+
+    /// Requests the UUID and state of every track currently held by the mixer.
+    ///
+    /// This can be used to inspect what is playing without keeping track
+    /// of each [`TrackHandle`]. The driver is messaged at the time of the call,
+    /// and tracks are listed in the mixer's internal order.
+    ///
+    /// [`TrackHandle`]: crate::tracks::TrackHandle
+    #[instrument(skip(self))]
+    pub fn active_tracks(&mut self) -> ActiveTracks {
+        let (tx, rx) = flume::bounded(1);
+
+        self.send(CoreMessage::RequestActiveTracks(tx));
+
+        ActiveTracks {
+            inner: rx.into_recv_async(),
+        }
+    }
+
+    /// Starts a local recording of this driver's mixed output, encoded independently
+    /// of the audio sent to Discord.
+    ///
+    /// This allows audio to be archived at a higher quality than a voice channel's
+    /// bitrate allows. Each recording has its own encoder, and receives a frame for
+    /// every frame mixed while connected: see [`Recording`] for details.
+    ///
+    /// [`Recording`]: Recording
+    #[instrument(skip(self))]
+    pub fn add_recording(&mut self, config: RecordConfig) -> Recording {
+        let (tx, rx) = flume::unbounded();
+
+        self.send(CoreMessage::AddRecording(config, tx));
+
+        Recording::new(rx)
+    }
+
     /// Attach a global event handler to an audio context. Global events may receive
     /// any [`EventContext`].
     ///
@@ -245,6 +806,38 @@ impl Driver {
         self.send(CoreMessage::AddEvent(EventData::new(event, action)));
     }
 
+    /// Sets a function to be called whenever any track played by this driver ends,
+    /// with that track's UUID and final state, replacing any existing function.
+    ///
+    /// Tracks end once they finish, are stopped, or fail: the state's [`playing`]
+    /// field tells these apart. This fires in addition to any [`TrackEvent::End`]
+    /// handlers, and is unaffected by [`remove_all_global_events`], which makes
+    /// it well suited to centralised logging or analytics.
+    ///
+    /// As with other event handlers, this function is called from the driver's
+    /// event task: it **must not** block or perform costly work.
+    ///
+    /// [`playing`]: crate::tracks::TrackState::playing
+    /// [`TrackEvent::End`]: crate::events::TrackEvent::End
+    /// [`remove_all_global_events`]: Driver::remove_all_global_events
+    #[instrument(skip(self, handler))]
+    pub fn set_global_track_end<F>(&mut self, handler: F)
+    where
+        F: Fn(Uuid, TrackState) + Send + Sync + 'static,
+    {
+        self.track_end = Some(TrackEndHandler(Arc::new(handler)));
+        self.send(CoreMessage::SetTrackEndHandler(self.track_end.clone()));
+    }
+
+    /// Removes the function set by [`set_global_track_end`], if any.
+    ///
+    /// [`set_global_track_end`]: Driver::set_global_track_end
+    #[instrument(skip(self))]
+    pub fn clear_global_track_end(&mut self) {
+        self.track_end = None;
+        self.send(CoreMessage::SetTrackEndHandler(None));
+    }
+
     /// Removes all global event handlers from an audio context.
     #[instrument(skip(self))]
     pub fn remove_all_global_events(&mut self) {
@@ -280,22 +873,80 @@ impl Driver {
     ///
     /// Requires the `"builtin-queue"` feature.
     ///
+    /// Fails with [`QueueError::Full`] if the queue has reached its maximum length.
+    ///
     /// [`Input`]: crate::input::Input
-    pub fn enqueue_source(&mut self, source: Input) -> TrackHandle {
+    /// [`QueueError::Full`]: crate::tracks::QueueError::Full
+    pub fn enqueue_source(&mut self, source: Input) -> QueueResult<TrackHandle> {
         let (track, handle) = tracks::create_player(source);
-        self.enqueue(track);
+        self.enqueue(track)?;
 
-        handle
+        Ok(handle)
+    }
+
+    /// Adds several audio [`Input`]s to this driver's built-in queue, in order.
+    ///
+    /// Requires the `"builtin-queue"` feature.
+    ///
+    /// Returns handles for every input which was queued. Inputs which could not be
+    /// added, such as once the queue has reached its maximum length, are returned
+    /// alongside their [`QueueError`] rather than halting the whole batch.
+    ///
+    /// [`Input`]: crate::input::Input
+    /// [`QueueError`]: crate::tracks::QueueError
+    pub fn enqueue_all(
+        &mut self,
+        sources: impl IntoIterator<Item = Input>,
+    ) -> (Vec<TrackHandle>, Vec<(Input, QueueError)>) {
+        let mut handles = vec![];
+        let mut failed = vec![];
+
+        for source in sources {
+            let (mut track, handle) = tracks::create_player(source);
+
+            match self.queue.add_raw(&mut track, None) {
+                Ok(()) => {
+                    self.play(track);
+                    handles.push(handle);
+                },
+                Err(e) => failed.push((track.source, e)),
+            }
+        }
+
+        (handles, failed)
     }
 
     /// Adds an existing [`Track`] to this driver's built-in queue.
     ///
     /// Requires the `"builtin-queue"` feature.
     ///
+    /// Fails with [`QueueError::Full`] if the queue has reached its maximum length.
+    ///
     /// [`Track`]: crate::tracks::Track
-    pub fn enqueue(&mut self, mut track: Track) {
-        self.queue.add_raw(&mut track);
+    /// [`QueueError::Full`]: crate::tracks::QueueError::Full
+    pub fn enqueue(&mut self, mut track: Track) -> QueueResult<()> {
+        self.queue.add_raw(&mut track, None)?;
         self.play(track);
+
+        Ok(())
+    }
+
+    /// Adds an existing [`Track`] to this driver's built-in queue, on behalf of a user.
+    ///
+    /// Requires the `"builtin-queue"` feature.
+    ///
+    /// Fails with [`QueueError::Full`] if the queue has reached its maximum length, or
+    /// with [`QueueError::Rejected`] if refused by the queue's [`EnqueueGuard`].
+    ///
+    /// [`Track`]: crate::tracks::Track
+    /// [`QueueError::Full`]: crate::tracks::QueueError::Full
+    /// [`QueueError::Rejected`]: crate::tracks::QueueError::Rejected
+    /// [`EnqueueGuard`]: crate::tracks::EnqueueGuard
+    pub fn enqueue_as(&mut self, mut track: Track, user_id: impl Into<UserId>) -> QueueResult<()> {
+        self.queue.add_raw(&mut track, Some(user_id.into()))?;
+        self.play(track);
+
+        Ok(())
     }
 }
 
@@ -333,3 +984,74 @@ impl Future for Connect {
         }
     }
 }
+
+/// Future for a call to [`Driver::play_only`].
+///
+/// This future resolves to the handles of all tracks stopped by the call;
+/// the driver is messaged at the time of the call.
+///
+/// [`Driver::play_only`]: Driver::play_only
+#[must_use = "displaced track handles are lost unless this future is awaited"]
+pub struct Displaced {
+    inner: RecvFut<'static, Vec<TrackHandle>>,
+}
+
+/// Future for a call to [`Driver::active_tracks`].
+///
+/// This future resolves to the UUID and state of each track held by the mixer.
+///
+/// [`Driver::active_tracks`]: Driver::active_tracks
+pub struct ActiveTracks {
+    inner: RecvFut<'static, Vec<(Uuid, TrackState)>>,
+}
+
+impl Future for ActiveTracks {
+    type Output = Vec<(Uuid, TrackState)>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match Pin::new(&mut self.inner).poll(cx) {
+            Poll::Ready(r) => Poll::Ready(r.unwrap_or_default()),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl Future for Displaced {
+    type Output = Vec<TrackHandle>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match Pin::new(&mut self.inner).poll(cx) {
+            Poll::Ready(r) => Poll::Ready(r.unwrap_or_default()),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_latency_follows_send_policy_and_mix_time() {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let _guard = rt.enter();
+
+        let mut driver = Driver::new(Default::default());
+        assert_eq!(driver.output_latency(), TIMESTEP_LENGTH);
+
+        driver.set_config(Config::default().send_policy(SendPolicy::BurstAhead(2)));
+        assert_eq!(driver.output_latency(), TIMESTEP_LENGTH * 3);
+
+        driver
+            .shared
+            .mix_timing
+            .lock()
+            .record(Duration::from_millis(30), 1);
+        assert_eq!(
+            driver.output_latency(),
+            Duration::from_millis(30) + TIMESTEP_LENGTH * 2
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use crate::id::{ChannelId, UserId};
+use std::collections::HashMap;
+
+/// Record of which users occupy the driver's voice channel, used to pause
+/// playback while the bot is alone.
+#[derive(Clone, Debug)]
+pub(crate) struct Occupancy {
+    pub(crate) pause_when_alone: bool,
+    pub(crate) ignore_bots: bool,
+    /// Whether the mixer has last been told to hold playback paused.
+    pub(crate) paused: bool,
+    /// The bot's own user and channel, once connected.
+    pub(crate) own: Option<(UserId, Option<ChannelId>)>,
+    /// Each other user's voice channel, and whether they are a bot.
+    users: HashMap<UserId, (ChannelId, bool)>,
+}
+
+impl Occupancy {
+    /// Updates a user's voice state, where `channel_id` is `None` if they have left voice.
+    pub(crate) fn update(&mut self, user_id: UserId, channel_id: Option<ChannelId>, is_bot: bool) {
+        if self.own.map_or(false, |(own_id, _)| own_id == user_id) {
+            return;
+        }
+
+        match channel_id {
+            Some(channel_id) => {
+                self.users.insert(user_id, (channel_id, is_bot));
+            },
+            None => {
+                self.users.remove(&user_id);
+            },
+        }
+    }
+
+    /// Returns whether the bot is in a voice channel which holds no (counted) users.
+    pub(crate) fn alone(&self) -> bool {
+        match self.own {
+            Some((_, Some(own_channel))) => !self
+                .users
+                .values()
+                .any(|(channel, is_bot)| *channel == own_channel && !(self.ignore_bots && *is_bot)),
+            _ => false,
+        }
+    }
+
+    /// Returns whether playback should currently be held paused.
+    pub(crate) fn should_pause(&self) -> bool {
+        self.pause_when_alone && self.alone()
+    }
+}
+
+impl Default for Occupancy {
+    fn default() -> Self {
+        Self {
+            pause_when_alone: false,
+            ignore_bots: true,
+            paused: false,
+            own: None,
+            users: HashMap::new(),
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use super::FrameKind;
+use crate::constants::{STEREO_FRAME_SIZE, VOICE_PACKET_MAX};
+use audiopus::{
+    coder::Encoder as OpusEncoder,
+    Application,
+    Bitrate,
+    Channels,
+    Result as OpusResult,
+    SampleRate,
+};
+use flume::{r#async::RecvStream, Receiver, Sender, TryRecvError};
+
+/// Encoding used for each frame of a [`Recording`].
+///
+/// [`Recording`]: Recording
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum RecordFormat {
+    /// Raw, interleaved stereo `f32` samples at 48kHz.
+    Pcm,
+    /// Opus frames, encoded at the given bitrate by an encoder separate from
+    /// the one used to send audio to Discord.
+    Opus(Bitrate),
+}
+
+/// Configuration for a local recording of a driver's mixed output.
+///
+/// Created with [`RecordConfig::new`], and passed to [`Driver::add_recording`].
+///
+/// [`Driver::add_recording`]: super::Driver::add_recording
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct RecordConfig {
+    /// Encoding used for each recorded frame.
+    ///
+    /// Defaults to [`RecordFormat::Opus`] at the encoder's maximum bitrate.
+    ///
+    /// [`RecordFormat::Opus`]: RecordFormat::Opus
+    pub format: RecordFormat,
+}
+
+impl RecordConfig {
+    /// Creates a recording configuration with the given frame encoding.
+    pub fn new(format: RecordFormat) -> Self {
+        Self { format }
+    }
+}
+
+impl Default for RecordConfig {
+    fn default() -> Self {
+        Self::new(RecordFormat::Opus(Bitrate::Max))
+    }
+}
+
+/// A single 20ms frame of a driver's mixed output.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct RecordedFrame {
+    /// What was sent to Discord for this frame.
+    ///
+    /// This reflects muting, even though the recorded audio does not.
+    pub kind: FrameKind,
+    /// The mixed audio of this frame.
+    pub audio: RecordedAudio,
+}
+
+/// Mixed audio of a [`RecordedFrame`], encoded as set by [`RecordConfig::format`].
+///
+/// [`RecordedFrame`]: RecordedFrame
+/// [`RecordConfig::format`]: RecordConfig::format
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum RecordedAudio {
+    /// Interleaved stereo samples, as configured by [`RecordFormat::Pcm`].
+    ///
+    /// [`RecordFormat::Pcm`]: RecordFormat::Pcm
+    Pcm(Vec<f32>),
+    /// An Opus packet, as configured by [`RecordFormat::Opus`].
+    ///
+    /// [`RecordFormat::Opus`]: RecordFormat::Opus
+    Opus(Vec<u8>),
+}
+
+/// Receiver for frames of a driver's mixed output, created by [`Driver::add_recording`].
+///
+/// One frame is produced every 20ms while the driver is connected, including
+/// silent frames. Each frame is recorded before muting is applied, and Opus passthrough
+/// is disabled while any recording is active.
+///
+/// Frames are buffered until received. The recording stops once this (and every clone)
+/// is dropped, or when the driver's mixer is restarted.
+///
+/// [`Driver::add_recording`]: super::Driver::add_recording
+#[derive(Clone, Debug)]
+pub struct Recording {
+    rx: Receiver<RecordedFrame>,
+}
+
+impl Recording {
+    pub(crate) fn new(rx: Receiver<RecordedFrame>) -> Self {
+        Self { rx }
+    }
+
+    /// Waits for the next recorded frame, returning `None` once the recording has stopped.
+    pub async fn recv(&self) -> Option<RecordedFrame> {
+        self.rx.recv_async().await.ok()
+    }
+
+    /// Returns the next recorded frame if one is ready, without waiting.
+    pub fn try_recv(&self) -> Option<RecordedFrame> {
+        match self.rx.try_recv() {
+            Ok(frame) => Some(frame),
+            Err(TryRecvError::Empty) | Err(TryRecvError::Disconnected) => None,
+        }
+    }
+
+    /// Converts this recording into a stream of frames, ending once the recording stops.
+    pub fn into_stream(self) -> RecvStream<'static, RecordedFrame> {
+        self.rx.into_stream()
+    }
+}
+
+/// Mixer-side end of a [`Recording`], holding any required encoder.
+pub(crate) struct RecordingSink {
+    encoder: Option<OpusEncoder>,
+    tx: Sender<RecordedFrame>,
+}
+
+impl RecordingSink {
+    pub(crate) fn new(config: RecordConfig, tx: Sender<RecordedFrame>) -> OpusResult<Self> {
+        let encoder = match config.format {
+            RecordFormat::Pcm => None,
+            RecordFormat::Opus(bitrate) => {
+                let mut encoder =
+                    OpusEncoder::new(SampleRate::Hz48000, Channels::Stereo, Application::Audio)?;
+                encoder.set_bitrate(bitrate)?;
+
+                Some(encoder)
+            },
+        };
+
+        Ok(Self { encoder, tx })
+    }
+
+    /// Encodes and sends one mixed frame, returning `false` if the recording has stopped.
+    pub(crate) fn write(&mut self, frame: &[f32; STEREO_FRAME_SIZE], kind: FrameKind) -> bool {
+        let audio = if let Some(encoder) = &mut self.encoder {
+            let mut packet = vec![0u8; VOICE_PACKET_MAX];
+            match encoder.encode_float(&frame[..], &mut packet[..]) {
+                Ok(len) => {
+                    packet.truncate(len);
+                    RecordedAudio::Opus(packet)
+                },
+                Err(_) => return false,
+            }
+        } else {
+            RecordedAudio::Pcm(frame.to_vec())
+        };
+
+        self.tx.send(RecordedFrame { kind, audio }).is_ok()
+    }
+}
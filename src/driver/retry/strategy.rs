@@ -10,6 +10,9 @@ pub enum Strategy {
     /// Exponential backoff waiting strategy, where the duration between
     /// attempts (approximately) doubles each time.
     Backoff(ExponentialBackoff),
+    /// Linear backoff waiting strategy, where the duration between
+    /// attempts grows by a fixed step each time.
+    Linear(LinearBackoff),
 }
 
 impl Strategy {
@@ -17,6 +20,7 @@ impl Strategy {
         match self {
             Self::Every(t) => *t,
             Self::Backoff(exp) => exp.retry_in(last_wait),
+            Self::Linear(lin) => lin.retry_in(last_wait),
         }
     }
 }
@@ -82,3 +86,88 @@ impl ExponentialBackoff {
         target_time
     }
 }
+
+/// Linear backoff waiting strategy.
+///
+/// Each attempt waits for the last delay plus `base`, clamped
+/// to a max value.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LinearBackoff {
+    /// Amount of time to wait before the first retry, and to add
+    /// to the delay for each subsequent retry.
+    ///
+    /// *Defaults to 1s.*
+    pub base: Duration,
+    /// Maximum amount of time to wait between retries.
+    ///
+    /// This will be clamped to `>=` base.
+    ///
+    /// *Defaults to 10s.*
+    pub max: Duration,
+}
+
+impl Default for LinearBackoff {
+    fn default() -> Self {
+        Self {
+            base: Duration::from_secs(1),
+            max: Duration::from_secs(10),
+        }
+    }
+}
+
+impl LinearBackoff {
+    pub(crate) fn retry_in(&self, last_wait: Option<Duration>) -> Duration {
+        let target_time = last_wait.map(|t| t + self.base).unwrap_or(self.base);
+        let safe_max = self.max.max(self.base);
+
+        target_time.min(safe_max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn linear_backoff_grows_by_base() {
+        let lin = LinearBackoff {
+            base: Duration::from_secs(2),
+            max: Duration::from_secs(60),
+        };
+
+        let first = lin.retry_in(None);
+        let second = lin.retry_in(Some(first));
+        let third = lin.retry_in(Some(second));
+
+        assert_eq!([first, second, third], [2, 4, 6].map(Duration::from_secs));
+    }
+
+    #[test]
+    fn linear_backoff_is_clamped_to_max() {
+        let lin = LinearBackoff {
+            base: Duration::from_secs(3),
+            max: Duration::from_secs(5),
+        };
+
+        assert_eq!(
+            lin.retry_in(Some(Duration::from_secs(3))),
+            Duration::from_secs(5)
+        );
+        assert_eq!(
+            lin.retry_in(Some(Duration::from_secs(5))),
+            Duration::from_secs(5)
+        );
+
+        // A max below base is raised to base.
+        let lin = LinearBackoff {
+            base: Duration::from_secs(3),
+            max: Duration::from_secs(1),
+        };
+
+        assert_eq!(lin.retry_in(None), Duration::from_secs(3));
+        assert_eq!(
+            lin.retry_in(Some(Duration::from_secs(3))),
+            Duration::from_secs(3)
+        );
+    }
+}
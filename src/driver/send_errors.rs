@@ -0,0 +1,65 @@
+use std::{
+    collections::VecDeque,
+    io::{Error as IoError, ErrorKind as IoErrorKind},
+    time::Instant,
+};
+
+/// Number of distinct errors retained by [`Driver::recent_send_errors`].
+///
+/// [`Driver::recent_send_errors`]: super::Driver::recent_send_errors
+const MAX_ENTRIES: usize = 16;
+
+/// A failure to send a voice or keepalive packet over UDP.
+///
+/// Consecutive identical errors are coalesced into one entry.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct UdpSendError {
+    /// The category of IO error encountered.
+    pub kind: IoErrorKind,
+    /// The error's description, as given by the OS.
+    pub message: String,
+    /// The number of consecutive times this error occurred.
+    pub count: usize,
+    /// When this error most recently occurred.
+    pub last_seen: Instant,
+}
+
+/// Ring buffer of recent UDP send errors, shared between a driver and its tasks.
+#[derive(Debug, Default)]
+pub(crate) struct SendErrorLog {
+    entries: VecDeque<UdpSendError>,
+}
+
+impl SendErrorLog {
+    /// Stores an error, returning the new entry if it differs from the latest one.
+    pub(crate) fn record(&mut self, error: &IoError) -> Option<&UdpSendError> {
+        let now = Instant::now();
+        let message = error.to_string();
+
+        if let Some(last) = self.entries.back_mut() {
+            if last.kind == error.kind() && last.message == message {
+                last.count += 1;
+                last.last_seen = now;
+                return None;
+            }
+        }
+
+        if self.entries.len() == MAX_ENTRIES {
+            self.entries.pop_front();
+        }
+
+        self.entries.push_back(UdpSendError {
+            kind: error.kind(),
+            message,
+            count: 1,
+            last_seen: now,
+        });
+
+        self.entries.back()
+    }
+
+    pub(crate) fn recent(&self) -> Vec<UdpSendError> {
+        self.entries.iter().cloned().collect()
+    }
+}
@@ -0,0 +1,40 @@
+use crate::constants::TIMESTEP_LENGTH;
+use std::time::Duration;
+
+/// Largest number of frames which [`SendPolicy::BurstAhead`] may send ahead of real time.
+///
+/// Larger bursts risk having packets throttled or dropped by Discord.
+pub const MAX_BURST_AHEAD: u8 = 5;
+
+/// Pacing strategy used by the mixer when sending voice packets.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum SendPolicy {
+    /// Each packet is sent as its frame falls due, one every 20ms.
+    ///
+    /// This is the default.
+    Steady,
+    /// Packets may be sent up to the given number of frames ahead of real time,
+    /// absorbing scheduling jitter on heavily loaded hosts.
+    ///
+    /// Values above [`MAX_BURST_AHEAD`] are treated as [`MAX_BURST_AHEAD`].
+    /// Audio changes (e.g., pausing or volume adjustments) will take effect
+    /// correspondingly later.
+    BurstAhead(u8),
+}
+
+impl SendPolicy {
+    /// Returns how far ahead of real time the mixer may send packets under this policy.
+    pub fn lead(self) -> Duration {
+        match self {
+            SendPolicy::Steady => Duration::ZERO,
+            SendPolicy::BurstAhead(n) => TIMESTEP_LENGTH * u32::from(n.min(MAX_BURST_AHEAD)),
+        }
+    }
+}
+
+impl Default for SendPolicy {
+    fn default() -> Self {
+        SendPolicy::Steady
+    }
+}
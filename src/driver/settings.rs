@@ -0,0 +1,146 @@
+use super::{Bitrate, VolumeCurve};
+use crate::constants::DEFAULT_BITRATE;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Persistable audio preferences of a [`Driver`], such as those chosen for a guild.
+///
+/// Settings are obtained from [`Driver::settings`], and may be stored (i.e., as JSON)
+/// and later restored using [`Driver::apply_settings`]. Fields absent from stored
+/// settings take their default values.
+///
+/// Per-track loudness normalisation is configured on each track via
+/// [`Track::set_auto_gain`], rather than here.
+///
+/// [`Driver`]: super::Driver
+/// [`Driver::settings`]: super::Driver::settings
+/// [`Driver::apply_settings`]: super::Driver::apply_settings
+/// [`Track::set_auto_gain`]: crate::tracks::Track::set_auto_gain
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+#[non_exhaustive]
+pub struct DriverSettings {
+    /// Bitrate used to encode Opus packets.
+    #[serde(with = "bitrate_repr")]
+    pub bitrate: Bitrate,
+    /// Curve used to map track volumes to mixer gain.
+    pub volume_curve: VolumeCurve,
+    /// Lowest volume applied to any track when mixing, if bounded.
+    pub min_volume: Option<f32>,
+    /// Highest volume applied to any track when mixing, if bounded.
+    pub max_volume: Option<f32>,
+    /// Time without playing tracks after which the driver leaves its channel, if any.
+    pub idle_timeout: Option<Duration>,
+}
+
+impl DriverSettings {
+    /// Creates a set of settings matching those of a newly created driver.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the bitrate used to encode Opus packets.
+    pub fn bitrate(mut self, bitrate: Bitrate) -> Self {
+        self.bitrate = bitrate;
+        self
+    }
+
+    /// Sets the curve used to map track volumes to mixer gain.
+    pub fn volume_curve(mut self, volume_curve: VolumeCurve) -> Self {
+        self.volume_curve = volume_curve;
+        self
+    }
+
+    /// Sets the bounds placed on each track's volume when mixing.
+    pub fn volume_clamp(mut self, min: Option<f32>, max: Option<f32>) -> Self {
+        self.min_volume = min;
+        self.max_volume = max;
+        self
+    }
+
+    /// Sets the driver's idle timeout.
+    pub fn idle_timeout(mut self, idle_timeout: Option<Duration>) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// Converts the stored volume bounds into those used by the driver, if valid.
+    pub(crate) fn clamp_bounds(&self) -> Option<(f32, f32)> {
+        let min = self.min_volume.unwrap_or(f32::NEG_INFINITY);
+        let max = self.max_volume.unwrap_or(f32::INFINITY);
+
+        if min <= max {
+            Some((min, max))
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for DriverSettings {
+    fn default() -> Self {
+        Self {
+            bitrate: DEFAULT_BITRATE,
+            volume_curve: Default::default(),
+            min_volume: None,
+            max_volume: None,
+            idle_timeout: None,
+        }
+    }
+}
+
+/// (De)serialization of [`Bitrate`], which has no `serde` support of its own.
+mod bitrate_repr {
+    use super::Bitrate;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    enum Repr {
+        Auto,
+        Max,
+        BitsPerSecond(i32),
+    }
+
+    pub fn serialize<S: Serializer>(bitrate: &Bitrate, serializer: S) -> Result<S::Ok, S::Error> {
+        match *bitrate {
+            Bitrate::Auto => Repr::Auto,
+            Bitrate::Max => Repr::Max,
+            Bitrate::BitsPerSecond(bits) => Repr::BitsPerSecond(bits),
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Bitrate, D::Error> {
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Auto => Bitrate::Auto,
+            Repr::Max => Bitrate::Max,
+            Repr::BitsPerSecond(bits) => Bitrate::BitsPerSecond(bits),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_json_round_trip() {
+        let settings = DriverSettings::new()
+            .bitrate(Bitrate::BitsPerSecond(96_000))
+            .volume_curve(VolumeCurve::Perceptual)
+            .volume_clamp(None, Some(1.5))
+            .idle_timeout(Some(Duration::from_secs(300)));
+
+        let json = serde_json::to_string(&settings).unwrap();
+        assert_eq!(
+            serde_json::from_str::<DriverSettings>(&json).unwrap(),
+            settings
+        );
+
+        assert_eq!(
+            serde_json::from_str::<DriverSettings>("{}").unwrap(),
+            DriverSettings::default()
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use super::{CryptoMode, MixTimingStats, SendErrorLog};
+use parking_lot::Mutex;
+use std::{
+    sync::atomic::{AtomicU32, AtomicUsize, Ordering},
+    time::Instant,
+};
+
+/// Live state written by a driver's background tasks, which the [`Driver`]
+/// can read without contacting them.
+///
+/// [`Driver`]: super::Driver
+#[derive(Debug, Default)]
+pub(crate) struct SharedState {
+    /// Recent per-frame mix times, if measured by the mixer.
+    pub(crate) mix_timing: Mutex<MixTimingStats>,
+    /// When the mixer last produced a frame, if it is actively mixing and
+    /// the watchdog is enabled.
+    pub(crate) last_frame: Mutex<Option<Instant>>,
+    /// SSRC assigned to the current voice connection.
+    pub(crate) ssrc: Mutex<Option<u32>>,
+    /// Encryption mode negotiated for the current voice connection.
+    pub(crate) crypto_mode: Mutex<Option<CryptoMode>>,
+    /// Recent errors returned when sending UDP packets.
+    pub(crate) send_errors: Mutex<SendErrorLog>,
+    /// Number of tracks currently held by the mixer.
+    pub(crate) track_count: AtomicUsize,
+    /// Number of tracks held by the mixer which are currently playing.
+    pub(crate) playing_count: AtomicUsize,
+    /// When the current voice session was joined, if connected.
+    ///
+    /// This is kept across reconnections within the session.
+    pub(crate) connected_since: Mutex<Option<Instant>>,
+    /// Number of successful reconnections within the current voice session.
+    pub(crate) reconnect_count: AtomicU32,
+}
+
+impl SharedState {
+    /// Records that a fresh voice session was joined.
+    pub(crate) fn start_session(&self) {
+        *self.connected_since.lock() = Some(Instant::now());
+        self.reconnect_count.store(0, Ordering::Relaxed);
+    }
+}
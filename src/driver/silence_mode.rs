@@ -0,0 +1,34 @@
+/// What the mixer sends while its tracks play silent audio.
+///
+/// This does not affect periods where no tracks are playing (or all are paused),
+/// when the mixer stops sending packets entirely after a short run of silent frames.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum SilenceMode {
+    /// Silent audio is encoded and sent like any other audio.
+    ///
+    /// This is the default.
+    Silence,
+    /// The Opus encoder's discontinuous transmission (DTX) is enabled: packets
+    /// holding only silence or background noise are replaced by an occasional
+    /// comfort noise update, and are otherwise not sent.
+    ///
+    /// This reduces bandwidth for bots which idle while playing quiet or silent
+    /// sources. Discord's clients treat the gaps as they do DTX from other users,
+    /// playing comfort noise without artifacts. Other receivers which do not
+    /// expect DTX may treat the gaps as packet loss. Tracks sent via Opus passthrough
+    /// are not re-encoded, and are unaffected.
+    Dtx,
+}
+
+impl SilenceMode {
+    pub(crate) fn uses_dtx(self) -> bool {
+        self == Self::Dtx
+    }
+}
+
+impl Default for SilenceMode {
+    fn default() -> Self {
+        Self::Silence
+    }
+}
@@ -1,7 +1,7 @@
 use super::message::*;
 use crate::{
     events::{EventStore, GlobalEvents, TrackEvent},
-    tracks::{TrackHandle, TrackState},
+    tracks::{PlayMode, TrackHandle, TrackState},
 };
 use flume::Receiver;
 use tracing::{debug, info, instrument, trace};
@@ -13,6 +13,7 @@ pub(crate) async fn runner(_interconnect: Interconnect, evt_rx: Receiver<EventMe
     let mut events: Vec<EventStore> = vec![];
     let mut states: Vec<TrackState> = vec![];
     let mut handles: Vec<TrackHandle> = vec![];
+    let mut track_end: Option<TrackEndHandler> = None;
 
     loop {
         use EventMessage::*;
@@ -33,6 +34,37 @@ pub(crate) async fn runner(_interconnect: Interconnect, evt_rx: Receiver<EventMe
 
                 event_store.add_event(data, state.position);
             },
+            Ok(RemoveTrackEvent(i, id)) => {
+                info!("Removing event from track {}.", i);
+
+                let event_store = events
+                    .get_mut(i)
+                    .expect("Event thread was given an illegal store index for RemoveTrackEvent.");
+
+                event_store.remove_event(id);
+            },
+            Ok(AddTrackCue(i, id, at, name)) => {
+                info!("Adding cue {:?} to track {}.", name, i);
+
+                let event_store = events
+                    .get_mut(i)
+                    .expect("Event thread was given an illegal store index for AddTrackCue.");
+
+                event_store.insert_cue(id, at, name);
+            },
+            Ok(ListScheduled(i, mut scheduled, tx)) => {
+                let event_store = events
+                    .get(i)
+                    .expect("Event thread was given an illegal store index for ListScheduled.");
+
+                scheduled.extend(event_store.scheduled());
+                let _ = tx.send(scheduled);
+            },
+            Ok(FireTrackEvent(i, evt)) => {
+                trace!("Firing track event {:?} for track {}.", evt, i);
+
+                global.fire_track_event(evt, i);
+            },
             Ok(FireCoreEvent(ctx)) => {
                 let ctx = ctx.to_user_context();
                 let evt = ctx
@@ -46,6 +78,12 @@ pub(crate) async fn runner(_interconnect: Interconnect, evt_rx: Receiver<EventMe
             Ok(RemoveGlobalEvents) => {
                 global.remove_handlers();
             },
+            Ok(SetHandlerConcurrency(concurrency)) => {
+                global.concurrency = concurrency;
+            },
+            Ok(SetTrackEndHandler(handler)) => {
+                track_end = handler;
+            },
             Ok(AddTrack(store, state, handle)) => {
                 events.push(store);
                 states.push(state);
@@ -69,14 +107,39 @@ pub(crate) async fn runner(_interconnect: Interconnect, evt_rx: Receiver<EventMe
                 match change {
                     Mode(mode) => {
                         let old = state.playing;
-                        state.playing = mode;
+                        state.set_mode(mode);
                         if old != mode {
                             global.fire_track_event(mode.as_track_event(), i);
+
+                            if mode == PlayMode::Errored {
+                                global.fire_track_event(TrackEvent::End, i);
+                            }
+
+                            if mode.is_done() && !old.is_done() {
+                                if let Some(TrackEndHandler(f)) = &track_end {
+                                    f(handles[i].uuid(), *state);
+                                }
+                            }
                         }
                     },
                     Volume(vol) => {
                         state.volume = vol;
                     },
+                    Pitch(semitones) => {
+                        state.pitch = semitones;
+                    },
+                    Speed(speed) => {
+                        state.speed = speed;
+                    },
+                    Route(route) => {
+                        state.route = route;
+                    },
+                    Pan(pan) => {
+                        state.pan = pan;
+                    },
+                    Muted(muted) => {
+                        state.muted = muted;
+                    },
                     Position(pos) => {
                         // Currently, only Tick should fire time events.
                         state.position = pos;
@@ -111,6 +174,15 @@ pub(crate) async fn runner(_interconnect: Interconnect, evt_rx: Receiver<EventMe
                 // NOTE: this should fire saved up blocks of state change evts.
                 global.tick(&mut events, &mut states, &mut handles).await;
             },
+            Ok(FireAwaiting) => {
+                // Fires the events of tracks removed outside of a mix cycle.
+                global
+                    .fire_awaiting(&mut events, &mut states, &mut handles)
+                    .await;
+            },
+            Ok(Flush(tx)) => {
+                let _ = tx.send(());
+            },
             Err(_) | Ok(Poison) => {
                 break;
             },
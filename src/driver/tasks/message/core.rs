@@ -1,29 +1,47 @@
 #![allow(missing_docs)]
 
+use super::TrackEndHandler;
 use crate::{
-    driver::{connection::error::Error, Bitrate, Config},
-    events::{context_data::DisconnectReason, EventData},
-    tracks::Track,
+    driver::{connection::error::Error, Bitrate, Config, RecordConfig, RecordedFrame, VolumeCurve},
+    events::{
+        context_data::{DisconnectReason, MixerStallData},
+        EventData,
+    },
+    tracks::{Track, TrackHandle, TrackState},
     ConnectionInfo,
 };
 use flume::Sender;
+use std::{io::Error as IoError, time::Duration};
+use uuid::Uuid;
 
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
 pub enum CoreMessage {
     ConnectWithResult(ConnectionInfo, Sender<Result<(), Error>>),
+    MoveTo(ConnectionInfo, Sender<Result<(), Error>>),
     RetryConnect(usize),
     SignalWsClosure(usize, ConnectionInfo, Option<DisconnectReason>),
     Disconnect,
-    SetTrack(Option<Track>),
+    SetTrack(Option<Track>, Option<Sender<Vec<TrackHandle>>>),
     AddTrack(Track),
+    RequestActiveTracks(Sender<Vec<(Uuid, TrackState)>>),
+    AddRecording(RecordConfig, Sender<RecordedFrame>),
     SetBitrate(Bitrate),
+    ResetEncoder,
+    SetVolumeClamp(f32, f32),
+    SetVolumeCurve(VolumeCurve),
+    SetIdleTimeout(Option<Duration>),
+    SetPausedAlone(bool),
     AddEvent(EventData),
     RemoveGlobalEvents,
+    SetTrackEndHandler(Option<TrackEndHandler>),
     SetConfig(Config),
     Mute(bool),
     Reconnect,
     FullReconnect,
     RebuildInterconnect,
+    MixerStall(MixerStallData),
+    IdleTimeout(Duration),
+    UdpSendError(IoError),
     Poison,
 }
@@ -1,24 +1,48 @@
 #![allow(missing_docs)]
 
 use crate::{
-    events::{CoreContext, EventData, EventStore},
-    tracks::{LoopState, PlayMode, TrackHandle, TrackState},
+    events::{CoreContext, EventData, EventId, EventStore, TrackEvent},
+    tracks::{LoopState, PlayMode, Route, ScheduledAction, TrackHandle, TrackState},
 };
-use std::time::Duration;
+use flume::Sender;
+use std::{fmt, sync::Arc, time::Duration};
+use uuid::Uuid;
+
+/// Callback type used by [`Driver::set_global_track_end`].
+///
+/// [`Driver::set_global_track_end`]: crate::driver::Driver::set_global_track_end
+pub type TrackEndFn = dyn Fn(Uuid, TrackState) + Send + Sync + 'static;
+
+#[derive(Clone)]
+pub struct TrackEndHandler(pub Arc<TrackEndFn>);
+
+impl fmt::Debug for TrackEndHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TrackEndHandler([function])")
+    }
+}
 
 pub enum EventMessage {
     // Event related.
     // Track events should fire off the back of state changes.
     AddGlobalEvent(EventData),
     AddTrackEvent(usize, EventData),
+    RemoveTrackEvent(usize, EventId),
+    AddTrackCue(usize, EventId, Duration, String),
+    ListScheduled(usize, Vec<ScheduledAction>, Sender<Vec<ScheduledAction>>),
+    FireTrackEvent(usize, TrackEvent),
     FireCoreEvent(CoreContext),
     RemoveGlobalEvents,
+    SetHandlerConcurrency(usize),
+    SetTrackEndHandler(Option<TrackEndHandler>),
 
     AddTrack(EventStore, TrackState, TrackHandle),
     ChangeState(usize, TrackStateChange),
     RemoveTrack(usize),
     RemoveAllTracks,
     Tick,
+    FireAwaiting,
+    Flush(Sender<()>),
 
     Poison,
 }
@@ -27,6 +51,11 @@ pub enum EventMessage {
 pub enum TrackStateChange {
     Mode(PlayMode),
     Volume(f32),
+    Pitch(f32),
+    Speed(f32),
+    Route(Route),
+    Pan(f32),
+    Muted(bool),
     Position(Duration),
     // Bool indicates user-set.
     Loops(LoopState, bool),
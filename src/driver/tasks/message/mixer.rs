@@ -3,10 +3,12 @@
 use super::{Interconnect, UdpRxMessage, UdpTxMessage, WsMessage};
 
 use crate::{
-    driver::{Bitrate, Config, CryptoState},
-    tracks::Track,
+    driver::{Bitrate, Config, CryptoState, RecordConfig, RecordedFrame, VolumeCurve},
+    tracks::{Track, TrackHandle, TrackState},
 };
 use flume::Sender;
+use std::time::Duration;
+use uuid::Uuid;
 use xsalsa20poly1305::XSalsa20Poly1305 as Cipher;
 
 pub struct MixerConnection {
@@ -25,11 +27,17 @@ impl Drop for MixerConnection {
 
 pub enum MixerMessage {
     AddTrack(Track),
-    SetTrack(Option<Track>),
+    SetTrack(Option<Track>, Option<Sender<Vec<TrackHandle>>>),
+    RequestActiveTracks(Sender<Vec<(Uuid, TrackState)>>),
+    AddRecording(RecordConfig, Sender<RecordedFrame>),
 
     SetBitrate(Bitrate),
     SetConfig(Config),
     SetMute(bool),
+    SetVolumeClamp(f32, f32),
+    SetVolumeCurve(VolumeCurve),
+    SetIdleTimeout(Option<Duration>),
+    SetPausedAlone(bool),
 
     SetConn(MixerConnection, u32),
     Ws(Option<Sender<WsMessage>>),
@@ -37,6 +45,7 @@ pub enum MixerMessage {
 
     ReplaceInterconnect(Interconnect),
     RebuildEncoder,
+    ResetEncoder,
 
     Poison,
 }
@@ -1,13 +1,15 @@
 use super::{disposal, error::Result, message::*};
 use crate::{
     constants::*,
-    tracks::{PlayMode, Track},
+    driver::{FrameKind, RecordingSink, SharedState, VolumeCurve},
+    events::TrackEvent,
+    tracks::{PlayMode, QueueRepeat, Track},
     Config,
 };
 use audiopus::{
-    coder::Encoder as OpusEncoder,
+    coder::{Encoder as OpusEncoder, GenericCtl},
     softclip::SoftClip,
-    Application as CodingMode,
+    Bandwidth,
     Bitrate,
     Channels,
 };
@@ -17,38 +19,64 @@ use discortp::{
 };
 use flume::{Receiver, Sender, TryRecvError};
 use rand::random;
-use std::{convert::TryInto, time::Instant};
+use std::{
+    convert::TryInto,
+    iter,
+    mem,
+    sync::{atomic::Ordering, Arc},
+    time::{Duration, Instant},
+};
 use tokio::runtime::Handle;
 use tracing::{debug, error, instrument};
 use xsalsa20poly1305::TAG_SIZE;
 
 pub struct Mixer {
     pub async_handle: Handle,
+    pub bandwidth: Bandwidth,
     pub bitrate: Bitrate,
     pub config: Config,
     pub conn_active: Option<MixerConnection>,
     pub deadline: Instant,
     pub disposer: Sender<DisposalMessage>,
     pub encoder: OpusEncoder,
+    pub frame_sink: Option<Sender<(FrameKind, Vec<f32>)>>,
+    pub idle_since: Option<Instant>,
+    pub idle_timeout: Option<Duration>,
     pub interconnect: Interconnect,
+    pub last_encode: Option<Instant>,
+    pub(crate) shared: Arc<SharedState>,
     pub mix_rx: Receiver<MixerMessage>,
     pub muted: bool,
     pub packet: [u8; VOICE_PACKET_MAX],
+    pub paused_alone: bool,
     pub prevent_events: bool,
+    pub recordings: Vec<RecordingSink>,
     pub silence_frames: u8,
     pub skip_sleep: bool,
     pub soft_clip: SoftClip,
     pub tracks: Vec<Track>,
+    pub volume_clamp: (f32, f32),
+    pub volume_curve: VolumeCurve,
     pub ws: Option<Sender<WsMessage>>,
 }
 
-fn new_encoder(bitrate: Bitrate) -> Result<OpusEncoder> {
-    let mut encoder = OpusEncoder::new(SAMPLE_RATE, Channels::Stereo, CodingMode::Audio)?;
+fn new_encoder(bitrate: Bitrate, config: &Config) -> Result<OpusEncoder> {
+    let mut encoder = OpusEncoder::new(SAMPLE_RATE, Channels::Stereo, config.opus_application)?;
     encoder.set_bitrate(bitrate)?;
+    encoder.set_vbr(config.opus_vbr)?;
+    encoder.set_vbr_constraint(config.opus_vbr_constraint)?;
+    encoder.set_dtx(config.silence_mode.uses_dtx())?;
 
     Ok(encoder)
 }
 
+/// Converts the configured seek fade into a count of interleaved stereo samples.
+fn seek_fade_len(config: &Config) -> usize {
+    let mono = (config.seek_crossfade.as_secs_f64() * SAMPLE_RATE_RAW as f64) as usize;
+
+    2 * mono.min(MONO_FRAME_SIZE)
+}
+
 impl Mixer {
     pub fn new(
         mix_rx: Receiver<MixerMessage>,
@@ -57,7 +85,7 @@ impl Mixer {
         config: Config,
     ) -> Self {
         let bitrate = DEFAULT_BITRATE;
-        let encoder = new_encoder(bitrate)
+        let encoder = new_encoder(bitrate, &config)
             .expect("Failed to create encoder in mixing thread with known-good values.");
         let soft_clip = SoftClip::new(Channels::Stereo);
 
@@ -80,21 +108,31 @@ impl Mixer {
 
         Self {
             async_handle,
+            bandwidth: Bandwidth::Auto,
             bitrate,
             config,
             conn_active: None,
             deadline: Instant::now(),
             disposer,
             encoder,
+            frame_sink: None,
+            idle_since: None,
+            idle_timeout: None,
             interconnect,
+            last_encode: None,
+            shared: Default::default(),
             mix_rx,
             muted: false,
             packet,
+            paused_alone: false,
             prevent_events: false,
+            recordings: Vec::new(),
             silence_frames: 0,
             skip_sleep: false,
             soft_clip,
             tracks,
+            volume_clamp: (f32::NEG_INFINITY, f32::INFINITY),
+            volume_curve: Default::default(),
             ws: None,
         }
     }
@@ -135,8 +173,15 @@ impl Mixer {
 
                         debug!("Mixer thread cycle: {:?}", e);
                     }
+
+                    if self.config.mixer_watchdog.is_some() {
+                        *self.shared.last_frame.lock() = Some(Instant::now());
+                    }
                 }
             } else {
+                // Waiting for a connection is not a stall.
+                *self.shared.last_frame.lock() = None;
+
                 match self.mix_rx.recv() {
                     Ok(m) => {
                         let (events, conn, should_exit) = self.handle_message(m);
@@ -153,6 +198,12 @@ impl Mixer {
                 }
             }
 
+            let playing = self.publish_track_counts();
+
+            if self.conn_active.is_some() {
+                self.check_idle(playing);
+            }
+
             // event failure? rebuild interconnect.
             // ws or udp failure? full connect
             // (soft reconnect is covered by the ws task.)
@@ -183,10 +234,52 @@ impl Mixer {
                 }
             }
         }
+
+        // Any held tracks are dropped alongside the mixer.
+        self.shared.track_count.store(0, Ordering::Relaxed);
+        self.shared.playing_count.store(0, Ordering::Relaxed);
+    }
+
+    fn publish_track_counts(&self) -> usize {
+        let playing = self
+            .tracks
+            .iter()
+            .filter(|track| track.playing == PlayMode::Play)
+            .count();
+
+        self.shared
+            .track_count
+            .store(self.tracks.len(), Ordering::Relaxed);
+        self.shared.playing_count.store(playing, Ordering::Relaxed);
+
+        playing
+    }
+
+    fn check_idle(&mut self, playing: usize) {
+        let timeout = match self.idle_timeout {
+            Some(timeout) => timeout,
+            None => return,
+        };
+
+        if playing > 0 {
+            self.idle_since = None;
+            return;
+        }
+
+        let idle_for = self.idle_since.get_or_insert_with(Instant::now).elapsed();
+
+        if idle_for >= timeout {
+            // The core task will drop this connection in response.
+            self.idle_since = None;
+            let _ = self
+                .interconnect
+                .core
+                .send(CoreMessage::IdleTimeout(idle_for));
+        }
     }
 
     #[inline]
-    fn handle_message(&mut self, msg: MixerMessage) -> (bool, bool, bool) {
+    pub(crate) fn handle_message(&mut self, msg: MixerMessage) -> (bool, bool, bool) {
         let mut events_failure = false;
         let mut conn_failure = false;
         let mut should_exit = false;
@@ -196,21 +289,44 @@ impl Mixer {
         let error = match msg {
             AddTrack(mut t) => {
                 t.source.prep_with_handle(self.async_handle.clone());
-                self.add_track(t)
+                self.add_track(t);
+                Ok(())
             },
-            SetTrack(t) => {
-                self.tracks.clear();
+            SetTrack(t, tx) => {
+                let displaced = self
+                    .tracks
+                    .iter_mut()
+                    .filter(|track| !track.playing.is_done())
+                    .map(|track| {
+                        track.stop();
+                        track.handle.clone()
+                    })
+                    .collect();
+
+                if let Some(tx) = tx {
+                    let _ = tx.send(displaced);
+                }
+
+                // Stopped tracks are removed (and fire their events) now, rather
+                // than waiting for the next mix cycle.
+                let to_remove = self.remove_done_tracks();
+                let mut out = Ok(());
 
-                let mut out = self.fire_event(EventMessage::RemoveAllTracks);
+                for event in iter::once(EventMessage::FireAwaiting)
+                    .chain(to_remove.into_iter().map(EventMessage::RemoveTrack))
+                {
+                    if let Err(e) = self.fire_event(event) {
+                        out = Err(e);
+                        break;
+                    }
+                }
 
                 if let Some(mut t) = t {
                     t.source.prep_with_handle(self.async_handle.clone());
 
                     // Do this unconditionally: this affects local state infallibly,
                     // with the event installation being the remote part.
-                    if let Err(e) = self.add_track(t) {
-                        out = Err(e);
-                    }
+                    self.add_track(t);
                 }
 
                 out
@@ -226,8 +342,48 @@ impl Mixer {
                 self.muted = m;
                 Ok(())
             },
+            SetVolumeClamp(min, max) => {
+                self.volume_clamp = (min, max);
+                Ok(())
+            },
+            SetVolumeCurve(curve) => {
+                self.volume_curve = curve;
+                Ok(())
+            },
+            RequestActiveTracks(tx) => {
+                let _ = tx.send(
+                    self.tracks
+                        .iter()
+                        .filter(|track| !track.playing.is_done())
+                        .map(|track| (track.uuid(), track.state()))
+                        .collect(),
+                );
+                Ok(())
+            },
+            AddRecording(record_config, tx) => {
+                match RecordingSink::new(record_config, tx) {
+                    Ok(sink) => self.recordings.push(sink),
+                    Err(e) => debug!("Failed to start recording: {:?}", e),
+                }
+                Ok(())
+            },
+            SetIdleTimeout(timeout) => {
+                self.idle_timeout = timeout;
+                self.idle_since = None;
+                Ok(())
+            },
+            SetPausedAlone(paused) => {
+                self.paused_alone = paused;
+
+                if !paused {
+                    self.resume_paused_alone();
+                }
+
+                Ok(())
+            },
             SetConn(conn, ssrc) => {
                 self.conn_active = Some(conn);
+                self.idle_since = None;
                 let mut rtp = MutableRtpPacket::new(&mut self.packet[..]).expect(
                     "Too few bytes in self.packet for RTP header.\
                         (Blame: VOICE_PACKET_MAX?)",
@@ -258,8 +414,19 @@ impl Mixer {
                 self.rebuild_tracks()
             },
             SetConfig(new_config) => {
+                if new_config.silence_mode != self.config.silence_mode {
+                    if let Err(e) = self.encoder.set_dtx(new_config.silence_mode.uses_dtx()) {
+                        error!("Failed to set encoder DTX {:?}", e);
+                    }
+                }
+
                 self.config = new_config.clone();
 
+                let seek_fade = seek_fade_len(&self.config);
+                for track in self.tracks.iter_mut() {
+                    track.seek_fade = seek_fade;
+                }
+
                 if self.tracks.capacity() < self.config.preallocated_tracks {
                     self.tracks
                         .reserve(self.config.preallocated_tracks - self.tracks.len());
@@ -274,18 +441,28 @@ impl Mixer {
 
                 Ok(())
             },
-            RebuildEncoder => match new_encoder(self.bitrate) {
-                Ok(encoder) => {
-                    self.encoder = encoder;
-                    Ok(())
-                },
-                Err(e) => {
-                    error!("Failed to rebuild encoder. Resetting bitrate. {:?}", e);
-                    self.bitrate = DEFAULT_BITRATE;
-                    self.encoder = new_encoder(self.bitrate)
-                        .expect("Failed fallback rebuild of OpusEncoder with safe inputs.");
-                    Ok(())
-                },
+            RebuildEncoder => {
+                self.bandwidth = Bandwidth::Auto;
+
+                match new_encoder(self.bitrate, &self.config) {
+                    Ok(encoder) => {
+                        self.encoder = encoder;
+                        Ok(())
+                    },
+                    Err(e) => {
+                        error!("Failed to rebuild encoder. Resetting bitrate. {:?}", e);
+                        self.bitrate = DEFAULT_BITRATE;
+                        self.encoder = new_encoder(self.bitrate, &self.config)
+                            .expect("Failed fallback rebuild of OpusEncoder with safe inputs.");
+                        Ok(())
+                    },
+                }
+            },
+            ResetEncoder => {
+                if let Err(e) = self.encoder.reset_state() {
+                    error!("Failed to reset encoder {:?}", e);
+                }
+                Ok(())
             },
             Ws(new_ws_handle) => {
                 self.ws = new_ws_handle;
@@ -317,19 +494,45 @@ impl Mixer {
         }
     }
 
+    /// Resumes every track which was paused for being alone in the voice channel.
+    fn resume_paused_alone(&mut self) {
+        let resumed: Vec<usize> = self
+            .tracks
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(i, track)| {
+                let resume = mem::take(&mut track.paused_alone) && track.playing == PlayMode::Pause;
+                resume.then(|| {
+                    track.play();
+                    i
+                })
+            })
+            .collect();
+
+        for i in resumed {
+            // If the event thread has died, then we'll detect that on the next tick.
+            let _ = self.fire_event(EventMessage::ChangeState(
+                i,
+                TrackStateChange::Mode(PlayMode::Play),
+            ));
+        }
+    }
+
     #[inline]
-    fn add_track(&mut self, mut track: Track) -> Result<()> {
+    pub(crate) fn add_track(&mut self, mut track: Track) {
+        track.seek_fade = seek_fade_len(&self.config);
         let evts = track.events.take().unwrap_or_default();
         let state = track.state();
         let handle = track.handle.clone();
 
         self.tracks.push(track);
 
-        self.interconnect
+        // If the event thread has died, then we'll detect that on the next tick:
+        // its rebuild will then reinstall this track.
+        let _ = self
+            .interconnect
             .events
-            .send(EventMessage::AddTrack(evts, state, handle))?;
-
-        Ok(())
+            .send(EventMessage::AddTrack(evts, state, handle));
     }
 
     // rebuilds the event thread's view of each track, in event of a full rebuild.
@@ -349,7 +552,7 @@ impl Mixer {
     }
 
     #[inline]
-    fn audio_commands_events(&mut self) -> Result<()> {
+    pub(crate) fn audio_commands_events(&mut self) -> Result<()> {
         // Apply user commands.
         for (i, track) in self.tracks.iter_mut().enumerate() {
             // This causes fallible event system changes,
@@ -357,8 +560,36 @@ impl Mixer {
             // detect that on the tick later.
             // Changes to play state etc. MUST all be handled.
             track.process_commands(i, &self.interconnect);
+
+            if self.paused_alone && track.playing == PlayMode::Play {
+                track.pause();
+                track.paused_alone = true;
+
+                let _ = self.interconnect.events.send(EventMessage::ChangeState(
+                    i,
+                    TrackStateChange::Mode(PlayMode::Pause),
+                ));
+            }
+        }
+
+        let to_remove = self.remove_done_tracks();
+
+        // Tick
+        self.fire_event(EventMessage::Tick)?;
+
+        // Then do removals.
+        for i in &to_remove[..] {
+            self.fire_event(EventMessage::RemoveTrack(*i))?;
         }
 
+        Ok(())
+    }
+
+    /// Removes all finished tracks, reporting their final states to the event thread.
+    ///
+    /// Returns the removed indices, which the event thread must be told to remove
+    /// (in order) once it has fired the tracks' events.
+    fn remove_done_tracks(&mut self) -> Vec<usize> {
         // TODO: do without vec?
         let mut i = 0;
         let mut to_remove = Vec::with_capacity(self.tracks.len());
@@ -372,25 +603,18 @@ impl Mixer {
                 let p_state = track.playing();
                 let to_drop = self.tracks.swap_remove(i);
                 to_remove.push(i);
-                self.fire_event(EventMessage::ChangeState(
+                // A dead event thread is detected by the message which follows.
+                let _ = self.fire_event(EventMessage::ChangeState(
                     i,
                     TrackStateChange::Mode(p_state),
-                ))?;
+                ));
                 let _ = self.disposer.send(DisposalMessage::Track(to_drop));
             } else {
                 i += 1;
             }
         }
 
-        // Tick
-        self.fire_event(EventMessage::Tick)?;
-
-        // Then do removals.
-        for i in &to_remove[..] {
-            self.fire_event(EventMessage::RemoveTrack(*i))?;
-        }
-
-        Ok(())
+        to_remove
     }
 
     #[inline]
@@ -399,12 +623,24 @@ impl Mixer {
             return;
         }
 
-        std::thread::sleep(self.deadline.saturating_duration_since(Instant::now()));
+        // Bursting ahead only shortens the sleep: frames are still produced
+        // at the same average rate.
+        let wake = self
+            .deadline
+            .checked_sub(self.config.send_policy.lead())
+            .unwrap_or(self.deadline);
+
+        std::thread::sleep(wake.saturating_duration_since(Instant::now()));
         self.deadline += TIMESTEP_LENGTH;
     }
 
     pub fn cycle(&mut self) -> Result<()> {
         let mut mix_buffer = [0f32; STEREO_FRAME_SIZE];
+        let mix_start = if self.config.mix_timing {
+            Some(Instant::now())
+        } else {
+            None
+        };
 
         // Walk over all the audio files, combining into one audio frame according
         // to volume, play state, etc.
@@ -423,15 +659,56 @@ impl Mixer {
                 &mut self.tracks,
                 &self.interconnect,
                 self.prevent_events,
+                self.frame_sink.is_none() && self.recordings.is_empty(),
+                self.volume_clamp,
+                self.volume_curve,
+                self.config.empty_input_error,
+                self.deadline,
             )
         };
 
         self.soft_clip.apply((&mut mix_buffer[..]).try_into()?)?;
+        self.apply_bandwidth_hint()?;
+
+        if let Some(start) = mix_start {
+            self.shared
+                .mix_timing
+                .lock()
+                .record(start.elapsed(), self.tracks.len());
+        }
 
         if self.muted {
             mix_len = MixType::MixedPcm(0);
         }
 
+        let kind = if mix_len != MixType::MixedPcm(0) {
+            FrameKind::Audio
+        } else if self.silence_frames > 0 {
+            FrameKind::Silence
+        } else {
+            FrameKind::Idle
+        };
+
+        // `Vec::retain_mut` is newer than our MSRV.
+        let mut i = 0;
+        while i < self.recordings.len() {
+            if self.recordings[i].write(&mix_buffer, kind) {
+                i += 1;
+            } else {
+                self.recordings.remove(i);
+            }
+        }
+
+        if let Some(sink) = &self.frame_sink {
+            let frame = if mix_len == MixType::MixedPcm(0) {
+                vec![0.0; STEREO_FRAME_SIZE]
+            } else {
+                mix_buffer.to_vec()
+            };
+
+            let _ = sink.send((kind, frame));
+        }
+
         if mix_len == MixType::MixedPcm(0) {
             if self.silence_frames > 0 {
                 self.silence_frames -= 1;
@@ -476,6 +753,26 @@ impl Mixer {
         Ok(())
     }
 
+    /// Applies the bandwidth hint of the sole playing track, if there is one.
+    fn apply_bandwidth_hint(&mut self) -> Result<()> {
+        let mut playing = self
+            .tracks
+            .iter()
+            .filter(|track| track.playing == PlayMode::Play);
+
+        let hint = match (playing.next(), playing.next()) {
+            (Some(track), None) => track.bandwidth,
+            _ => Bandwidth::Auto,
+        };
+
+        if hint != self.bandwidth {
+            self.encoder.set_bandwidth(hint)?;
+            self.bandwidth = hint;
+        }
+
+        Ok(())
+    }
+
     fn set_bitrate(&mut self, bitrate: Bitrate) -> Result<()> {
         self.encoder.set_bitrate(bitrate).map_err(Into::into)
     }
@@ -499,11 +796,28 @@ impl Mixer {
             let payload_len = match mix_len {
                 MixType::Passthrough(opus_len) => opus_len,
                 MixType::MixedPcm(_samples) => {
+                    let now = Instant::now();
+                    if let Some(last) = self.last_encode.replace(now) {
+                        if self.config.encoder_reset.should_reset(now - last) {
+                            debug!("Resetting encoder after {:?} without audio.", now - last);
+                            self.encoder.reset_state()?;
+                        }
+                    }
+
                     let total_payload_space = payload.len() - crypto_mode.payload_suffix_len();
-                    self.encoder.encode_float(
+                    let len = self.encoder.encode_float(
                         &buffer[..STEREO_FRAME_SIZE],
                         &mut payload[TAG_SIZE..total_payload_space],
-                    )?
+                    )?;
+
+                    // Opus signals frames which DTX has elided with a payload of
+                    // at most 2 bytes: these are skipped, leaving a gap in timestamps.
+                    if len <= 2 && self.config.silence_mode.uses_dtx() {
+                        rtp.set_timestamp(rtp.get_timestamp() + MONO_FRAME_SIZE as u32);
+                        return Ok(());
+                    }
+
+                    len
                 },
             };
 
@@ -544,40 +858,110 @@ enum MixType {
 }
 
 #[inline]
+#[allow(clippy::too_many_arguments)]
 fn mix_tracks<'a>(
     opus_frame: &'a mut [u8],
     mix_buffer: &mut [f32; STEREO_FRAME_SIZE],
     tracks: &mut Vec<Track>,
     interconnect: &Interconnect,
     prevent_events: bool,
+    allow_passthrough: bool,
+    volume_clamp: (f32, f32),
+    volume_curve: VolumeCurve,
+    empty_input_error: bool,
+    frame_time: Instant,
 ) -> MixType {
     let mut len = 0;
 
     // Opus frame passthrough.
-    // This requires that we have only one track, who has volume 1.0, and an
-    // Opus codec type.
-    let do_passthrough = tracks.len() == 1 && {
-        let track = &tracks[0];
-        (track.volume - 1.0).abs() < f32::EPSILON && track.source.supports_passthrough()
-    };
+    // This requires that we have only one track, who has volume 1.0, no
+    // other processing, and an Opus codec type.
+    let do_passthrough = allow_passthrough
+        && tracks.len() == 1
+        && tracks[0].supports_passthrough()
+        && (volume_clamp.0..=volume_clamp.1).contains(&1.0);
 
     for (i, track) in tracks.iter_mut().enumerate() {
-        let vol = track.volume;
-        let stream = &mut track.source;
+        track
+            .handle
+            .set_passthrough(do_passthrough && track.playing == PlayMode::Play);
 
         if track.playing != PlayMode::Play {
             continue;
         }
 
+        // Scheduled tracks begin on the frame sent nearest to their start time.
+        if let Some(at) = track.start_at {
+            if at > frame_time + TIMESTEP_LENGTH / 2 {
+                continue;
+            }
+
+            track.start_at = None;
+        }
+
+        track.first_mix_at.get_or_insert_with(Instant::now);
+
+        let was_muted = track.muted;
+        let was_ramping = track.volume_ramp.is_some();
+        let old_volume = track.volume;
         let (temp_len, opus_len) = if do_passthrough {
             (0, track.source.read_opus_frame(opus_frame).ok())
+        } else if let Some(len) = track.mix(mix_buffer, volume_clamp, volume_curve) {
+            (len, None)
         } else {
-            (stream.mix(mix_buffer, vol), None)
+            track.fail();
+            continue;
         };
 
-        len = len.max(temp_len);
+        if !track.muted {
+            len = len.max(temp_len);
+        }
+
+        if was_muted && !track.muted && !prevent_events {
+            let _ = interconnect
+                .events
+                .send(EventMessage::ChangeState(i, TrackStateChange::Muted(false)));
+        }
+
+        // Automated volumes are reported as they change, and ramps once they finish.
+        let ramp_done = was_ramping && track.volume_ramp.is_none();
+        let automated = track.volume_automation.is_some() && track.volume != old_volume;
+        if (ramp_done || automated) && !prevent_events {
+            let _ = interconnect.events.send(EventMessage::ChangeState(
+                i,
+                TrackStateChange::Volume(track.volume),
+            ));
+        }
+
         if temp_len > 0 || opus_len.is_some() {
+            if track.play_time == Duration::ZERO
+                && track.source.resampled_from().is_some()
+                && !prevent_events
+            {
+                let _ = interconnect
+                    .events
+                    .send(EventMessage::FireTrackEvent(i, TrackEvent::Resampled));
+            }
+
+            let looped = track.loop_tail.is_some();
             track.step_frame();
+            track.finish_pending_seek(i, interconnect);
+
+            if looped && !prevent_events {
+                let _ = interconnect.events.send(EventMessage::ChangeState(
+                    i,
+                    TrackStateChange::Position(track.position),
+                ));
+                let _ = interconnect.events.send(EventMessage::ChangeState(
+                    i,
+                    TrackStateChange::Loops(track.loops, false),
+                ));
+            }
+        } else if track.finish_pending_seek(i, interconnect) {
+            // The track ended while fading out for a seek: play on from the new position.
+        } else if empty_input_error && track.play_time == Duration::ZERO {
+            // An input which ends before producing any audio has most likely failed.
+            track.fail();
         } else if track.do_loop() {
             if let Ok(time) = track.seek_time(Default::default()) {
                 // have to reproduce self.fire_event here
@@ -597,6 +981,26 @@ fn mix_tracks<'a>(
                     ));
                 }
             }
+        } else if track.queue_repeat() == QueueRepeat::All
+            && track.seek_time(Default::default()).is_ok()
+        {
+            // The track's queue moves it to the back once told that it has
+            // looped, and resumes it when its turn comes around again.
+            track.pause();
+            if !prevent_events {
+                let _ = interconnect.events.send(EventMessage::ChangeState(
+                    i,
+                    TrackStateChange::Mode(track.playing),
+                ));
+                let _ = interconnect.events.send(EventMessage::ChangeState(
+                    i,
+                    TrackStateChange::Position(track.position),
+                ));
+                let _ = interconnect.events.send(EventMessage::ChangeState(
+                    i,
+                    TrackStateChange::Loops(track.loops, false),
+                ));
+            }
         } else {
             track.end();
         }
@@ -613,16 +1017,334 @@ fn mix_tracks<'a>(
 ///
 /// We pass in an async handle for the benefit of some Input classes (e.g., restartables)
 /// who need to run their restart code elsewhere and return blank data until such time.
-#[instrument(skip(interconnect, mix_rx, async_handle))]
+#[instrument(skip(interconnect, mix_rx, async_handle, shared))]
 pub(crate) fn runner(
     interconnect: Interconnect,
     mix_rx: Receiver<MixerMessage>,
     async_handle: Handle,
     config: Config,
+    shared: Arc<SharedState>,
 ) {
     let mut mixer = Mixer::new(mix_rx, async_handle, interconnect, config);
+    mixer.shared = shared;
 
     mixer.run();
 
     let _ = mixer.disposer.send(DisposalMessage::Poison);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{input::Input, tracks};
+
+    /// Creates an interconnect for mixing tests, along with the receiving end of
+    /// its event channel.
+    fn test_mixer() -> (Interconnect, Receiver<EventMessage>) {
+        let (core, _) = flume::unbounded();
+        let (events, events_rx) = flume::unbounded();
+        let (mixer, _) = flume::unbounded();
+
+        let interconnect = Interconnect {
+            core,
+            events,
+            mixer,
+        };
+
+        (interconnect, events_rx)
+    }
+
+    /// Returns each volume reported to the event thread since last called.
+    fn volume_changes(events: &Receiver<EventMessage>) -> Vec<f32> {
+        events
+            .try_iter()
+            .filter_map(|msg| match msg {
+                EventMessage::ChangeState(_, TrackStateChange::Volume(volume)) => Some(volume),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Creates a track playing `frames` frames of constant float PCM.
+    fn pcm_track(frames: usize) -> Track {
+        let bytes: Vec<u8> = (0..frames * STEREO_FRAME_SIZE)
+            .flat_map(|_| 0.5f32.to_le_bytes())
+            .collect();
+
+        tracks::create_player(Input::float_pcm(true, bytes.into())).0
+    }
+
+    /// Mixes one frame of `tracks` at `frame_time`, returning the mix and its output.
+    fn mix_once(
+        tracks: &mut Vec<Track>,
+        interconnect: &Interconnect,
+        frame_time: Instant,
+    ) -> (MixType, [f32; STEREO_FRAME_SIZE]) {
+        let mut opus_frame = [0u8; VOICE_PACKET_MAX];
+        let mut mix_buffer = [0f32; STEREO_FRAME_SIZE];
+
+        let mix = mix_tracks(
+            &mut opus_frame[..],
+            &mut mix_buffer,
+            tracks,
+            interconnect,
+            false,
+            true,
+            (f32::NEG_INFINITY, f32::INFINITY),
+            Default::default(),
+            false,
+            frame_time,
+        );
+
+        (mix, mix_buffer)
+    }
+
+    fn mix_empty_track(empty_input_error: bool) -> PlayMode {
+        let (interconnect, _events) = test_mixer();
+
+        let input = Input::float_pcm(true, Vec::<u8>::new().into());
+        let mut tracks = vec![tracks::create_player(input).0];
+
+        let mut opus_frame = [0u8; VOICE_PACKET_MAX];
+        let mut mix_buffer = [0f32; STEREO_FRAME_SIZE];
+
+        mix_tracks(
+            &mut opus_frame[..],
+            &mut mix_buffer,
+            &mut tracks,
+            &interconnect,
+            true,
+            true,
+            (f32::NEG_INFINITY, f32::INFINITY),
+            Default::default(),
+            empty_input_error,
+            Instant::now(),
+        );
+
+        tracks[0].playing()
+    }
+
+    #[test]
+    fn zero_byte_input_errors() {
+        assert_eq!(mix_empty_track(true), PlayMode::Errored);
+    }
+
+    #[test]
+    fn zero_byte_input_ends_if_allowed() {
+        assert_eq!(mix_empty_track(false), PlayMode::End);
+    }
+
+    #[test]
+    fn scheduled_track_waits_for_nearest_tick() {
+        let (interconnect, _events) = test_mixer();
+
+        let mut track = pcm_track(4);
+        let now = Instant::now();
+        track.start_at = Some(now + 3 * TIMESTEP_LENGTH);
+        let mut tracks = vec![track];
+
+        let mut mix = |frame_time| mix_once(&mut tracks, &interconnect, frame_time).0;
+
+        assert_eq!(mix(now), MixType::MixedPcm(0));
+        assert_eq!(mix(now + 2 * TIMESTEP_LENGTH), MixType::MixedPcm(0));
+        assert_ne!(
+            mix(now + 3 * TIMESTEP_LENGTH - TIMESTEP_LENGTH / 4),
+            MixType::MixedPcm(0)
+        );
+        assert_ne!(mix(now), MixType::MixedPcm(0));
+    }
+
+    #[test]
+    fn muted_track_advances_silently_until_unmuted() {
+        let (interconnect, events) = test_mixer();
+
+        let mut track = pcm_track(4);
+        track.unmute_at(2 * TIMESTEP_LENGTH);
+        let mut tracks = vec![track];
+
+        let mut mix = || {
+            let (out, mix_buffer) = mix_once(&mut tracks, &interconnect, Instant::now());
+            (out, mix_buffer[0])
+        };
+
+        assert_eq!(mix(), (MixType::MixedPcm(0), 0.0));
+        assert_eq!(mix(), (MixType::MixedPcm(0), 0.0));
+        assert_ne!(mix(), (MixType::MixedPcm(0), 0.0));
+        assert_eq!(tracks[0].position, 3 * TIMESTEP_LENGTH);
+        assert!(!tracks[0].is_muted());
+
+        let unmutes = events
+            .try_iter()
+            .filter(|msg| {
+                matches!(
+                    msg,
+                    EventMessage::ChangeState(0, TrackStateChange::Muted(false))
+                )
+            })
+            .count();
+        assert_eq!(unmutes, 1);
+    }
+
+    #[test]
+    fn inaudible_track_keeps_timing() {
+        let (interconnect, _events) = test_mixer();
+
+        // Returns the position of a track after mixing it until it ends.
+        let play_out = |volume: f32| {
+            let mut track = pcm_track(3);
+            track.set_volume(volume);
+            track.set_loops(tracks::LoopState::Finite(1)).unwrap();
+            let mut tracks = vec![track];
+
+            while !tracks[0].playing.is_done() {
+                mix_once(&mut tracks, &interconnect, Instant::now());
+            }
+
+            (tracks[0].position, tracks[0].play_time)
+        };
+
+        assert_eq!(play_out(0.0), play_out(1.0));
+        assert_eq!(play_out(0.0).1, 6 * TIMESTEP_LENGTH);
+    }
+
+    #[test]
+    fn volume_ramp_reports_volume_once_finished() {
+        let (interconnect, events) = test_mixer();
+
+        let mut track = pcm_track(4);
+        track.set_volume_over(0.5, 2 * TIMESTEP_LENGTH);
+        let mut tracks = vec![track];
+
+        mix_once(&mut tracks, &interconnect, Instant::now());
+        mix_once(&mut tracks, &interconnect, Instant::now());
+        assert!(volume_changes(&events).is_empty());
+
+        mix_once(&mut tracks, &interconnect, Instant::now());
+        assert_eq!(volume_changes(&events), vec![0.5]);
+
+        mix_once(&mut tracks, &interconnect, Instant::now());
+        assert!(volume_changes(&events).is_empty());
+    }
+
+    #[test]
+    fn volume_automation_reports_changes() {
+        let (interconnect, events) = test_mixer();
+
+        let mut track = pcm_track(4);
+        track.set_volume_automation(vec![(Duration::ZERO, 1.0), (2 * TIMESTEP_LENGTH, 0.5)]);
+        let mut tracks = vec![track];
+
+        let mut reported = vec![];
+        for _ in 0..4 {
+            mix_once(&mut tracks, &interconnect, Instant::now());
+            reported.extend(volume_changes(&events));
+        }
+
+        assert_eq!(reported, vec![0.75, 0.5]);
+    }
+
+    #[test]
+    fn fast_track_reads_input_at_speed() {
+        let (interconnect, _events) = test_mixer();
+
+        let mut track = pcm_track(6);
+        track.set_playback_speed(2.0);
+        let mut tracks = vec![track];
+
+        while !tracks[0].playing.is_done() {
+            mix_once(&mut tracks, &interconnect, Instant::now());
+        }
+
+        assert_eq!(tracks[0].position, 6 * TIMESTEP_LENGTH);
+        assert_eq!(tracks[0].play_time, 3 * TIMESTEP_LENGTH);
+    }
+
+    #[test]
+    fn queue_repeat_follows_own_loops() {
+        let (interconnect, _events) = test_mixer();
+
+        let repeat = tracks::SharedRepeat::default();
+        repeat.set(QueueRepeat::Track);
+
+        let mut track = pcm_track(2);
+        track.set_loops(tracks::LoopState::Finite(1)).unwrap();
+        track.queue_repeat = Some(repeat.clone());
+        let mut tracks = vec![track];
+
+        // The track's own loop is used up, then the queue loops it indefinitely.
+        for _ in 0..8 {
+            mix_once(&mut tracks, &interconnect, Instant::now());
+        }
+        assert_eq!(tracks[0].playing, PlayMode::Play);
+        assert_eq!(tracks[0].loops, tracks::LoopState::Finite(0));
+
+        // Repeating all tracks leaves the track paused at its start for the queue.
+        repeat.set(QueueRepeat::All);
+        for _ in 0..3 {
+            mix_once(&mut tracks, &interconnect, Instant::now());
+        }
+        assert_eq!(tracks[0].playing, PlayMode::Pause);
+        assert_eq!(tracks[0].position, Duration::ZERO);
+
+        repeat.set(QueueRepeat::Off);
+        tracks[0].play();
+        for _ in 0..3 {
+            mix_once(&mut tracks, &interconnect, Instant::now());
+        }
+        assert_eq!(tracks[0].playing, PlayMode::End);
+    }
+
+    #[test]
+    fn looping_track_is_sample_accurate() {
+        const LOOP_LEN: usize = 1_000;
+        const FRAMES: usize = 2_999;
+
+        let (interconnect, _events) = test_mixer();
+
+        // Each stereo sample holds its own index, to detect gaps or repeats.
+        let bytes: Vec<u8> = (0..LOOP_LEN)
+            .flat_map(|i| [i as f32, -(i as f32)])
+            .flat_map(f32::to_le_bytes)
+            .collect();
+        let mut track = tracks::create_player(Input::float_pcm(true, bytes.into())).0;
+        track.set_loops(tracks::LoopState::Infinite).unwrap();
+        let mut tracks = vec![track];
+
+        let mut played = 0;
+        for _ in 0..FRAMES {
+            let (mix, mix_buffer) = mix_once(&mut tracks, &interconnect, Instant::now());
+            assert_eq!(mix, MixType::MixedPcm(STEREO_FRAME_BYTE_SIZE));
+
+            for pair in mix_buffer.chunks_exact(2) {
+                let expected = (played % LOOP_LEN) as f32;
+                assert_eq!(pair, [expected, -expected], "sample {}", played);
+                played += 1;
+            }
+        }
+
+        let tail = (FRAMES * MONO_FRAME_SIZE) % LOOP_LEN;
+        assert_eq!(
+            tracks[0].position,
+            Duration::from_nanos(tail as u64 * 1_000_000_000 / SAMPLE_RATE_RAW as u64)
+        );
+    }
+
+    #[test]
+    fn seek_fades_in_from_silence() {
+        let mut track = pcm_track(2);
+        track.seek_fade = seek_fade_len(&Config::default());
+
+        track.seek_time(Duration::ZERO).unwrap();
+
+        let mut mix_buffer = [0f32; STEREO_FRAME_SIZE];
+        track.mix(
+            &mut mix_buffer,
+            (f32::NEG_INFINITY, f32::INFINITY),
+            Default::default(),
+        );
+
+        assert_eq!(mix_buffer[0], 0.0);
+        assert!(mix_buffer[track.seek_fade / 2] < 0.5);
+        assert_eq!(mix_buffer[STEREO_FRAME_SIZE - 1], 0.5);
+    }
+}
@@ -2,19 +2,23 @@
 
 pub(crate) mod disposal;
 pub mod error;
-mod events;
+pub(crate) mod events;
 pub mod message;
 pub mod mixer;
 pub(crate) mod udp_rx;
 pub(crate) mod udp_tx;
+mod watchdog;
 pub(crate) mod ws;
 
 use std::time::Duration;
 
-use super::connection::{error::Error as ConnectionError, Connection};
+use super::{
+    connection::{error::Error as ConnectionError, Connection},
+    SharedState,
+};
 use crate::{
     events::{
-        context_data::{DisconnectKind, DisconnectReason},
+        context_data::{DisconnectKind, DisconnectReason, IdleDisconnectData},
         internal_data::{InternalConnect, InternalDisconnect},
         CoreContext,
     },
@@ -23,18 +27,33 @@ use crate::{
 };
 use flume::{Receiver, RecvError, Sender};
 use message::*;
+use std::sync::{atomic::Ordering, Arc};
 use tokio::{runtime::Handle, spawn, time::sleep as tsleep};
 use tracing::{debug, instrument, trace};
 
-pub(crate) fn start(config: Config, rx: Receiver<CoreMessage>, tx: Sender<CoreMessage>) {
-    spawn(async move {
+/// Shortest stall which the mixer watchdog will report, as frames are
+/// normally only produced every 20ms.
+const MIN_WATCHDOG_THRESHOLD: Duration = Duration::from_millis(100);
+
+pub(crate) fn start(
+    config: Config,
+    rx: Receiver<CoreMessage>,
+    tx: Sender<CoreMessage>,
+    shared: Arc<SharedState>,
+    runtime: &Handle,
+) {
+    runtime.spawn(async move {
         trace!("Driver started.");
-        runner(config, rx, tx).await;
+        runner(config, rx, tx, shared).await;
         trace!("Driver finished.");
     });
 }
 
-fn start_internals(core: Sender<CoreMessage>, config: Config) -> Interconnect {
+fn start_internals(
+    core: Sender<CoreMessage>,
+    config: Config,
+    shared: Arc<SharedState>,
+) -> Interconnect {
     let (evt_tx, evt_rx) = flume::unbounded();
     let (mix_tx, mix_rx) = flume::unbounded();
 
@@ -51,36 +70,49 @@ fn start_internals(core: Sender<CoreMessage>, config: Config) -> Interconnect {
         trace!("Event processor finished.");
     });
 
+    let _ = interconnect
+        .events
+        .send(EventMessage::SetHandlerConcurrency(
+            config.event_handler_concurrency,
+        ));
+
+    if let Some(threshold) = config.mixer_watchdog {
+        spawn(watchdog::runner(
+            interconnect.core.clone(),
+            shared.clone(),
+            threshold.max(MIN_WATCHDOG_THRESHOLD),
+        ));
+    }
+
     let ic = interconnect.clone();
     let handle = Handle::current();
     std::thread::spawn(move || {
         trace!("Mixer started.");
-        mixer::runner(ic, mix_rx, handle, config);
+        mixer::runner(ic, mix_rx, handle, config, shared);
         trace!("Mixer finished.");
     });
 
     interconnect
 }
 
-#[instrument(skip(rx, tx))]
-async fn runner(mut config: Config, rx: Receiver<CoreMessage>, tx: Sender<CoreMessage>) {
+#[instrument(skip(rx, tx, shared))]
+async fn runner(
+    mut config: Config,
+    rx: Receiver<CoreMessage>,
+    tx: Sender<CoreMessage>,
+    shared: Arc<SharedState>,
+) {
     let mut next_config: Option<Config> = None;
+    let mut track_end: Option<TrackEndHandler> = None;
     let mut connection: Option<Connection> = None;
-    let mut interconnect = start_internals(tx, config.clone());
+    let mut interconnect = start_internals(tx, config.clone(), shared.clone());
     let mut retrying = None;
     let mut attempt_idx = 0;
 
     loop {
         match rx.recv_async().await {
             Ok(CoreMessage::ConnectWithResult(info, tx)) => {
-                config = if let Some(new_config) = next_config.take() {
-                    let _ = interconnect
-                        .mixer
-                        .send(MixerMessage::SetConfig(new_config.clone()));
-                    new_config
-                } else {
-                    config
-                };
+                apply_next_config(&mut config, &mut next_config, &interconnect);
 
                 if connection
                     .as_ref()
@@ -91,8 +123,9 @@ async fn runner(mut config: Config, rx: Receiver<CoreMessage>, tx: Sender<CoreMe
                     // active connection.
                     // This allows the gateway component to keep sending join requests independent
                     // of driver failures.
+                    *shared.connected_since.lock() = None;
                     connection = ConnectionRetryData::connect(tx, info, &mut attempt_idx)
-                        .attempt(&mut retrying, &interconnect, &config)
+                        .attempt(&mut retrying, &interconnect, &config, &shared)
                         .await;
                 } else {
                     // No reconnection was attempted as there's a valid, identical connection;
@@ -100,18 +133,36 @@ async fn runner(mut config: Config, rx: Receiver<CoreMessage>, tx: Sender<CoreMe
                     let _ = tx.send(Ok(()));
                 }
             },
+            Ok(CoreMessage::MoveTo(info, tx)) => {
+                apply_next_config(&mut config, &mut next_config, &interconnect);
+
+                let moved = ConnectionRetryData::move_to(tx, info, &mut attempt_idx)
+                    .attempt(&mut retrying, &interconnect, &config, &shared)
+                    .await;
+
+                // Keep the old connection (and its audio) alive while retries are pending.
+                if moved.is_some() || retrying.is_none() {
+                    connection = moved;
+                }
+            },
             Ok(CoreMessage::RetryConnect(retry_idx)) => {
                 debug!("Retrying idx: {} (vs. {})", retry_idx, attempt_idx);
                 if retry_idx == attempt_idx {
                     if let Some(progress) = retrying.take() {
-                        connection = progress
-                            .attempt(&mut retrying, &interconnect, &config)
+                        let moving = matches!(progress.flavour, ConnectionFlavour::Move(_));
+                        let new_conn = progress
+                            .attempt(&mut retrying, &interconnect, &config, &shared)
                             .await;
+
+                        if new_conn.is_some() || !moving || retrying.is_none() {
+                            connection = new_conn;
+                        }
                     }
                 }
             },
             Ok(CoreMessage::Disconnect) => {
                 let last_conn = connection.take();
+                *shared.connected_since.lock() = None;
                 let _ = interconnect.mixer.send(MixerMessage::DropConn);
                 let _ = interconnect.mixer.send(MixerMessage::RebuildEncoder);
 
@@ -121,6 +172,7 @@ async fn runner(mut config: Config, rx: Receiver<CoreMessage>, tx: Sender<CoreMe
                             kind: DisconnectKind::Runtime,
                             reason: None,
                             info: conn.info.clone(),
+                            attempts: 0,
                         }),
                     ));
                 }
@@ -134,6 +186,7 @@ async fn runner(mut config: Config, rx: Receiver<CoreMessage>, tx: Sender<CoreMe
                     reason = None;
                 } else {
                     connection = None;
+                    *shared.connected_since.lock() = None;
                     let _ = interconnect.mixer.send(MixerMessage::DropConn);
                     let _ = interconnect.mixer.send(MixerMessage::RebuildEncoder);
                 }
@@ -143,23 +196,60 @@ async fn runner(mut config: Config, rx: Receiver<CoreMessage>, tx: Sender<CoreMe
                         kind: DisconnectKind::Runtime,
                         reason,
                         info: ws_info,
+                        attempts: 0,
                     }),
                 ));
             },
-            Ok(CoreMessage::SetTrack(s)) => {
-                let _ = interconnect.mixer.send(MixerMessage::SetTrack(s));
+            Ok(CoreMessage::SetTrack(s, tx)) => {
+                let _ = interconnect.mixer.send(MixerMessage::SetTrack(s, tx));
             },
             Ok(CoreMessage::AddTrack(s)) => {
                 let _ = interconnect.mixer.send(MixerMessage::AddTrack(s));
             },
+            Ok(CoreMessage::RequestActiveTracks(tx)) => {
+                let _ = interconnect
+                    .mixer
+                    .send(MixerMessage::RequestActiveTracks(tx));
+            },
+            Ok(CoreMessage::AddRecording(record_config, tx)) => {
+                let _ = interconnect
+                    .mixer
+                    .send(MixerMessage::AddRecording(record_config, tx));
+            },
             Ok(CoreMessage::SetBitrate(b)) => {
                 let _ = interconnect.mixer.send(MixerMessage::SetBitrate(b));
             },
+            Ok(CoreMessage::ResetEncoder) => {
+                let _ = interconnect.mixer.send(MixerMessage::ResetEncoder);
+            },
+            Ok(CoreMessage::SetVolumeClamp(min, max)) => {
+                let _ = interconnect
+                    .mixer
+                    .send(MixerMessage::SetVolumeClamp(min, max));
+            },
+            Ok(CoreMessage::SetVolumeCurve(curve)) => {
+                let _ = interconnect.mixer.send(MixerMessage::SetVolumeCurve(curve));
+            },
+            Ok(CoreMessage::SetIdleTimeout(timeout)) => {
+                let _ = interconnect
+                    .mixer
+                    .send(MixerMessage::SetIdleTimeout(timeout));
+            },
+            Ok(CoreMessage::SetPausedAlone(paused)) => {
+                let _ = interconnect
+                    .mixer
+                    .send(MixerMessage::SetPausedAlone(paused));
+            },
             Ok(CoreMessage::SetConfig(mut new_config)) => {
                 next_config = Some(new_config.clone());
 
                 new_config.make_safe(&config, connection.is_some());
 
+                let _ = interconnect
+                    .events
+                    .send(EventMessage::SetHandlerConcurrency(
+                        new_config.event_handler_concurrency,
+                    ));
                 let _ = interconnect.mixer.send(MixerMessage::SetConfig(new_config));
             },
             Ok(CoreMessage::AddEvent(evt)) => {
@@ -168,6 +258,12 @@ async fn runner(mut config: Config, rx: Receiver<CoreMessage>, tx: Sender<CoreMe
             Ok(CoreMessage::RemoveGlobalEvents) => {
                 let _ = interconnect.events.send(EventMessage::RemoveGlobalEvents);
             },
+            Ok(CoreMessage::SetTrackEndHandler(handler)) => {
+                track_end = handler.clone();
+                let _ = interconnect
+                    .events
+                    .send(EventMessage::SetTrackEndHandler(handler));
+            },
             Ok(CoreMessage::Mute(m)) => {
                 let _ = interconnect.mixer.send(MixerMessage::SetMute(m));
             },
@@ -198,13 +294,15 @@ async fn runner(mut config: Config, rx: Receiver<CoreMessage>, tx: Sender<CoreMe
 
                     if full_connect {
                         connection = ConnectionRetryData::reconnect(info, &mut attempt_idx)
-                            .attempt(&mut retrying, &interconnect, &config)
+                            .attempt(&mut retrying, &interconnect, &config, &shared)
                             .await;
                     } else if let Some(ref connection) = &connection {
+                        shared.reconnect_count.fetch_add(1, Ordering::Relaxed);
                         let _ = interconnect.events.send(EventMessage::FireCoreEvent(
                             CoreContext::DriverReconnect(InternalConnect {
                                 info: connection.info.clone(),
                                 ssrc: connection.ssrc,
+                                attempt: 1,
                             }),
                         ));
                     }
@@ -215,22 +313,86 @@ async fn runner(mut config: Config, rx: Receiver<CoreMessage>, tx: Sender<CoreMe
                     let info = conn.info.clone();
 
                     connection = ConnectionRetryData::reconnect(info, &mut attempt_idx)
-                        .attempt(&mut retrying, &interconnect, &config)
+                        .attempt(&mut retrying, &interconnect, &config, &shared)
                         .await;
                 },
             Ok(CoreMessage::RebuildInterconnect) => {
                 interconnect.restart_volatile_internals();
+
+                let latest_config = next_config.as_ref().unwrap_or(&config);
+                let _ = interconnect
+                    .events
+                    .send(EventMessage::SetHandlerConcurrency(
+                        latest_config.event_handler_concurrency,
+                    ));
+                let _ = interconnect
+                    .events
+                    .send(EventMessage::SetTrackEndHandler(track_end.clone()));
+            },
+            Ok(CoreMessage::IdleTimeout(idle_for)) =>
+                if let Some(conn) = connection.take() {
+                    *shared.connected_since.lock() = None;
+                    let _ = interconnect.mixer.send(MixerMessage::DropConn);
+                    let _ = interconnect.mixer.send(MixerMessage::RebuildEncoder);
+
+                    let _ = interconnect.events.send(EventMessage::FireCoreEvent(
+                        CoreContext::IdleDisconnect(IdleDisconnectData {
+                            channel_id: conn.info.channel_id,
+                            guild_id: conn.info.guild_id,
+                            idle_for,
+                        }),
+                    ));
+                    let _ = interconnect.events.send(EventMessage::FireCoreEvent(
+                        CoreContext::DriverDisconnect(InternalDisconnect {
+                            kind: DisconnectKind::Runtime,
+                            reason: None,
+                            info: conn.info.clone(),
+                            attempts: 0,
+                        }),
+                    ));
+                },
+            Ok(CoreMessage::UdpSendError(e)) =>
+                if let Some(entry) = shared.send_errors.lock().record(&e) {
+                    let _ = interconnect.events.send(EventMessage::FireCoreEvent(
+                        CoreContext::SendError(entry.clone()),
+                    ));
+                },
+            Ok(CoreMessage::MixerStall(data)) => {
+                let _ = interconnect
+                    .events
+                    .send(EventMessage::FireCoreEvent(CoreContext::MixerStall(data)));
             },
             Err(RecvError::Disconnected) | Ok(CoreMessage::Poison) => {
                 break;
             },
         }
+
+        *shared.ssrc.lock() = connection.as_ref().map(|conn| conn.ssrc);
+        *shared.crypto_mode.lock() = connection.as_ref().map(|conn| conn.crypto_mode);
     }
 
+    *shared.ssrc.lock() = None;
+    *shared.crypto_mode.lock() = None;
+    *shared.connected_since.lock() = None;
+
     trace!("Main thread exited");
     interconnect.poison_all();
 }
 
+/// Applies any config change deferred until the next connection attempt.
+fn apply_next_config(
+    config: &mut Config,
+    next_config: &mut Option<Config>,
+    interconnect: &Interconnect,
+) {
+    if let Some(new_config) = next_config.take() {
+        let _ = interconnect
+            .mixer
+            .send(MixerMessage::SetConfig(new_config.clone()));
+        *config = new_config;
+    }
+}
+
 struct ConnectionRetryData {
     flavour: ConnectionFlavour,
     attempts: usize,
@@ -248,6 +410,14 @@ impl ConnectionRetryData {
         Self::base(ConnectionFlavour::Connect(tx), info, idx_src)
     }
 
+    fn move_to(
+        tx: Sender<Result<(), ConnectionError>>,
+        info: ConnectionInfo,
+        idx_src: &mut usize,
+    ) -> Self {
+        Self::base(ConnectionFlavour::Move(tx), info, idx_src)
+    }
+
     fn reconnect(info: ConnectionInfo, idx_src: &mut usize) -> Self {
         Self::base(ConnectionFlavour::Reconnect, info, idx_src)
     }
@@ -269,9 +439,16 @@ impl ConnectionRetryData {
         attempt_slot: &mut Option<Self>,
         interconnect: &Interconnect,
         config: &Config,
+        shared: &SharedState,
     ) -> Option<Connection> {
         match Connection::new(self.info.clone(), interconnect, config, self.idx).await {
             Ok(connection) => {
+                if matches!(self.flavour, ConnectionFlavour::Reconnect) {
+                    shared.reconnect_count.fetch_add(1, Ordering::Relaxed);
+                } else {
+                    shared.start_session();
+                }
+
                 match self.flavour {
                     ConnectionFlavour::Connect(tx) => {
                         // Other side may not be listening: this is fine.
@@ -281,6 +458,18 @@ impl ConnectionRetryData {
                             CoreContext::DriverConnect(InternalConnect {
                                 info: connection.info.clone(),
                                 ssrc: connection.ssrc,
+                                attempt: self.attempts + 1,
+                            }),
+                        ));
+                    },
+                    ConnectionFlavour::Move(tx) => {
+                        let _ = tx.send(Ok(()));
+
+                        let _ = interconnect.events.send(EventMessage::FireCoreEvent(
+                            CoreContext::DriverMove(InternalConnect {
+                                info: connection.info.clone(),
+                                ssrc: connection.ssrc,
+                                attempt: self.attempts + 1,
                             }),
                         ));
                     },
@@ -289,6 +478,7 @@ impl ConnectionRetryData {
                             CoreContext::DriverReconnect(InternalConnect {
                                 info: connection.info.clone(),
                                 ssrc: connection.ssrc,
+                                attempt: self.attempts + 1,
                             }),
                         ));
                     },
@@ -321,9 +511,10 @@ impl ConnectionRetryData {
                     *attempt_slot = Some(self);
                 } else {
                     let reason = Some(DisconnectReason::from(&why));
+                    *shared.connected_since.lock() = None;
 
                     match self.flavour {
-                        ConnectionFlavour::Connect(tx) => {
+                        ConnectionFlavour::Connect(tx) | ConnectionFlavour::Move(tx) => {
                             // See above.
                             let _ = tx.send(Err(why));
 
@@ -332,6 +523,7 @@ impl ConnectionRetryData {
                                     kind: DisconnectKind::Connect,
                                     reason,
                                     info: self.info,
+                                    attempts: self.attempts + 1,
                                 }),
                             ));
                         },
@@ -341,6 +533,7 @@ impl ConnectionRetryData {
                                     kind: DisconnectKind::Reconnect,
                                     reason,
                                     info: self.info,
+                                    attempts: self.attempts + 1,
                                 }),
                             ));
                         },
@@ -355,5 +548,76 @@ impl ConnectionRetryData {
 
 enum ConnectionFlavour {
     Connect(Sender<Result<(), ConnectionError>>),
+    Move(Sender<Result<(), ConnectionError>>),
     Reconnect,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        driver::retry::{Retry, Strategy},
+        events::context_data::DisconnectData,
+    };
+
+    #[test]
+    fn failed_connection_reports_every_attempt() {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        let (core, _core_rx) = flume::unbounded();
+        let (events, events_rx) = flume::unbounded();
+        let (mixer, _mixer_rx) = flume::unbounded();
+        let interconnect = Interconnect {
+            core,
+            events,
+            mixer,
+        };
+
+        let config = Config::default().driver_retry(Retry {
+            strategy: Strategy::Every(Duration::ZERO),
+            retry_limit: Some(1),
+        });
+        let shared = SharedState::default();
+
+        // Nothing listens on port 1, so every attempt is refused.
+        let info = ConnectionInfo {
+            channel_id: None,
+            endpoint: "127.0.0.1:1".into(),
+            guild_id: 1.into(),
+            session_id: "session".into(),
+            token: "token".into(),
+            user_id: 2.into(),
+        };
+
+        let (tx, rx) = flume::unbounded();
+        let mut idx = 0;
+        let mut retrying = Some(ConnectionRetryData::connect(tx, info, &mut idx));
+        let mut tries = 0;
+
+        rt.block_on(async {
+            while let Some(progress) = retrying.take() {
+                tries += 1;
+                assert!(progress
+                    .attempt(&mut retrying, &interconnect, &config, &shared)
+                    .await
+                    .is_none());
+            }
+        });
+
+        assert_eq!(tries, 2);
+        assert!(matches!(rx.try_recv(), Ok(Err(_))));
+
+        let disconnect = events_rx
+            .try_iter()
+            .find_map(|msg| match msg {
+                EventMessage::FireCoreEvent(CoreContext::DriverDisconnect(d)) => Some(d),
+                _ => None,
+            })
+            .expect("failed connection should be reported");
+
+        assert_eq!(DisconnectData::from(&disconnect).attempts, 2);
+    }
+}
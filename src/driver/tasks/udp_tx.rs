@@ -1,8 +1,8 @@
 use super::message::*;
 use crate::constants::*;
 use discortp::discord::MutableKeepalivePacket;
-use flume::Receiver;
-use std::sync::Arc;
+use flume::{Receiver, Sender};
+use std::{sync::Arc, time::Duration};
 use tokio::{
     net::UdpSocket,
     time::{timeout_at, Instant},
@@ -11,7 +11,9 @@ use tracing::{error, instrument, trace};
 
 struct UdpTx {
     ssrc: u32,
+    keepalive_gap: Duration,
     rx: Receiver<UdpTxMessage>,
+    core: Sender<CoreMessage>,
 
     udp_tx: Arc<UdpSocket>,
 }
@@ -23,7 +25,7 @@ impl UdpTx {
             .expect("FATAL: Insufficient bytes given to keepalive packet.");
         ka.set_ssrc(self.ssrc);
 
-        let mut ka_time = Instant::now() + UDP_KEEPALIVE_GAP;
+        let mut ka_time = Instant::now() + self.keepalive_gap;
 
         loop {
             use UdpTxMessage::*;
@@ -32,13 +34,15 @@ impl UdpTx {
                     trace!("Sending UDP Keepalive.");
                     if let Err(e) = self.udp_tx.send(&keepalive_bytes[..]).await {
                         error!("Fatal UDP keepalive send error: {:?}.", e);
+                        let _ = self.core.send(CoreMessage::UdpSendError(e));
                         break;
                     }
-                    ka_time += UDP_KEEPALIVE_GAP;
+                    ka_time += self.keepalive_gap;
                 },
                 Ok(Ok(Packet(p))) =>
                     if let Err(e) = self.udp_tx.send(&p[..]).await {
                         error!("Fatal UDP packet send error: {:?}.", e);
+                        let _ = self.core.send(CoreMessage::UdpSendError(e));
                         break;
                     },
                 Ok(Err(e)) => {
@@ -53,13 +57,21 @@ impl UdpTx {
     }
 }
 
-#[instrument(skip(udp_msg_rx))]
-pub(crate) async fn runner(udp_msg_rx: Receiver<UdpTxMessage>, ssrc: u32, udp_tx: Arc<UdpSocket>) {
+#[instrument(skip(udp_msg_rx, core))]
+pub(crate) async fn runner(
+    udp_msg_rx: Receiver<UdpTxMessage>,
+    core: Sender<CoreMessage>,
+    ssrc: u32,
+    keepalive_gap: Duration,
+    udp_tx: Arc<UdpSocket>,
+) {
     trace!("UDP transmit handle started.");
 
     let mut txer = UdpTx {
         ssrc,
+        keepalive_gap: keepalive_gap.max(UDP_KEEPALIVE_MIN_GAP),
         rx: udp_msg_rx,
+        core,
         udp_tx,
     };
 
@@ -0,0 +1,64 @@
+use super::message::*;
+use crate::{driver::SharedState, events::context_data::MixerStallData};
+use flume::Sender;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::time::sleep as tsleep;
+use tracing::{instrument, warn};
+
+/// Periodically checks that the mixer is still producing frames, reporting
+/// any stalls longer than `threshold` to the core task.
+#[instrument(skip(core, shared))]
+pub(crate) async fn runner(
+    core: Sender<CoreMessage>,
+    shared: Arc<SharedState>,
+    threshold: Duration,
+) {
+    let mut stalled_at: Option<Instant> = None;
+
+    loop {
+        tsleep(threshold / 2).await;
+
+        if core.is_disconnected() {
+            break;
+        }
+
+        let last_frame = *shared.last_frame.lock();
+
+        let report = match (last_frame, stalled_at) {
+            (Some(last), None) if last.elapsed() > threshold => {
+                stalled_at = Some(last);
+
+                let duration = last.elapsed();
+                warn!("Mixer has stalled for {:?}.", duration);
+
+                Some(MixerStallData {
+                    duration,
+                    resolved: false,
+                })
+            },
+            (Some(last), Some(stall_start)) if last != stall_start => {
+                stalled_at = None;
+
+                Some(MixerStallData {
+                    duration: last - stall_start,
+                    resolved: true,
+                })
+            },
+            (None, Some(_)) => {
+                // The mixer has since gone idle, which is not a stall.
+                stalled_at = None;
+                None
+            },
+            _ => None,
+        };
+
+        if let Some(data) = report {
+            if core.send(CoreMessage::MixerStall(data)).is_err() {
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+/// Mapping from a track's [`volume`] to the gain applied by the mixer.
+///
+/// [`volume`]: crate::tracks::Track::volume
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum VolumeCurve {
+    /// Volume is applied directly as a linear gain.
+    ///
+    /// This is the default.
+    Linear,
+    /// Volume is cubed before being applied, approximating how loudness is perceived.
+    ///
+    /// This makes evenly-spaced volume values (i.e., from a UI slider) sound evenly
+    /// spaced: `0.5` is roughly half as loud as `1.0`, rather than only slightly quieter.
+    /// A volume of `1.0` is unchanged.
+    Perceptual,
+}
+
+impl VolumeCurve {
+    /// Returns the linear gain the mixer will apply for a given volume.
+    pub fn gain(self, volume: f32) -> f32 {
+        match self {
+            VolumeCurve::Linear => volume,
+            VolumeCurve::Perceptual => volume * volume * volume,
+        }
+    }
+}
+
+impl Default for VolumeCurve {
+    fn default() -> Self {
+        VolumeCurve::Linear
+    }
+}
@@ -29,4 +29,40 @@ pub struct ConnectData<'a> {
     ///
     /// [RTP SSRC]: https://tools.ietf.org/html/rfc3550#section-3
     pub ssrc: u32,
+    /// The number of (re)connection attempts made so far, including this one.
+    ///
+    /// This is `1` if the driver connected on its first attempt.
+    pub attempt: usize,
+}
+
+/// Progress of an ongoing voice connection attempt.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub struct ConnectProgressData {
+    /// ID of the voice channel being joined, if it is known.
+    pub channel_id: Option<ChannelId>,
+    /// ID of the target voice channel's parent guild.
+    pub guild_id: GuildId,
+    /// The step of the handshake which has just begun.
+    pub stage: ConnectStage,
+}
+
+/// Steps taken while establishing a voice connection, in order.
+///
+/// A successful connection is then reported by [`CoreEvent::DriverConnect`]
+/// or [`CoreEvent::DriverReconnect`].
+///
+/// [`CoreEvent::DriverConnect`]: crate::events::CoreEvent::DriverConnect
+/// [`CoreEvent::DriverReconnect`]: crate::events::CoreEvent::DriverReconnect
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum ConnectStage {
+    /// Opening a websocket connection to Discord's voice server.
+    Gateway,
+    /// Identifying with the voice server, and awaiting its session parameters.
+    Identify,
+    /// Discovering this host's external IP address and port over UDP.
+    UdpDiscovery,
+    /// Selecting an encryption mode, and awaiting the session's key.
+    SelectProtocol,
 }
@@ -0,0 +1,15 @@
+use crate::tracks::{TrackHandle, TrackState};
+
+/// Cue point reached by a track, registered via [`TrackHandle::add_cue`].
+///
+/// [`TrackHandle::add_cue`]: crate::tracks::TrackHandle::add_cue
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct CueData<'a> {
+    /// Name given to this cue when it was registered.
+    pub name: &'a str,
+    /// State of the track which reached this cue.
+    pub state: &'a TrackState,
+    /// Handle to the track which reached this cue.
+    pub handle: &'a TrackHandle,
+}
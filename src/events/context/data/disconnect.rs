@@ -29,6 +29,12 @@ pub struct DisconnectData<'a> {
     pub guild_id: GuildId,
     /// Unique string describing this session for validation/authentication purposes.
     pub session_id: &'a str,
+    /// The number of (re)connection attempts made before giving up.
+    ///
+    /// This is `0` for [`DisconnectKind::Runtime`] disconnections.
+    ///
+    /// [`DisconnectKind::Runtime`]: DisconnectKind::Runtime
+    pub attempts: usize,
 }
 
 /// The location that a voice connection was terminated.
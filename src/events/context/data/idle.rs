@@ -0,0 +1,18 @@
+use crate::id::*;
+use std::time::Duration;
+
+/// Details of a voice connection closed by the driver's idle timeout.
+///
+/// See [`Driver::set_idle_timeout`].
+///
+/// [`Driver::set_idle_timeout`]: crate::driver::Driver::set_idle_timeout
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub struct IdleDisconnectData {
+    /// ID of the voice channel which was left, if it is known.
+    pub channel_id: Option<ChannelId>,
+    /// ID of the voice channel's parent guild.
+    pub guild_id: GuildId,
+    /// How long the driver went without any playing tracks.
+    pub idle_for: Duration,
+}
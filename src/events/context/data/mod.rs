@@ -2,11 +2,25 @@
 //!
 //! [`EventContext`]: super::EventContext
 mod connect;
+mod cue;
 mod disconnect;
+mod idle;
 mod rtcp;
+mod send_error;
 mod speaking;
+mod stall;
 mod voice;
 
 use discortp::{rtcp::Rtcp, rtp::Rtp};
 
-pub use self::{connect::*, disconnect::*, rtcp::*, speaking::*, voice::*};
+pub use self::{
+    connect::*,
+    cue::*,
+    disconnect::*,
+    idle::*,
+    rtcp::*,
+    send_error::*,
+    speaking::*,
+    stall::*,
+    voice::*,
+};
@@ -0,0 +1,16 @@
+use std::io::ErrorKind as IoErrorKind;
+
+/// A new failure to send a packet over UDP.
+///
+/// Repeats of the previous error are not reported again, but are counted in
+/// [`Driver::recent_send_errors`].
+///
+/// [`Driver::recent_send_errors`]: crate::driver::Driver::recent_send_errors
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub struct SendErrorData<'a> {
+    /// The category of IO error encountered.
+    pub kind: IoErrorKind,
+    /// The error's description, as given by the OS.
+    pub message: &'a str,
+}
@@ -0,0 +1,19 @@
+use std::time::Duration;
+
+/// Details of a period in which the mixer failed to produce audio frames on time.
+///
+/// See [`Config::mixer_watchdog`].
+///
+/// [`Config::mixer_watchdog`]: crate::Config::mixer_watchdog
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub struct MixerStallData {
+    /// How long the mixer has gone without producing a frame.
+    ///
+    /// If `resolved`, this is the total length of the stall.
+    pub duration: Duration,
+    /// Whether the mixer has since resumed producing frames.
+    ///
+    /// Each stall is reported once when detected, and once more when it ends.
+    pub resolved: bool,
+}
@@ -6,6 +6,7 @@ use discortp::{rtcp::Rtcp, rtp::Rtp};
 pub struct InternalConnect {
     pub info: ConnectionInfo,
     pub ssrc: u32,
+    pub attempt: usize,
 }
 
 #[derive(Debug)]
@@ -13,6 +14,7 @@ pub struct InternalDisconnect {
     pub kind: DisconnectKind,
     pub reason: Option<DisconnectReason>,
     pub info: ConnectionInfo,
+    pub attempts: usize,
 }
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
@@ -44,6 +46,7 @@ impl<'a> From<&'a InternalConnect> for ConnectData<'a> {
             session_id: &val.info.session_id,
             server: &val.info.endpoint,
             ssrc: val.ssrc,
+            attempt: val.attempt,
         }
     }
 }
@@ -56,6 +59,7 @@ impl<'a> From<&'a InternalDisconnect> for DisconnectData<'a> {
             channel_id: val.info.channel_id,
             guild_id: val.info.guild_id,
             session_id: &val.info.session_id,
+            attempts: val.attempts,
         }
     }
 }
@@ -3,6 +3,7 @@ pub(crate) mod internal_data;
 
 use super::*;
 use crate::{
+    driver::UdpSendError,
     model::payload::{ClientDisconnect, Speaking},
     tracks::{TrackHandle, TrackState},
 };
@@ -26,6 +27,11 @@ pub enum EventContext<'a> {
     /// [`EventStore::add_event`]: EventStore::add_event
     /// [`TrackHandle::add_event`]: TrackHandle::add_event
     Track(&'a [(&'a TrackState, &'a TrackHandle)]),
+    /// Cue point context, passed to [`TrackEvent::Cue`] handlers once for
+    /// each cue reached by a track.
+    ///
+    /// [`TrackEvent::Cue`]: TrackEvent::Cue
+    Cue(CueData<'a>),
     /// Speaking state update, typically describing how another voice
     /// user is transmitting audio data. Clients must send at least one such
     /// packet to allow SSRC/UserID matching.
@@ -40,12 +46,23 @@ pub enum EventContext<'a> {
     RtcpPacket(RtcpData<'a>),
     /// Fired whenever a client disconnects.
     ClientDisconnect(ClientDisconnect),
+    /// Fires as this driver begins each step of connecting to a voice channel.
+    ConnectProgress(ConnectProgressData),
     /// Fires when this driver successfully connects to a voice channel.
     DriverConnect(ConnectData<'a>),
     /// Fires when this driver successfully reconnects after a network error.
     DriverReconnect(ConnectData<'a>),
+    /// Fires when this driver has moved its tracks onto a new voice connection.
+    DriverMove(ConnectData<'a>),
     /// Fires when this driver fails to connect to, or drops from, a voice channel.
     DriverDisconnect(DisconnectData<'a>),
+    /// Fires when the mixer stalls, and again once it recovers.
+    MixerStall(MixerStallData),
+    /// Fires when this driver leaves a voice channel after having no playing tracks
+    /// for its idle timeout.
+    IdleDisconnect(IdleDisconnectData),
+    /// Fires when sending a packet over UDP fails with a new error.
+    SendError(SendErrorData<'a>),
 }
 
 #[derive(Debug)]
@@ -55,9 +72,14 @@ pub enum CoreContext {
     VoicePacket(InternalVoicePacket),
     RtcpPacket(InternalRtcpPacket),
     ClientDisconnect(ClientDisconnect),
+    ConnectProgress(ConnectProgressData),
     DriverConnect(InternalConnect),
     DriverReconnect(InternalConnect),
+    DriverMove(InternalConnect),
     DriverDisconnect(InternalDisconnect),
+    MixerStall(MixerStallData),
+    IdleDisconnect(IdleDisconnectData),
+    SendError(UdpSendError),
 }
 
 impl<'a> CoreContext {
@@ -70,9 +92,17 @@ impl<'a> CoreContext {
             VoicePacket(evt) => EventContext::VoicePacket(VoiceData::from(evt)),
             RtcpPacket(evt) => EventContext::RtcpPacket(RtcpData::from(evt)),
             ClientDisconnect(evt) => EventContext::ClientDisconnect(*evt),
+            ConnectProgress(evt) => EventContext::ConnectProgress(*evt),
             DriverConnect(evt) => EventContext::DriverConnect(ConnectData::from(evt)),
             DriverReconnect(evt) => EventContext::DriverReconnect(ConnectData::from(evt)),
+            DriverMove(evt) => EventContext::DriverMove(ConnectData::from(evt)),
             DriverDisconnect(evt) => EventContext::DriverDisconnect(DisconnectData::from(evt)),
+            MixerStall(evt) => EventContext::MixerStall(*evt),
+            IdleDisconnect(evt) => EventContext::IdleDisconnect(*evt),
+            SendError(evt) => EventContext::SendError(SendErrorData {
+                kind: evt.kind,
+                message: &evt.message,
+            }),
         }
     }
 }
@@ -89,9 +119,14 @@ impl EventContext<'_> {
             VoicePacket(_) => Some(CoreEvent::VoicePacket),
             RtcpPacket(_) => Some(CoreEvent::RtcpPacket),
             ClientDisconnect(_) => Some(CoreEvent::ClientDisconnect),
+            ConnectProgress(_) => Some(CoreEvent::ConnectProgress),
             DriverConnect(_) => Some(CoreEvent::DriverConnect),
             DriverReconnect(_) => Some(CoreEvent::DriverReconnect),
+            DriverMove(_) => Some(CoreEvent::DriverMove),
             DriverDisconnect(_) => Some(CoreEvent::DriverDisconnect),
+            MixerStall(_) => Some(CoreEvent::MixerStall),
+            IdleDisconnect(_) => Some(CoreEvent::IdleDisconnect),
+            SendError(_) => Some(CoreEvent::SendError),
             _ => None,
         }
     }
@@ -46,10 +46,46 @@ pub enum CoreEvent {
     RtcpPacket,
     /// Fires whenever a user disconnects from the same stream as the bot.
     ClientDisconnect,
+    /// Fires as this driver begins each step of connecting to a voice channel,
+    /// such as UDP discovery or encryption mode selection.
+    ///
+    /// This may help to show connection status to users, or to diagnose
+    /// where a connection attempt stalls.
+    ConnectProgress,
     /// Fires when this driver successfully connects to a voice channel.
     DriverConnect,
     /// Fires when this driver successfully reconnects after a network error.
     DriverReconnect,
+    /// Fires when this driver has moved to a new voice connection via [`Driver::move_to`],
+    /// carrying over all of its tracks.
+    ///
+    /// [`Driver::move_to`]: crate::driver::Driver::move_to
+    DriverMove,
     /// Fires when this driver fails to connect to, or drops from, a voice channel.
     DriverDisconnect,
+    /// Fires when the mixer has not produced an audio frame within the window set by
+    /// [`Config::mixer_watchdog`], and again once it recovers.
+    ///
+    /// Stalls are typically caused by blocking work in user closures, such as those
+    /// passed to [`TrackHandle::action`] or [`TrackHandle::add_frame_tap`].
+    ///
+    /// [`Config::mixer_watchdog`]: crate::Config::mixer_watchdog
+    /// [`TrackHandle::action`]: crate::tracks::TrackHandle::action
+    /// [`TrackHandle::add_frame_tap`]: crate::tracks::TrackHandle::add_frame_tap
+    MixerStall,
+    /// Fires when this driver leaves a voice channel after having no playing tracks
+    /// for the duration set by [`Driver::set_idle_timeout`].
+    ///
+    /// This is followed by a [`DriverDisconnect`] event.
+    ///
+    /// [`Driver::set_idle_timeout`]: crate::driver::Driver::set_idle_timeout
+    /// [`DriverDisconnect`]: CoreEvent::DriverDisconnect
+    IdleDisconnect,
+    /// Fires when sending a voice or keepalive packet over UDP fails.
+    ///
+    /// Repeats of the previous error do not fire this event again, but are
+    /// counted by [`Driver::recent_send_errors`].
+    ///
+    /// [`Driver::recent_send_errors`]: crate::driver::Driver::recent_send_errors
+    SendError,
 }
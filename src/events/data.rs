@@ -1,8 +1,29 @@
 use super::*;
-use std::{cmp::Ordering, time::Duration};
+use std::{
+    cmp::Ordering,
+    sync::atomic::{AtomicU64, Ordering as AtomicOrdering},
+    time::Duration,
+};
+
+/// Unique identifier of a registered event handler, used to remove it via
+/// [`EventStore::remove_event`] or [`TrackHandle::remove_event`].
+///
+/// [`EventStore::remove_event`]: EventStore::remove_event
+/// [`TrackHandle::remove_event`]: crate::tracks::TrackHandle::remove_event
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct EventId(u64);
+
+impl EventId {
+    pub(crate) fn next() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+        Self(NEXT_ID.fetch_add(1, AtomicOrdering::Relaxed))
+    }
+}
 
 /// Internal representation of an event, as handled by the audio context.
 pub struct EventData {
+    pub(crate) id: EventId,
     pub(crate) event: Event,
     pub(crate) fire_time: Option<Duration>,
     pub(crate) action: Box<dyn EventHandler>,
@@ -23,12 +44,18 @@ impl EventData {
     /// [`Cancel`]: Event::Cancel
     pub fn new<F: EventHandler + 'static>(event: Event, action: F) -> Self {
         Self {
+            id: EventId::next(),
             event,
             fire_time: None,
             action: Box::new(action),
         }
     }
 
+    /// Returns the unique identifier of this event handler.
+    pub fn id(&self) -> EventId {
+        self.id
+    }
+
     /// Computes the next firing time for a timer event.
     pub fn compute_activation(&mut self, now: Duration) {
         match self.event {
@@ -47,8 +74,8 @@ impl std::fmt::Debug for EventData {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         write!(
             f,
-            "Event {{ event: {:?}, fire_time: {:?}, action: <fn> }}",
-            self.event, self.fire_time
+            "Event {{ id: {:?}, event: {:?}, fire_time: {:?}, action: <fn> }}",
+            self.id, self.event, self.fire_time
         )
     }
 }
@@ -1,14 +1,32 @@
 use super::*;
 use crate::{
     constants::*,
-    tracks::{PlayMode, TrackHandle, TrackState},
+    tracks::{PlayMode, ScheduledAction, ScheduledKind, TrackHandle, TrackState},
 };
+use futures::stream::{self, StreamExt};
 use std::{
     collections::{BinaryHeap, HashMap},
     time::Duration,
 };
 use tracing::info;
 
+/// Runs the handler of each given event against `ctx`, with up to `concurrency`
+/// handlers in flight at once, returning their results in the order given.
+async fn act_all(
+    events: &[EventData],
+    ctx: &EventContext<'_>,
+    concurrency: usize,
+) -> Vec<Option<Event>> {
+    // Collecting first keeps the closure (and its lifetimes) out of the
+    // stream's type, which must be `Send` to be held across this await.
+    let pending: Vec<_> = events.iter().map(|evt| evt.action.act(ctx)).collect();
+
+    stream::iter(pending)
+        .buffered(concurrency.max(1))
+        .collect()
+        .await
+}
+
 #[derive(Debug, Default)]
 /// Storage for [`EventData`], designed to be used for both local and global contexts.
 ///
@@ -19,6 +37,7 @@ use tracing::info;
 pub struct EventStore {
     timed: BinaryHeap<EventData>,
     untimed: HashMap<UntimedEvent, Vec<EventData>>,
+    cues: Vec<(EventId, Duration, String)>,
     local_only: bool,
 }
 
@@ -43,6 +62,10 @@ impl EventStore {
 
     /// Add an event to this store.
     ///
+    /// Any number of handlers may be registered for the same event: adding
+    /// a handler never replaces another. Handlers for the same [`TrackEvent`]
+    /// or [`CoreEvent`] are called in the order they were added.
+    ///
     /// Updates `evt` according to [`EventData::compute_activation`].
     ///
     /// [`EventData::compute_activation`]: EventData::compute_activation
@@ -76,24 +99,101 @@ impl EventStore {
         }
     }
 
+    /// Removes the event handler with the given identifier from this store.
+    ///
+    /// This is a no-op if no such handler exists.
+    pub fn remove_event(&mut self, id: EventId) {
+        if self.timed.iter().any(|evt| evt.id == id) {
+            let mut timed = std::mem::take(&mut self.timed).into_vec();
+            timed.retain(|evt| evt.id != id);
+            self.timed = timed.into();
+        }
+
+        for handlers in self.untimed.values_mut() {
+            handlers.retain(|evt| evt.id != id);
+        }
+
+        self.cues.retain(|(cue_id, _, _)| *cue_id != id);
+    }
+
+    /// Registers a named cue point, to fire [`TrackEvent::Cue`] once the
+    /// track's position reaches `at`, returning its identifier.
+    ///
+    /// [`TrackEvent::Cue`]: TrackEvent::Cue
+    pub(crate) fn add_cue(&mut self, at: Duration, name: String) -> EventId {
+        let id = EventId::next();
+        self.insert_cue(id, at, name);
+        id
+    }
+
+    pub(crate) fn insert_cue(&mut self, id: EventId, at: Duration, name: String) {
+        self.cues.push((id, at, name));
+    }
+
+    /// Lists the timed events and cues which have yet to fire.
+    pub(crate) fn scheduled(&self) -> Vec<ScheduledAction> {
+        let events = self.timed.iter().map(|evt| ScheduledAction {
+            id: evt.id,
+            kind: ScheduledKind::Event {
+                event: evt.event,
+                fire_time: evt.fire_time,
+            },
+        });
+
+        let cues = self.cues.iter().map(|(id, at, name)| ScheduledAction {
+            id: *id,
+            kind: ScheduledKind::Cue {
+                at: *at,
+                name: name.clone(),
+            },
+        });
+
+        events.chain(cues).collect()
+    }
+
+    /// Removes and returns the names of all cues at or before `position`,
+    /// in the order they were added.
+    pub(crate) fn take_ready_cues(&mut self, position: Duration) -> Vec<String> {
+        if self.cues.iter().all(|(_, at, _)| *at > position) {
+            return vec![];
+        }
+
+        let (ready, pending) = std::mem::take(&mut self.cues)
+            .into_iter()
+            .partition(|(_, at, _)| *at <= position);
+        self.cues = pending;
+
+        ready.into_iter().map(|(_, _, name)| name).collect()
+    }
+
     /// Processes all events due up to and including `now`.
-    pub(crate) async fn process_timed(&mut self, now: Duration, ctx: EventContext<'_>) {
-        while let Some(evt) = self.timed.peek() {
-            if evt
-                .fire_time
-                .as_ref()
-                .expect("Timed event must have a fire_time.")
-                > &now
-            {
-                break;
-            }
-            let mut evt = self
-                .timed
-                .pop()
-                .expect("Can only succeed due to peek = Some(...).");
+    ///
+    /// Up to `concurrency` handlers are run at once.
+    pub(crate) async fn process_timed(
+        &mut self,
+        now: Duration,
+        ctx: EventContext<'_>,
+        concurrency: usize,
+    ) {
+        let mut ready = vec![];
+
+        while self.timed_event_ready(now) {
+            ready.push(
+                self.timed
+                    .pop()
+                    .expect("Can only succeed due to peek = Some(...)."),
+            );
+        }
+
+        if ready.is_empty() {
+            return;
+        }
+
+        let results = act_all(&ready, &ctx, concurrency).await;
 
+        for (mut evt, result) in ready.into_iter().zip(results) {
             let old_evt_type = evt.event;
-            if let Some(new_evt_type) = evt.action.act(&ctx).await {
+            if let Some(new_evt_type) = result {
                 evt.event = new_evt_type;
                 self.add_event(evt, now);
             } else if let Event::Periodic(d, _) = old_evt_type {
@@ -117,11 +217,14 @@ impl EventStore {
     }
 
     /// Processes all events attached to the given track event.
+    ///
+    /// Up to `concurrency` handlers are run at once.
     pub(crate) async fn process_untimed(
         &mut self,
         now: Duration,
         untimed_event: UntimedEvent,
         ctx: EventContext<'_>,
+        concurrency: usize,
     ) {
         // move a Vec in and out: not too expensive, but could be better.
         // Although it's obvious that moving an event out of one vec and into
@@ -129,28 +232,24 @@ impl EventStore {
         // convincing the compiler of this is non-trivial without making them dedicated
         // fields.
         let events = self.untimed.remove(&untimed_event);
-        if let Some(mut events) = events {
+        if let Some(events) = events {
+            let results = act_all(&events, &ctx, concurrency).await;
+
             // TODO: Possibly use tombstones to prevent realloc/memcpys?
             // i.e., never shrink array, replace ended tracks with <DEAD>,
             // maintain a "first-track" stack and freelist alongside.
-            let mut i = 0;
-            while i < events.len() {
-                let evt = &mut events[i];
+            let mut kept = Vec::with_capacity(events.len());
+            for (mut evt, result) in events.into_iter().zip(results) {
                 // Only remove/readd if the event type changes (i.e., Some AND new != old)
-                if let Some(new_evt_type) = evt.action.act(&ctx).await {
-                    if evt.event == new_evt_type {
-                        let mut evt = events.remove(i);
-
+                match result {
+                    Some(new_evt_type) if evt.event == new_evt_type => {
                         evt.event = new_evt_type;
                         self.add_event(evt, now);
-                    } else {
-                        i += 1;
-                    }
-                } else {
-                    i += 1;
-                };
+                    },
+                    _ => kept.push(evt),
+                }
             }
-            self.untimed.insert(untimed_event, events);
+            self.untimed.insert(untimed_event, kept);
         }
     }
 }
@@ -160,6 +259,8 @@ pub(crate) struct GlobalEvents {
     pub(crate) store: EventStore,
     pub(crate) time: Duration,
     pub(crate) awaiting_tick: HashMap<TrackEvent, Vec<usize>>,
+    /// Maximum number of handlers run at once for any single event.
+    pub(crate) concurrency: usize,
 }
 
 impl GlobalEvents {
@@ -168,7 +269,9 @@ impl GlobalEvents {
     }
 
     pub(crate) async fn fire_core_event(&mut self, evt: CoreEvent, ctx: EventContext<'_>) {
-        self.store.process_untimed(self.time, evt.into(), ctx).await;
+        self.store
+            .process_untimed(self.time, evt.into(), ctx, self.concurrency)
+            .await;
     }
 
     pub(crate) fn fire_track_event(&mut self, evt: TrackEvent, index: usize) {
@@ -193,7 +296,11 @@ impl GlobalEvents {
             let global_ctx: Vec<(&TrackState, &TrackHandle)> =
                 states.iter().zip(handles.iter()).collect();
             self.store
-                .process_timed(self.time, EventContext::Track(&global_ctx[..]))
+                .process_timed(
+                    self.time,
+                    EventContext::Track(&global_ctx[..]),
+                    self.concurrency,
+                )
                 .await;
         }
 
@@ -210,11 +317,50 @@ impl GlobalEvents {
                     .expect("Missing handle index for Tick (local timed).");
 
                 event_store
-                    .process_timed(state.play_time, EventContext::Track(&[(state, handle)]))
+                    .process_timed(
+                        state.play_time,
+                        EventContext::Track(&[(state, handle)]),
+                        self.concurrency,
+                    )
                     .await;
+
+                // Cues are removed once reached, so each fires only once
+                // even if the track later loops or seeks backwards.
+                for name in event_store.take_ready_cues(state.position) {
+                    let ctx = || {
+                        EventContext::Cue(context_data::CueData {
+                            name: &name,
+                            state,
+                            handle,
+                        })
+                    };
+
+                    event_store
+                        .process_untimed(
+                            state.position,
+                            TrackEvent::Cue.into(),
+                            ctx(),
+                            self.concurrency,
+                        )
+                        .await;
+                    self.store
+                        .process_untimed(self.time, TrackEvent::Cue.into(), ctx(), self.concurrency)
+                        .await;
+                }
             }
         }
 
+        self.fire_awaiting(events, states, handles).await;
+    }
+
+    /// Fires all untimed track events queued since the last tick,
+    /// without advancing time.
+    pub(crate) async fn fire_awaiting(
+        &mut self,
+        events: &mut [EventStore],
+        states: &mut [TrackState],
+        handles: &mut [TrackHandle],
+    ) {
         for (evt, indices) in self.awaiting_tick.iter() {
             let untimed = (*evt).into();
 
@@ -239,6 +385,7 @@ impl GlobalEvents {
                         state.position,
                         untimed,
                         EventContext::Track(&[(state, handle)]),
+                        self.concurrency,
                     )
                     .await;
             }
@@ -260,7 +407,12 @@ impl GlobalEvents {
                     .collect();
 
                 self.store
-                    .process_untimed(self.time, untimed, EventContext::Track(&global_ctx[..]))
+                    .process_untimed(
+                        self.time,
+                        untimed,
+                        EventContext::Track(&global_ctx[..]),
+                        self.concurrency,
+                    )
                     .await
             }
         }
@@ -271,3 +423,128 @@ impl GlobalEvents {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tracks::TrackHandle;
+    use std::sync::{Arc, Mutex};
+    use uuid::Uuid;
+
+    struct Recorder {
+        id: usize,
+        log: Arc<Mutex<Vec<usize>>>,
+    }
+
+    #[async_trait]
+    impl EventHandler for Recorder {
+        async fn act(&self, _ctx: &EventContext<'_>) -> Option<Event> {
+            self.log.lock().unwrap().push(self.id);
+            None
+        }
+    }
+
+    #[test]
+    fn same_event_handlers_fire_in_registration_order() {
+        let log = Arc::new(Mutex::new(vec![]));
+        let mut store = EventStore::new_local();
+
+        for id in 0..3 {
+            store.add_event(
+                EventData::new(
+                    Event::Track(TrackEvent::End),
+                    Recorder {
+                        id,
+                        log: log.clone(),
+                    },
+                ),
+                Duration::default(),
+            );
+        }
+
+        let (tx, _rx) = flume::unbounded();
+        let handle = TrackHandle::new(tx, false, Uuid::new_v4(), Default::default());
+        let state = TrackState::default();
+
+        futures::executor::block_on(store.process_untimed(
+            Duration::default(),
+            TrackEvent::End.into(),
+            EventContext::Track(&[(&state, &handle)]),
+            1,
+        ));
+
+        assert_eq!(*log.lock().unwrap(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn cues_are_taken_only_once() {
+        let mut store = EventStore::new_local();
+        store.add_cue(Duration::from_millis(40), "b".into());
+        store.add_cue(Duration::from_millis(20), "a".into());
+        store.add_cue(Duration::from_secs(1), "c".into());
+
+        assert!(store.take_ready_cues(Duration::from_millis(0)).is_empty());
+        assert_eq!(
+            store.take_ready_cues(Duration::from_millis(40)),
+            vec!["b", "a"]
+        );
+        assert!(store.take_ready_cues(Duration::from_millis(40)).is_empty());
+        assert_eq!(store.take_ready_cues(Duration::from_secs(2)), vec!["c"]);
+    }
+
+    #[test]
+    fn fast_track_reaches_cues_by_source_position() {
+        let log = Arc::new(Mutex::new(vec![]));
+        let mut store = EventStore::new_local();
+        store.add_cue(3 * TIMESTEP_LENGTH, "a".into());
+        store.add_event(
+            EventData::new(
+                Event::Track(TrackEvent::Cue),
+                Recorder {
+                    id: 0,
+                    log: log.clone(),
+                },
+            ),
+            Duration::default(),
+        );
+
+        let (tx, _rx) = flume::unbounded();
+        let mut events = vec![store];
+        let mut handles = vec![TrackHandle::new(
+            tx,
+            false,
+            Uuid::new_v4(),
+            Default::default(),
+        )];
+        let mut states = vec![TrackState {
+            playing: PlayMode::Play,
+            speed: 2.0,
+            ..Default::default()
+        }];
+
+        let mut global = GlobalEvents::default();
+        futures::executor::block_on(global.tick(&mut events, &mut states, &mut handles));
+
+        assert_eq!(states[0].position, 2 * TIMESTEP_LENGTH);
+        assert_eq!(states[0].play_time, TIMESTEP_LENGTH);
+        assert!(log.lock().unwrap().is_empty());
+
+        futures::executor::block_on(global.tick(&mut events, &mut states, &mut handles));
+
+        assert_eq!(states[0].position, 4 * TIMESTEP_LENGTH);
+        assert_eq!(states[0].play_time, 2 * TIMESTEP_LENGTH);
+        assert_eq!(*log.lock().unwrap(), vec![0]);
+    }
+
+    #[test]
+    fn cues_can_be_cancelled() {
+        let mut store = EventStore::new_local();
+        let a = store.add_cue(Duration::from_millis(20), "a".into());
+        store.add_cue(Duration::from_millis(40), "b".into());
+
+        store.remove_event(a);
+
+        assert_eq!(store.scheduled().len(), 1);
+        assert_eq!(store.take_ready_cues(Duration::from_secs(1)), vec!["b"]);
+    }
+}
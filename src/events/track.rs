@@ -22,4 +22,34 @@ pub enum TrackEvent {
     End,
     /// The attached track has looped.
     Loop,
+    /// The attached track's input failed while playing.
+    ///
+    /// An [`End`] event is fired alongside this event, as the
+    /// track is then removed from the driver.
+    ///
+    /// [`End`]: Self::End
+    Error,
+    /// The attached track has reached a cue point registered via
+    /// [`TrackHandle::add_cue`].
+    ///
+    /// Handlers receive [`EventContext::Cue`] rather than [`EventContext::Track`].
+    ///
+    /// [`TrackHandle::add_cue`]: crate::tracks::TrackHandle::add_cue
+    /// [`EventContext::Cue`]: super::EventContext::Cue
+    /// [`EventContext::Track`]: super::EventContext::Track
+    Cue,
+    /// The attached track has started playing an input which is being resampled
+    /// to 48kHz, as set by [`Input::with_sample_rate`].
+    ///
+    /// This fires once, alongside the track's first frame of audio. The source's rate
+    /// is available via [`Input::resampled_from`] or the track's metadata.
+    ///
+    /// [`Input::with_sample_rate`]: crate::input::Input::with_sample_rate
+    /// [`Input::resampled_from`]: crate::input::Input::resampled_from
+    Resampled,
+    /// The attached track's metadata has been updated with newly discovered
+    /// information, following a call to [`TrackHandle::refresh_metadata`].
+    ///
+    /// [`TrackHandle::refresh_metadata`]: crate::tracks::TrackHandle::refresh_metadata
+    MetadataUpdate,
 }
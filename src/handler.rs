@@ -5,7 +5,7 @@ use crate::{
     id::{ChannelId, GuildId, UserId},
     info::{ConnectionInfo, ConnectionProgress},
     join::*,
-    shards::{Shard, VoiceUpdate},
+    shards::{Shard, VoiceFlags, VoiceUpdate},
     Config,
 };
 use flume::Sender;
@@ -52,6 +52,8 @@ pub struct Call {
     /// Whether the current handler is set to mute voice connections.
     self_mute: bool,
     user_id: UserId,
+    /// Additional flags sent in voice state updates.
+    voice_flags: VoiceFlags,
     /// Will be set when a `Call` is made via the [`new`]
     /// method.
     ///
@@ -132,6 +134,7 @@ impl Call {
             self_deaf: false,
             self_mute: false,
             user_id,
+            voice_flags: Default::default(),
             ws,
         }
     }
@@ -388,6 +391,31 @@ impl Call {
         self.self_mute
     }
 
+    /// Sets additional, unofficial flags (such as `self_video`) to be sent in
+    /// this call's voice state updates.
+    ///
+    /// **Experimental**: see [`VoiceFlags`] for caveats.
+    ///
+    /// If there is no live voice connection, then this only acts as a settings
+    /// update for future connections.
+    ///
+    /// [`VoiceFlags`]: VoiceFlags
+    #[instrument(skip(self))]
+    pub async fn set_voice_flags(&mut self, flags: VoiceFlags) -> JoinResult<()> {
+        self.voice_flags = flags;
+
+        if self.connection.is_some() {
+            self.update().await
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns the additional flags sent in this call's voice state updates.
+    pub fn voice_flags(&self) -> VoiceFlags {
+        self.voice_flags
+    }
+
     /// Updates the voice server data.
     ///
     /// You should only need to use this if you initialized the `Call` via
@@ -447,11 +475,12 @@ impl Call {
     #[instrument(skip(self))]
     async fn update(&mut self) -> JoinResult<()> {
         if let Some(ws) = self.ws.as_mut() {
-            ws.update_voice_state(
+            ws.update_voice_state_with_flags(
                 self.guild_id,
                 self.connection.as_ref().map(|c| c.0.channel_id()),
                 self.self_deaf,
                 self.self_mute,
+                self.voice_flags,
             )
             .await
         } else {
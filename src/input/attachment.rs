@@ -0,0 +1,157 @@
+use super::{children_to_reader, error::AttachmentError, Codec, Container, Input, Metadata};
+use futures::StreamExt;
+use reqwest::{header::CONTENT_TYPE, Client};
+use std::process::{Command, Stdio};
+use tokio::{io::AsyncWriteExt, process::ChildStdin};
+use tracing::{debug, warn};
+use url::Url;
+
+/// Hosts from which [`attachment`] will fetch files.
+///
+/// [`attachment`]: attachment
+pub const ATTACHMENT_HOSTS: &[&str] = &["cdn.discordapp.com", "media.discordapp.net"];
+
+/// Streams an audio file uploaded to Discord through `ffmpeg`, creating an audio source.
+///
+/// The `url` must point to one of the [`ATTACHMENT_HOSTS`]. Before any audio is
+/// decoded, the response's `Content-Type` must match one of `allowed_types`, and
+/// its declared `Content-Length` must not exceed `max_bytes`. Each allowed type may be
+/// an exact MIME type (i.e., `"audio/mpeg"`) or a wildcard such as `"audio/*"`; an empty
+/// list allows any type.
+///
+/// The file is streamed into `ffmpeg` rather than buffered. If more than `max_bytes`
+/// are received regardless of the declared length, the download is abandoned and
+/// the track ends early.
+///
+/// This source is not seek-compatible. Requires the `"attachment"` feature.
+///
+/// [`ATTACHMENT_HOSTS`]: ATTACHMENT_HOSTS
+pub async fn attachment(
+    url: &str,
+    max_bytes: u64,
+    allowed_types: &[&str],
+) -> Result<Input, AttachmentError> {
+    let url = Url::parse(url).map_err(AttachmentError::Url)?;
+
+    let host = url.host_str().unwrap_or_default();
+    if url.scheme() != "https" || !ATTACHMENT_HOSTS.contains(&host) {
+        return Err(AttachmentError::UntrustedHost(url));
+    }
+
+    let response = Client::new()
+        .get(url)
+        .send()
+        .await
+        .map_err(AttachmentError::Http)?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(AttachmentError::Status(status.as_u16()));
+    }
+
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+
+    if !allowed_types.is_empty()
+        && !content_type.as_deref().map_or(false, |ct| {
+            allowed_types
+                .iter()
+                .any(|allowed| mime_matches(allowed, ct))
+        })
+    {
+        return Err(AttachmentError::ContentType(content_type));
+    }
+
+    let declared = response.content_length();
+    if let Some(declared) = declared.filter(|len| *len > max_bytes) {
+        return Err(AttachmentError::TooLarge {
+            max_bytes,
+            declared,
+        });
+    }
+
+    let mut child = Command::new("ffmpeg")
+        .args(["-i", "pipe:0"])
+        .args([
+            "-f",
+            "f32le",
+            "-ac",
+            "2",
+            "-ar",
+            "48000",
+            "-acodec",
+            "pcm_f32le",
+            "-",
+        ])
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(AttachmentError::Io)?;
+
+    let stdin = child
+        .stdin
+        .take()
+        .map(ChildStdin::from_std)
+        .transpose()
+        .map_err(AttachmentError::Io)?
+        .expect("Stdin was requested as piped.");
+
+    tokio::spawn(async move {
+        let mut stdin = stdin;
+        let mut body = response.bytes_stream();
+        let mut received = 0u64;
+
+        while let Some(chunk) = body.next().await {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    debug!("Attachment download failed: {:?}", e);
+                    break;
+                },
+            };
+
+            received += chunk.len() as u64;
+            if received > max_bytes {
+                warn!(
+                    "Attachment exceeded {} bytes: abandoning download.",
+                    max_bytes
+                );
+                break;
+            }
+
+            if stdin.write_all(&chunk).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let metadata = Metadata {
+        channels: Some(2),
+        sample_rate: Some(48_000),
+        ..Default::default()
+    };
+
+    Ok(Input::new(
+        true,
+        children_to_reader::<f32>(vec![child]),
+        Codec::FloatPcm,
+        Container::Raw,
+        Some(metadata),
+    ))
+}
+
+/// Returns whether the MIME type `ty` (ignoring any parameters) matches `allowed`.
+fn mime_matches(allowed: &str, ty: &str) -> bool {
+    let essence = ty.split(';').next().unwrap_or_default().trim();
+
+    match allowed.strip_suffix("/*") {
+        Some(top) => essence
+            .split_once('/')
+            .map_or(false, |(ty_top, _)| ty_top.eq_ignore_ascii_case(top)),
+        None => essence.eq_ignore_ascii_case(allowed),
+    }
+}
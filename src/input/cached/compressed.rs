@@ -1,4 +1,4 @@
-use super::{apply_length_hint, compressed_cost_per_sec, default_config};
+use super::{apply_length_hint, compressed_cost_per_sec, default_config, CacheLimit, MemoryConfig};
 use crate::{
     constants::*,
     input::{
@@ -54,6 +54,7 @@ pub struct Compressed {
     pub metadata: Metadata,
     /// Stereo-ness of the captured source.
     pub stereo: bool,
+    limit: Option<CacheLimit>,
 }
 
 impl Compressed {
@@ -74,6 +75,31 @@ impl Compressed {
     /// [`Input`]: Input
     /// [`Metadata::duration`]: crate::input::Metadata::duration
     pub fn with_config(source: Input, bitrate: Bitrate, config: Option<Config>) -> Result<Self> {
+        Self::with_memory_config(
+            source,
+            bitrate,
+            MemoryConfig {
+                streamcatcher: config,
+                max_bytes: None,
+            },
+        )
+    }
+
+    /// Wrap an existing [`Input`] with an in-memory store, compressed using Opus,
+    /// optionally limiting the size of the store.
+    ///
+    /// Fails with [`Error::CacheLimit`] if `source`'s [`Metadata::duration`] shows that it
+    /// cannot fit within [`MemoryConfig::max_bytes`].
+    ///
+    /// [`Input`]: Input
+    /// [`Error::CacheLimit`]: Error::CacheLimit
+    /// [`Metadata::duration`]: crate::input::Metadata::duration
+    /// [`MemoryConfig::max_bytes`]: MemoryConfig::max_bytes
+    pub fn with_memory_config(
+        source: Input,
+        bitrate: Bitrate,
+        mem_config: MemoryConfig,
+    ) -> Result<Self> {
         let channels = if source.stereo {
             Channels::Stereo
         } else {
@@ -83,7 +109,7 @@ impl Compressed {
 
         encoder.set_bitrate(bitrate)?;
 
-        Self::with_encoder(source, encoder, config)
+        Self::build(source, encoder, mem_config)
     }
 
     /// Wrap an existing [`Input`] with an in-memory store, compressed using a user-defined
@@ -95,16 +121,30 @@ impl Compressed {
     /// [`Input`]: Input
     /// [`new`]: Compressed::new
     pub fn with_encoder(
-        mut source: Input,
+        source: Input,
         encoder: OpusEncoder,
         config: Option<Config>,
     ) -> Result<Self> {
+        Self::build(
+            source,
+            encoder,
+            MemoryConfig {
+                streamcatcher: config,
+                max_bytes: None,
+            },
+        )
+    }
+
+    fn build(mut source: Input, encoder: OpusEncoder, mem_config: MemoryConfig) -> Result<Self> {
         let bitrate = encoder.bitrate()?;
         let cost_per_sec = compressed_cost_per_sec(bitrate);
         let stereo = source.stereo;
         let metadata = source.metadata.take();
+        mem_config.check_duration(metadata.duration, cost_per_sec)?;
 
-        let mut config = config.unwrap_or_else(|| default_config(cost_per_sec));
+        let mut config = mem_config
+            .streamcatcher
+            .unwrap_or_else(|| default_config(cost_per_sec));
 
         // apply length hint.
         if config.length_hint.is_none() {
@@ -113,14 +153,20 @@ impl Compressed {
             }
         }
 
+        let limit = mem_config.max_bytes.map(CacheLimit::new);
+
         let raw = config
-            .build_tx(Box::new(source), OpusCompressor::new(encoder, stereo))
+            .build_tx(
+                Box::new(source),
+                OpusCompressor::new(encoder, stereo, limit.clone()),
+            )
             .map_err(Error::Streamcatcher)?;
 
         Ok(Self {
             raw,
             metadata,
             stereo,
+            limit,
         })
     }
 
@@ -131,8 +177,16 @@ impl Compressed {
             raw: self.raw.new_handle(),
             metadata: self.metadata.clone(),
             stereo: self.stereo,
+            limit: self.limit.clone(),
         }
     }
+
+    /// Returns whether the source was cut short by reaching [`MemoryConfig::max_bytes`].
+    ///
+    /// [`MemoryConfig::max_bytes`]: MemoryConfig::max_bytes
+    pub fn limit_exceeded(&self) -> bool {
+        self.limit.as_ref().map_or(false, CacheLimit::exceeded)
+    }
 }
 
 impl From<Compressed> for Input {
@@ -162,16 +216,20 @@ pub struct OpusCompressor {
     stereo_input: bool,
     frame_pos: usize,
     audio_bytes: AtomicUsize,
+    limit: Option<CacheLimit>,
+    stored_bytes: usize,
 }
 
 impl OpusCompressor {
-    fn new(encoder: OpusEncoder, stereo_input: bool) -> Self {
+    fn new(encoder: OpusEncoder, stereo_input: bool, limit: Option<CacheLimit>) -> Self {
         Self {
             encoder,
             last_frame: Vec::with_capacity(4000),
             stereo_input,
             frame_pos: 0,
             audio_bytes: Default::default(),
+            limit,
+            stored_bytes: 0,
         }
     }
 }
@@ -227,6 +285,13 @@ where
                             trace!("Next packet to write has {:?}", pkt_len);
                             self.frame_pos = 0;
                             self.last_frame.truncate(pkt_len);
+
+                            self.stored_bytes += output_start + pkt_len;
+                            if let Some(Err(e)) =
+                                self.limit.as_ref().map(|l| l.check(self.stored_bytes))
+                            {
+                                out = Some(Err(e));
+                            }
                             break;
                         },
                         Err(OpusError::Opus(OpusErrorCode::BufferTooSmall)) => {
@@ -0,0 +1,171 @@
+use crate::input::error::{CacheLimitExceeded, Error, Result};
+use parking_lot::Mutex;
+use std::{
+    io::{Error as IoError, ErrorKind as IoErrorKind, Read, Result as IoResult, Seek, SeekFrom},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use streamcatcher::Config;
+use symphonia_core::io::MediaSource;
+
+/// Options for building a [`Memory`] or [`Compressed`] cache.
+///
+/// [`Memory`]: super::Memory
+/// [`Compressed`]: super::Compressed
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct MemoryConfig {
+    /// Configuration of the underlying [`streamcatcher`] store.
+    ///
+    /// Defaults to `None`, using [`default_config`].
+    ///
+    /// [`streamcatcher`]: https://docs.rs/streamcatcher/0.1.0/streamcatcher/struct.Config.html
+    /// [`default_config`]: super::default_config
+    pub streamcatcher: Option<Config>,
+    /// Maximum number of bytes the cache may hold, if any.
+    ///
+    /// Sources whose [`Metadata::duration`] implies a larger cache are rejected with
+    /// [`Error::CacheLimit`] when the cache is created. Otherwise, reads which would
+    /// exceed the limit fail with a [`CacheLimitExceeded`] error, ending the track.
+    ///
+    /// Defaults to `None`, placing no limit on the size of the cache.
+    ///
+    /// [`Metadata::duration`]: crate::input::Metadata::duration
+    /// [`Error::CacheLimit`]: crate::input::error::Error::CacheLimit
+    /// [`CacheLimitExceeded`]: crate::input::error::CacheLimitExceeded
+    pub max_bytes: Option<usize>,
+}
+
+impl MemoryConfig {
+    /// Creates a configuration with no size limit, using the default store configuration.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the configuration of the underlying store.
+    pub fn streamcatcher(mut self, streamcatcher: Config) -> Self {
+        self.streamcatcher = Some(streamcatcher);
+        self
+    }
+
+    /// Sets the maximum number of bytes the cache may hold.
+    pub fn max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Rejects sources whose expected size is known to exceed the limit.
+    pub(crate) fn check_duration(
+        &self,
+        duration: Option<Duration>,
+        cost_per_sec: usize,
+    ) -> Result<()> {
+        match (self.max_bytes, duration) {
+            (Some(max_bytes), Some(duration))
+                if duration.as_secs_f64() * cost_per_sec as f64 > max_bytes as f64 =>
+                Err(Error::CacheLimit { max_bytes }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Tracks how much of a cache's limit has been used.
+#[derive(Clone, Debug)]
+pub(crate) struct CacheLimit {
+    max_bytes: usize,
+    exceeded: Arc<AtomicBool>,
+}
+
+impl CacheLimit {
+    pub(crate) fn new(max_bytes: usize) -> Self {
+        Self {
+            max_bytes,
+            exceeded: Default::default(),
+        }
+    }
+
+    /// Returns whether `stored` bytes fit within this limit, recording if not.
+    pub(crate) fn check(&self, stored: usize) -> IoResult<()> {
+        if stored > self.max_bytes {
+            Err(self.exceed())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn exceed(&self) -> IoError {
+        self.exceeded.store(true, Ordering::Release);
+
+        IoError::new(
+            IoErrorKind::Other,
+            CacheLimitExceeded {
+                max_bytes: self.max_bytes,
+            },
+        )
+    }
+
+    pub(crate) fn exceeded(&self) -> bool {
+        self.exceeded.load(Ordering::Acquire)
+    }
+}
+
+/// Source wrapper which fails reads once more than a [`CacheLimit`] has been read.
+pub(crate) struct LimitedSource<R> {
+    // `MediaSource` requires `Sync`, which `Reader` need not be.
+    inner: Mutex<R>,
+    limit: CacheLimit,
+    read: usize,
+}
+
+impl<R> LimitedSource<R> {
+    pub(crate) fn new(inner: R, limit: CacheLimit) -> Self {
+        Self {
+            inner: Mutex::new(inner),
+            limit,
+            read: 0,
+        }
+    }
+}
+
+impl<R: Read> Read for LimitedSource<R> {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        let remaining = self.limit.max_bytes - self.read;
+
+        if remaining == 0 {
+            // A source which ends exactly at the limit still fits.
+            let mut probe = [0u8; 1];
+            return match self.inner.get_mut().read(&mut probe)? {
+                0 => Ok(0),
+                _ => Err(self.limit.exceed()),
+            };
+        }
+
+        let len = buf.len().min(remaining);
+        let len = self.inner.get_mut().read(&mut buf[..len])?;
+
+        self.read += len;
+        Ok(len)
+    }
+}
+
+impl<R> Seek for LimitedSource<R> {
+    fn seek(&mut self, _pos: SeekFrom) -> IoResult<u64> {
+        Err(IoError::new(
+            IoErrorKind::InvalidInput,
+            "Seeking not supported on Reader of this type.",
+        ))
+    }
+}
+
+impl<R: Read + Send> MediaSource for LimitedSource<R> {
+    fn is_seekable(&self) -> bool {
+        false
+    }
+
+    fn byte_len(&self) -> Option<u64> {
+        None
+    }
+}
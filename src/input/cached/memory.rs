@@ -1,4 +1,11 @@
-use super::{apply_length_hint, default_config, raw_cost_per_sec};
+use super::{
+    apply_length_hint,
+    default_config,
+    raw_cost_per_sec,
+    CacheLimit,
+    LimitedSource,
+    MemoryConfig,
+};
 use crate::input::{
     error::{Error, Result},
     CodecType,
@@ -40,6 +47,7 @@ pub struct Memory {
     pub stereo: bool,
     /// Framing mechanism for the inner bytestore.
     pub container: Container,
+    limit: Option<CacheLimit>,
 }
 
 impl Memory {
@@ -58,15 +66,38 @@ impl Memory {
     ///
     /// [`Input`]: Input
     /// [`Metadata::duration`]: crate::input::Metadata::duration
-    pub fn with_config(mut source: Input, config: Option<Config>) -> Result<Self> {
+    pub fn with_config(source: Input, config: Option<Config>) -> Result<Self> {
+        Self::with_memory_config(
+            source,
+            MemoryConfig {
+                streamcatcher: config,
+                max_bytes: None,
+            },
+        )
+    }
+
+    /// Wrap an existing [`Input`] with an in-memory store with the same codec and framing,
+    /// optionally limiting the size of the store.
+    ///
+    /// Fails with [`Error::CacheLimit`] if `source`'s [`Metadata::duration`] shows that it
+    /// cannot fit within [`MemoryConfig::max_bytes`].
+    ///
+    /// [`Input`]: Input
+    /// [`Error::CacheLimit`]: Error::CacheLimit
+    /// [`Metadata::duration`]: crate::input::Metadata::duration
+    /// [`MemoryConfig::max_bytes`]: MemoryConfig::max_bytes
+    pub fn with_memory_config(mut source: Input, mem_config: MemoryConfig) -> Result<Self> {
         let stereo = source.stereo;
         let kind = (&source.kind).into();
         let container = source.container;
         let metadata = source.metadata.take();
 
         let cost_per_sec = raw_cost_per_sec(stereo);
+        mem_config.check_duration(metadata.duration, cost_per_sec)?;
 
-        let mut config = config.unwrap_or_else(|| default_config(cost_per_sec));
+        let mut config = mem_config
+            .streamcatcher
+            .unwrap_or_else(|| default_config(cost_per_sec));
 
         // apply length hint.
         if config.length_hint.is_none() {
@@ -75,8 +106,15 @@ impl Memory {
             }
         }
 
+        let limit = mem_config.max_bytes.map(CacheLimit::new);
+        let reader = match &limit {
+            Some(limit) =>
+                Reader::Extension(Box::new(LimitedSource::new(source.reader, limit.clone()))),
+            None => source.reader,
+        };
+
         let raw = config
-            .build(Box::new(source.reader))
+            .build(Box::new(reader))
             .map_err(Error::Streamcatcher)?;
 
         Ok(Self {
@@ -85,6 +123,7 @@ impl Memory {
             kind,
             stereo,
             container,
+            limit,
         })
     }
 
@@ -97,8 +136,16 @@ impl Memory {
             kind: self.kind,
             stereo: self.stereo,
             container: self.container,
+            limit: self.limit.clone(),
         }
     }
+
+    /// Returns whether the source was cut short by reaching [`MemoryConfig::max_bytes`].
+    ///
+    /// [`MemoryConfig::max_bytes`]: MemoryConfig::max_bytes
+    pub fn limit_exceeded(&self) -> bool {
+        self.limit.as_ref().map_or(false, CacheLimit::exceeded)
+    }
 }
 
 impl TryFrom<Memory> for Input {
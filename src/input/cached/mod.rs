@@ -3,11 +3,13 @@
 
 mod compressed;
 mod hint;
+mod limit;
 mod memory;
 #[cfg(test)]
 mod tests;
 
-pub use self::{compressed::*, hint::*, memory::*};
+pub(crate) use self::limit::{CacheLimit, LimitedSource};
+pub use self::{compressed::*, hint::*, limit::MemoryConfig, memory::*};
 
 use crate::constants::*;
 use crate::input::utils;
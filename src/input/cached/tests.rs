@@ -1,7 +1,7 @@
 use super::*;
 use crate::{
     constants::*,
-    input::{error::Error, Codec, Container, Input},
+    input::{error::Error, Codec, Container, Input, Metadata},
     test_utils::*,
 };
 use audiopus::{coder::Decoder, Bitrate, Channels, SampleRate};
@@ -9,6 +9,7 @@ use byteorder::{LittleEndian, ReadBytesExt};
 use std::{
     convert::TryInto,
     io::{Cursor, Read},
+    time::Duration,
 };
 
 #[tokio::test]
@@ -62,6 +63,48 @@ fn compressed_triggers_valid_passthrough() {
         .unwrap();
 }
 
+#[test]
+fn memory_fails_reads_past_limit() {
+    let data = make_sine(50 * MONO_FRAME_SIZE, true);
+    let input = Input::new(
+        true,
+        data.clone().into(),
+        Codec::FloatPcm,
+        Container::Raw,
+        None,
+    );
+
+    let memory =
+        Memory::with_memory_config(input, MemoryConfig::new().max_bytes(data.len() / 2)).unwrap();
+    let mut input: Input = memory.new_handle().try_into().unwrap();
+
+    let mut out_buf = vec![];
+    assert!(input.read_to_end(&mut out_buf).is_err());
+    assert!(memory.limit_exceeded());
+}
+
+#[test]
+fn memory_rejects_sources_known_to_exceed_limit() {
+    let data = make_sine(50 * MONO_FRAME_SIZE, true);
+    let metadata = Metadata {
+        duration: Some(Duration::from_secs(1)),
+        ..Default::default()
+    };
+    let input = Input::new(
+        true,
+        data.into(),
+        Codec::FloatPcm,
+        Container::Raw,
+        Some(metadata),
+    );
+
+    let result = Memory::with_memory_config(input, MemoryConfig::new().max_bytes(1_000));
+    assert!(matches!(
+        result,
+        Err(Error::CacheLimit { max_bytes: 1_000 })
+    ));
+}
+
 fn one_s_compressed_sine(stereo: bool) -> Compressed {
     let data = make_sine(50 * MONO_FRAME_SIZE, stereo);
 
@@ -127,6 +127,8 @@ impl From<DcaMetadata> for Metadata {
             .map(|mut m| (m.title.take(), m.artist.take()))
             .unwrap_or_else(|| (None, None));
 
+        let source_url = d.origin.take().and_then(|mut o| o.url.take());
+
         let channels = Some(d.opus.channels);
         let sample_rate = Some(d.opus.sample_rate);
 
@@ -136,8 +138,35 @@ impl From<DcaMetadata> for Metadata {
 
             channels,
             sample_rate,
+            source_url,
 
             ..Default::default()
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dca_metadata_populates_fields() {
+        let raw = br#"{
+            "dca": {"version": 1, "tool": {"name": "dca", "version": "1.0.0", "url": "", "author": ""}},
+            "opus": {"mode": "voip", "sample_rate": 48000, "frame_size": 960, "abr": 64, "vbr": 1, "channels": 2},
+            "info": {"title": "Song", "artist": "Band"},
+            "origin": {"url": "https://example.com/song.mp3"}
+        }"#;
+
+        let metadata: Metadata = serde_json::from_slice::<DcaMetadata>(raw).unwrap().into();
+
+        assert_eq!(metadata.track.as_deref(), Some("Song"));
+        assert_eq!(metadata.artist.as_deref(), Some("Band"));
+        assert_eq!(metadata.channels, Some(2));
+        assert_eq!(metadata.sample_rate, Some(48000));
+        assert_eq!(
+            metadata.source_url.as_deref(),
+            Some("https://example.com/song.mp3")
+        );
+    }
+}
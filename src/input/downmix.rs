@@ -0,0 +1,306 @@
+//! Folding of multichannel (e.g., 5.1) sources into the mixer's stereo.
+
+use super::Reader;
+use byteorder::{ByteOrder, LittleEndian};
+use std::{
+    f32::consts::FRAC_1_SQRT_2,
+    fmt::{Debug, Error as FormatError, Formatter},
+    io::{Error as IoError, ErrorKind as IoErrorKind, Read, Result as IoResult, Seek, SeekFrom},
+    mem,
+    result::Result as StdResult,
+};
+
+const OUT_FRAME_LEN: usize = 2 * mem::size_of::<f32>();
+
+/// Arrangement of the audio channels in a source.
+///
+/// Multichannel layouts list their channels in the order used by `ffmpeg` and WAV files.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum ChannelLayout {
+    /// One channel.
+    Mono,
+    /// Two channels: front left, front right.
+    Stereo,
+    /// Four channels: front left, front right, back left, back right.
+    Quad,
+    /// Six channels: front left, front right, front centre, LFE, surround left,
+    /// surround right.
+    Surround51,
+    /// Eight channels: front left, front right, front centre, LFE, back left,
+    /// back right, side left, side right.
+    Surround71,
+}
+
+impl ChannelLayout {
+    /// Returns the standard layout having `channels` channels, if there is one.
+    pub fn from_channels(channels: u8) -> Option<Self> {
+        match channels {
+            1 => Some(Self::Mono),
+            2 => Some(Self::Stereo),
+            4 => Some(Self::Quad),
+            6 => Some(Self::Surround51),
+            8 => Some(Self::Surround71),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of channels in this layout.
+    pub fn channels(self) -> usize {
+        match self {
+            Self::Mono => 1,
+            Self::Stereo => 2,
+            Self::Quad => 4,
+            Self::Surround51 => 6,
+            Self::Surround71 => 8,
+        }
+    }
+
+    /// Returns whether this layout must be downmixed to be played in stereo.
+    pub fn is_surround(self) -> bool {
+        self.channels() > 2
+    }
+}
+
+impl Default for ChannelLayout {
+    fn default() -> Self {
+        Self::Stereo
+    }
+}
+
+/// Gains used to fold the channels of a surround source into stereo.
+///
+/// Each front channel is kept in its own side, while the centre channel is added
+/// to both sides at `center` gain, each surround (side or back) channel to its
+/// own side at `surround` gain, and the LFE channel to both sides at `lfe` gain.
+///
+/// Defaults to the ITU-R BS.775 downmix: centre and surround channels at -3dB,
+/// with the LFE channel discarded.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct DownmixMatrix {
+    /// Gain applied to the centre channel in each side.
+    pub center: f32,
+    /// Gain applied to the surround (side or back) channels.
+    pub surround: f32,
+    /// Gain applied to the LFE channel in each side.
+    pub lfe: f32,
+}
+
+impl DownmixMatrix {
+    /// Creates the default (ITU-R BS.775) downmix.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the gain of the centre channel.
+    pub fn center(mut self, gain: f32) -> Self {
+        self.center = gain;
+        self
+    }
+
+    /// Sets the gain of the surround channels.
+    pub fn surround(mut self, gain: f32) -> Self {
+        self.surround = gain;
+        self
+    }
+
+    /// Sets the gain of the LFE channel.
+    pub fn lfe(mut self, gain: f32) -> Self {
+        self.lfe = gain;
+        self
+    }
+
+    /// Folds one frame of `layout` audio into a `(left, right)` pair.
+    fn fold(&self, layout: ChannelLayout, frame: &[f32]) -> (f32, f32) {
+        match layout {
+            ChannelLayout::Mono => (frame[0], frame[0]),
+            ChannelLayout::Stereo => (frame[0], frame[1]),
+            ChannelLayout::Quad => (
+                frame[0] + self.surround * frame[2],
+                frame[1] + self.surround * frame[3],
+            ),
+            ChannelLayout::Surround51 | ChannelLayout::Surround71 => {
+                let shared = self.center * frame[2] + self.lfe * frame[3];
+                let (mut left, mut right) = (frame[0] + shared, frame[1] + shared);
+
+                for pair in frame[4..].chunks_exact(2) {
+                    left += self.surround * pair[0];
+                    right += self.surround * pair[1];
+                }
+
+                (left, right)
+            },
+        }
+    }
+}
+
+impl Default for DownmixMatrix {
+    fn default() -> Self {
+        Self {
+            center: FRAC_1_SQRT_2,
+            surround: FRAC_1_SQRT_2,
+            lfe: 0.0,
+        }
+    }
+}
+
+/// A source of interleaved multichannel PCM audio, folded into stereo `f32`
+/// samples as it is read.
+///
+/// Created via [`Input::with_channel_layout`].
+///
+/// Supports seeking if the inner source does.
+///
+/// [`Input::with_channel_layout`]: super::Input::with_channel_layout
+pub struct Downmix {
+    inner: Reader,
+    layout: ChannelLayout,
+    matrix: DownmixMatrix,
+    /// Whether the inner source holds `f32` (rather than `i16`) samples.
+    float: bool,
+    /// Bytes of an incomplete source frame.
+    partial: Vec<u8>,
+    /// Downmixed bytes not yet returned to the reader.
+    pending: Vec<u8>,
+}
+
+impl Downmix {
+    pub(crate) fn new(inner: Reader, layout: ChannelLayout, float: bool) -> Self {
+        Self {
+            inner,
+            layout,
+            matrix: Default::default(),
+            float,
+            partial: Vec::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Returns the layout of the inner source.
+    pub fn layout(&self) -> ChannelLayout {
+        self.layout
+    }
+
+    /// Returns the gains used to fold the inner source into stereo.
+    pub fn matrix(&self) -> DownmixMatrix {
+        self.matrix
+    }
+
+    pub(crate) fn set_matrix(&mut self, matrix: DownmixMatrix) {
+        self.matrix = matrix;
+    }
+
+    /// Returns whether the inner source supports seeking.
+    pub fn is_seekable(&self) -> bool {
+        self.inner.is_seekable()
+    }
+
+    pub(crate) fn inner(&self) -> &Reader {
+        &self.inner
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut Reader {
+        &mut self.inner
+    }
+
+    fn sample_len(&self) -> usize {
+        if self.float {
+            mem::size_of::<f32>()
+        } else {
+            mem::size_of::<i16>()
+        }
+    }
+
+    fn in_frame_len(&self) -> usize {
+        self.layout.channels() * self.sample_len()
+    }
+
+    /// Converts a byte count of the inner source into stereo `f32` output bytes.
+    fn to_output(&self, inner: u64) -> u64 {
+        (inner / self.in_frame_len() as u64) * OUT_FRAME_LEN as u64
+    }
+
+    fn downmix_into(&mut self, bytes: &[u8]) {
+        let sample_len = self.sample_len();
+        let frame_len = self.in_frame_len();
+        let mut frame = [0f32; 8];
+
+        for raw in bytes.chunks_exact(frame_len) {
+            for (sample, raw) in frame.iter_mut().zip(raw.chunks_exact(sample_len)) {
+                *sample = if self.float {
+                    LittleEndian::read_f32(raw)
+                } else {
+                    f32::from(LittleEndian::read_i16(raw)) / 32768.0
+                };
+            }
+
+            let (left, right) = self
+                .matrix
+                .fold(self.layout, &frame[..self.layout.channels()]);
+            let mut out = [0u8; OUT_FRAME_LEN];
+            LittleEndian::write_f32(&mut out[..4], left);
+            LittleEndian::write_f32(&mut out[4..], right);
+            self.pending.extend_from_slice(&out);
+        }
+    }
+}
+
+impl Read for Downmix {
+    fn read(&mut self, buffer: &mut [u8]) -> IoResult<usize> {
+        let frame_len = self.in_frame_len();
+
+        while self.pending.is_empty() {
+            let mut chunk = vec![0u8; frame_len * (1 + buffer.len() / OUT_FRAME_LEN)];
+            let len = match self.inner.read(&mut chunk) {
+                Ok(0) => return Ok(0),
+                Ok(len) => len,
+                Err(e) if e.kind() == IoErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+
+            self.partial.extend_from_slice(&chunk[..len]);
+            let whole = self.partial.len() - self.partial.len() % frame_len;
+            let bytes: Vec<u8> = self.partial.drain(..whole).collect();
+            self.downmix_into(&bytes);
+        }
+
+        let len = buffer.len().min(self.pending.len());
+        buffer[..len].copy_from_slice(&self.pending[..len]);
+        self.pending.drain(..len);
+
+        Ok(len)
+    }
+}
+
+impl Seek for Downmix {
+    fn seek(&mut self, pos: SeekFrom) -> IoResult<u64> {
+        let target = match pos {
+            SeekFrom::Start(pos) => pos,
+            _ =>
+                return Err(IoError::new(
+                    IoErrorKind::InvalidInput,
+                    "Downmixed sources only support seeking from the start.",
+                )),
+        };
+
+        let inner_target = (target / OUT_FRAME_LEN as u64) * self.in_frame_len() as u64;
+        let reached = self.inner.seek(SeekFrom::Start(inner_target))?;
+
+        self.partial.clear();
+        self.pending.clear();
+
+        Ok(self.to_output(reached))
+    }
+}
+
+impl Debug for Downmix {
+    fn fmt(&self, f: &mut Formatter<'_>) -> StdResult<(), FormatError> {
+        f.debug_struct("Downmix")
+            .field("inner", &self.inner)
+            .field("layout", &self.layout)
+            .field("matrix", &self.matrix)
+            .field("float", &self.float)
+            .finish()
+    }
+}
@@ -12,8 +12,22 @@ use streamcatcher::CatcherError;
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum Error {
+    /// A source was expected to exceed the [`max_bytes`] limit of a cached input.
+    ///
+    /// Callers may wish to retry using a streamed input.
+    ///
+    /// [`max_bytes`]: crate::input::cached::MemoryConfig::max_bytes
+    CacheLimit {
+        /// Configured limit on the size of the cache, in bytes.
+        max_bytes: usize,
+    },
     /// An error occurred while opening a new DCA source.
     Dca(DcaError),
+    /// An HTTP request for a remote file failed, or returned an error status.
+    ///
+    /// Requires the `"http"` feature.
+    #[cfg(feature = "http")]
+    Http(reqwest::Error),
     /// An error occurred while reading, or opening a file.
     Io(IoError),
     /// An error occurred while parsing JSON (i.e., during metadata/stereo detection).
@@ -72,7 +86,11 @@ impl From<OpusError> for Error {
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            Error::CacheLimit { max_bytes } =>
+                write!(f, "source exceeds cache limit of {} bytes", max_bytes),
             Error::Dca(_) => write!(f, "opening file DCA failed"),
+            #[cfg(feature = "http")]
+            Error::Http(e) => e.fmt(f),
             Error::Io(e) => e.fmt(f),
             Error::Json {
                 error: _,
@@ -93,7 +111,10 @@ impl fmt::Display for Error {
 impl StdError for Error {
     fn source(&self) -> Option<&(dyn StdError + 'static)> {
         match self {
+            Error::CacheLimit { .. } => None,
             Error::Dca(e) => Some(e),
+            #[cfg(feature = "http")]
+            Error::Http(e) => Some(e),
             Error::Io(e) => e.source(),
             Error::Json {
                 error,
@@ -111,6 +132,28 @@ impl StdError for Error {
     }
 }
 
+/// Error returned by reads from a cached input's source once the cache holds
+/// [`max_bytes`].
+///
+/// This is held within the [`io::Error`] returned by such reads, and causes the
+/// track playing the input to end with an error.
+///
+/// [`max_bytes`]: crate::input::cached::MemoryConfig::max_bytes
+/// [`io::Error`]: std::io::Error
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CacheLimitExceeded {
+    /// Configured limit on the size of the cache, in bytes.
+    pub max_bytes: usize,
+}
+
+impl fmt::Display for CacheLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cache limit of {} bytes exceeded", self.max_bytes)
+    }
+}
+
+impl StdError for CacheLimitExceeded {}
+
 /// An error returned from the [`dca`] method.
 ///
 /// [`dca`]: crate::input::dca
@@ -153,6 +196,104 @@ impl StdError for DcaError {
     }
 }
 
+/// An error returned from the [`Input::preview`] method.
+///
+/// [`Input::preview`]: crate::input::Input::preview
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum PreviewError {
+    /// The source could not be decoded.
+    Decode(IoError),
+    /// The source ended before producing any audio.
+    Empty,
+    /// Decoding did not finish within the given time.
+    Timeout,
+}
+
+impl fmt::Display for PreviewError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PreviewError::Decode(e) => e.fmt(f),
+            PreviewError::Empty => write!(f, "source produced no audio"),
+            PreviewError::Timeout => write!(f, "preview timed out"),
+        }
+    }
+}
+
+impl StdError for PreviewError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            PreviewError::Decode(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// An error returned from the [`attachment`] method.
+///
+/// [`attachment`]: crate::input::attachment
+#[cfg(feature = "attachment")]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum AttachmentError {
+    /// The given URL could not be parsed.
+    Url(url::ParseError),
+    /// The URL did not use HTTPS, or did not point to one of the [`ATTACHMENT_HOSTS`].
+    ///
+    /// [`ATTACHMENT_HOSTS`]: crate::input::ATTACHMENT_HOSTS
+    UntrustedHost(url::Url),
+    /// The request for the file failed.
+    Http(reqwest::Error),
+    /// The server replied with the given unsuccessful status code.
+    Status(u16),
+    /// The file's `Content-Type` (if any) was not one of the allowed types.
+    ContentType(Option<String>),
+    /// The file's declared `Content-Length` exceeded the maximum size.
+    TooLarge {
+        /// Maximum accepted size of the file, in bytes.
+        max_bytes: u64,
+        /// Size of the file declared by the server, in bytes.
+        declared: u64,
+    },
+    /// `ffmpeg` could not be started.
+    Io(IoError),
+}
+
+#[cfg(feature = "attachment")]
+impl fmt::Display for AttachmentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttachmentError::Url(e) => e.fmt(f),
+            AttachmentError::UntrustedHost(url) => write!(f, "untrusted attachment url: {}", url),
+            AttachmentError::Http(e) => e.fmt(f),
+            AttachmentError::Status(code) => write!(f, "attachment request failed: {}", code),
+            AttachmentError::ContentType(ty) =>
+                write!(f, "disallowed attachment content type: {:?}", ty),
+            AttachmentError::TooLarge {
+                max_bytes,
+                declared,
+            } => write!(
+                f,
+                "attachment of {} bytes exceeds limit of {} bytes",
+                declared, max_bytes
+            ),
+            AttachmentError::Io(e) => e.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "attachment")]
+impl StdError for AttachmentError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            AttachmentError::Url(e) => Some(e),
+            AttachmentError::Http(e) => Some(e),
+            AttachmentError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
 /// Convenience type for fallible return of [`Input`]s.
 ///
 /// [`Input`]: crate::input::Input
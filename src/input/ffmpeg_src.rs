@@ -1,6 +1,7 @@
 use super::{
     children_to_reader,
     error::{Error, Result},
+    ChannelLayout,
     Codec,
     Container,
     Input,
@@ -16,10 +17,14 @@ use tracing::debug;
 
 /// Opens an audio file through `ffmpeg` and creates an audio source.
 ///
+/// Quad, 5.1, and 7.1 files are downmixed to stereo by Songbird rather than `ffmpeg`,
+/// so that their downmix can be adjusted using [`Track::set_downmix`].
+///
 /// This source is not seek-compatible.
 /// If you need looping or track seeking, then consider using
 /// [`Restartable::ffmpeg`].
 ///
+/// [`Track::set_downmix`]: crate::tracks::Track::set_downmix
 /// [`Restartable::ffmpeg`]: crate::input::restartable::Restartable::ffmpeg
 pub async fn ffmpeg<P: AsRef<OsStr>>(path: P) -> Result<Input> {
     _ffmpeg(path.as_ref()).await
@@ -30,16 +35,25 @@ pub(crate) async fn _ffmpeg(path: &OsStr) -> Result<Input> {
     let is_stereo = is_stereo(path)
         .await
         .unwrap_or_else(|_e| (false, Default::default()));
-    let stereo_val = if is_stereo.0 { "2" } else { "1" };
+    // Surround sources keep their channels, to be folded into stereo by the mixer.
+    let surround = is_stereo
+        .1
+        .channels
+        .and_then(ChannelLayout::from_channels)
+        .filter(|layout| layout.is_surround());
+    let channels_val = match surround {
+        Some(layout) => layout.channels().to_string(),
+        None => (if is_stereo.0 { "2" } else { "1" }).to_string(),
+    };
 
-    _ffmpeg_optioned(
+    let input = _ffmpeg_optioned(
         path,
         &[],
         &[
             "-f",
             "s16le",
             "-ac",
-            stereo_val,
+            &channels_val,
             "-ar",
             "48000",
             "-acodec",
@@ -48,7 +62,12 @@ pub(crate) async fn _ffmpeg(path: &OsStr) -> Result<Input> {
         ],
         Some(is_stereo),
     )
-    .await
+    .await?;
+
+    Ok(match surround {
+        Some(layout) => input.with_channel_layout(layout),
+        None => input,
+    })
 }
 
 /// Opens an audio file through `ffmpeg` and creates an audio source, with
@@ -123,6 +142,21 @@ pub(crate) async fn _ffmpeg_optioned(
 }
 
 pub(crate) async fn is_stereo(path: &OsStr) -> Result<(bool, Metadata)> {
+    let value = ffprobe_json(path).await?;
+    let metadata = Metadata::from_ffprobe_json(&value);
+
+    debug!("FFprobe metadata {:?}", metadata);
+
+    if let Some(count) = metadata.channels {
+        Ok((count == 2, metadata))
+    } else {
+        Err(Error::Streams)
+    }
+}
+
+/// Runs `ffprobe` on `path`, returning its description of the file's format,
+/// streams, and chapters.
+pub(crate) async fn ffprobe_json(path: &OsStr) -> Result<Value> {
     let args = [
         "-v",
         "quiet",
@@ -130,6 +164,7 @@ pub(crate) async fn is_stereo(path: &OsStr) -> Result<(bool, Metadata)> {
         "json",
         "-show_format",
         "-show_streams",
+        "-show_chapters",
         "-i",
     ];
 
@@ -140,20 +175,10 @@ pub(crate) async fn is_stereo(path: &OsStr) -> Result<(bool, Metadata)> {
         .output()
         .await?;
 
-    let value: Value = serde_json::from_reader(&out.stdout[..]).map_err(|err| Error::Json {
+    serde_json::from_reader(&out.stdout[..]).map_err(|err| Error::Json {
         error: err,
         parsed_text: std::str::from_utf8(&out.stdout[..])
             .unwrap_or_default()
             .to_string(),
-    })?;
-
-    let metadata = Metadata::from_ffprobe_json(&value);
-
-    debug!("FFprobe metadata {:?}", metadata);
-
-    if let Some(count) = metadata.channels {
-        Ok((count == 2, metadata))
-    } else {
-        Err(Error::Streams)
-    }
+    })
 }
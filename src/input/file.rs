@@ -0,0 +1,94 @@
+//! Local file sources which can be read from several places at once.
+
+use parking_lot::Mutex;
+use std::{
+    fs::File,
+    io::{Error as IoError, ErrorKind as IoErrorKind, Read, Result as IoResult, Seek, SeekFrom},
+    sync::Arc,
+};
+
+/// A source contained in a local file, created via [`Reader::from_file`].
+///
+/// Each handle keeps its own read position, so that views made by
+/// [`new_handle`] can be played independently of one another without
+/// reopening the file.
+///
+/// Supports seeking.
+///
+/// [`Reader::from_file`]: super::Reader::from_file
+/// [`new_handle`]: FileSource::new_handle
+#[derive(Debug)]
+pub struct FileSource {
+    file: Arc<Mutex<File>>,
+    start: u64,
+    pos: u64,
+}
+
+impl FileSource {
+    /// Wraps `file`, treating its current position as the start of the stream.
+    ///
+    /// Returns the file unchanged if it is not a regular, seekable file
+    /// (such as a named pipe).
+    pub fn new(mut file: File) -> Result<Self, File> {
+        let is_file = file.metadata().map(|m| m.is_file()).unwrap_or(false);
+        let start = match file.stream_position() {
+            Ok(start) if is_file => start,
+            _ => return Err(file),
+        };
+
+        Ok(Self {
+            file: Arc::new(Mutex::new(file)),
+            start,
+            pos: start,
+        })
+    }
+
+    /// Creates a new handle onto the same file, whose read position starts
+    /// from the beginning of the stream.
+    pub fn new_handle(&self) -> Self {
+        Self {
+            file: self.file.clone(),
+            start: self.start,
+            pos: self.start,
+        }
+    }
+}
+
+impl Read for FileSource {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        let mut file = self.file.lock();
+
+        file.seek(SeekFrom::Start(self.pos))?;
+        let len = file.read(buf)?;
+        self.pos += len as u64;
+
+        Ok(len)
+    }
+}
+
+impl Seek for FileSource {
+    fn seek(&mut self, pos: SeekFrom) -> IoResult<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::Current(delta) => offset_by(self.pos, delta),
+            SeekFrom::End(delta) => offset_by(self.file.lock().metadata()?.len(), delta),
+        };
+
+        self.pos = target.ok_or_else(|| {
+            IoError::new(
+                IoErrorKind::InvalidInput,
+                "Cannot seek before the start of a file.",
+            )
+        })?;
+
+        Ok(self.pos)
+    }
+}
+
+fn offset_by(base: u64, delta: i64) -> Option<u64> {
+    if delta >= 0 {
+        base.checked_add(delta as u64)
+    } else {
+        base.checked_sub(delta.unsigned_abs())
+    }
+}
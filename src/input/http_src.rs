@@ -0,0 +1,342 @@
+//! Remote files served over HTTP(S), which seek using range requests.
+
+use super::{
+    children_to_reader,
+    error::{Error, Result},
+    is_stereo,
+    restartable::Restart,
+    Codec,
+    Container,
+    Input,
+    Metadata,
+};
+use async_trait::async_trait;
+use futures::StreamExt;
+use reqwest::{
+    header::{ACCEPT_RANGES, CONTENT_LENGTH, RANGE},
+    Client,
+    Response,
+    StatusCode,
+};
+use std::{
+    ffi::OsStr,
+    process::{Command, Stdio},
+    time::Duration,
+};
+use tokio::{io::AsyncWriteExt, process::ChildStdin};
+use tracing::debug;
+
+/// Byte range support advertised by a server in response to a `HEAD` request.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+struct RangeSupport {
+    /// Whether the server advertised `Accept-Ranges: bytes`.
+    ranges: bool,
+    /// Length of the file, in bytes.
+    len: Option<u64>,
+}
+
+impl RangeSupport {
+    /// Estimates the byte offset of `time` within a file lasting `duration`.
+    ///
+    /// This assumes a constant bitrate, so will be approximate for VBR audio.
+    /// Returns `None` if a range request cannot or need not be made.
+    fn offset_for(&self, time: Duration, duration: Option<Duration>) -> Option<u64> {
+        let len = self.len.filter(|_| self.ranges)?;
+        let duration = duration.filter(|d| !d.is_zero())?;
+
+        let offset = (len as f64 * time.as_secs_f64() / duration.as_secs_f64()) as u64;
+
+        if offset > 0 && offset < len {
+            Some(offset)
+        } else {
+            None
+        }
+    }
+}
+
+/// Asks the server whether it supports range requests for `url`.
+///
+/// Servers which reject `HEAD` requests are treated as lacking support.
+async fn range_support(client: &Client, url: &str) -> RangeSupport {
+    let response = match client.head(url).send().await {
+        Ok(response) if response.status().is_success() => response,
+        _ => return RangeSupport::default(),
+    };
+
+    let headers = response.headers();
+    let ranges = headers
+        .get(ACCEPT_RANGES)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.split(',').any(|unit| unit.trim() == "bytes"))
+        .unwrap_or(false);
+    let len = headers
+        .get(CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok());
+
+    RangeSupport { ranges, len }
+}
+
+/// Requests `url`, starting from byte `offset` if one is given.
+async fn fetch(client: &Client, url: &str, offset: Option<u64>) -> Result<Response> {
+    let mut request = client.get(url);
+    if let Some(offset) = offset {
+        request = request.header(RANGE, format!("bytes={}-", offset));
+    }
+
+    request
+        .send()
+        .await
+        .and_then(Response::error_for_status)
+        .map_err(Error::Http)
+}
+
+/// Recreates a remote file's stream after each seek.
+///
+/// When the server supports range requests, seeks fetch the remainder of the file
+/// from an estimated byte offset. Otherwise, the file is downloaded again from
+/// the start, and decoded audio up to the target is discarded.
+pub(crate) struct HttpRestarter {
+    client: Client,
+    url: String,
+    support: Option<RangeSupport>,
+    probed: Option<(bool, Metadata)>,
+}
+
+impl HttpRestarter {
+    pub(crate) fn new(url: String) -> Self {
+        Self {
+            client: Client::new(),
+            url,
+            support: None,
+            probed: None,
+        }
+    }
+
+    async fn probe(&mut self) -> Result<(bool, Metadata)> {
+        if self.probed.is_none() {
+            self.probed = Some(is_stereo(OsStr::new(&self.url)).await?);
+        }
+
+        Ok(self.probed.clone().expect("Probe result was just stored."))
+    }
+
+    async fn support(&mut self) -> RangeSupport {
+        if self.support.is_none() {
+            self.support = Some(range_support(&self.client, &self.url).await);
+        }
+
+        self.support.unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl Restart for HttpRestarter {
+    async fn call_restart(&mut self, time: Option<Duration>) -> Result<Input> {
+        let (stereo, metadata) = self.probe().await?;
+        let offset = match time {
+            Some(time) => self.support().await.offset_for(time, metadata.duration),
+            None => None,
+        };
+
+        let response = fetch(&self.client, &self.url, offset).await?;
+
+        // Servers may ignore the range, sending the whole file instead.
+        let ranged = offset.is_some() && response.status() == StatusCode::PARTIAL_CONTENT;
+        let skip = if ranged { None } else { time };
+
+        debug!(
+            "Fetching {} from byte {:?}, skipping {:?} of audio.",
+            self.url, offset, skip
+        );
+
+        stream_to_ffmpeg(response, stereo, skip, metadata)
+    }
+
+    async fn lazy_init(&mut self) -> Result<(Option<Metadata>, Codec, Container)> {
+        self.probe()
+            .await
+            .map(|(_stereo, metadata)| (Some(metadata), Codec::FloatPcm, Container::Raw))
+    }
+}
+
+/// Decodes a response body using `ffmpeg`, discarding the first `skip` of its audio.
+fn stream_to_ffmpeg(
+    response: Response,
+    stereo: bool,
+    skip: Option<Duration>,
+    metadata: Metadata,
+) -> Result<Input> {
+    let skip = skip.map(|t| format!("{:.3}", t.as_secs_f64()));
+    let pre_input_args = match &skip {
+        Some(ts) => vec!["-ss", ts],
+        None => vec![],
+    };
+
+    let mut child = Command::new("ffmpeg")
+        .args(pre_input_args)
+        .args(["-i", "pipe:0"])
+        .args([
+            "-f",
+            "f32le",
+            "-ac",
+            if stereo { "2" } else { "1" },
+            "-ar",
+            "48000",
+            "-acodec",
+            "pcm_f32le",
+            "-",
+        ])
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+
+    let stdin = child
+        .stdin
+        .take()
+        .map(ChildStdin::from_std)
+        .transpose()?
+        .expect("Stdin was requested as piped.");
+
+    tokio::spawn(async move {
+        let mut stdin = stdin;
+        let mut body = response.bytes_stream();
+
+        while let Some(chunk) = body.next().await {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    debug!("HTTP download failed: {:?}", e);
+                    break;
+                },
+            };
+
+            if stdin.write_all(&chunk).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    Ok(Input::new(
+        stereo,
+        children_to_reader::<f32>(vec![child]),
+        Codec::FloatPcm,
+        Container::Raw,
+        Some(metadata),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::{
+        io::AsyncReadExt,
+        net::{TcpListener, TcpStream},
+    };
+
+    #[test]
+    fn offset_is_estimated_from_duration() {
+        let support = RangeSupport {
+            ranges: true,
+            len: Some(1_000),
+        };
+        let duration = Some(Duration::from_secs(10));
+
+        assert_eq!(
+            support.offset_for(Duration::from_secs(5), duration),
+            Some(500)
+        );
+        assert_eq!(support.offset_for(Duration::ZERO, duration), None);
+        assert_eq!(support.offset_for(Duration::from_secs(11), duration), None);
+        assert_eq!(support.offset_for(Duration::from_secs(5), None), None);
+
+        let unranged = RangeSupport {
+            ranges: false,
+            ..support
+        };
+        assert_eq!(unranged.offset_for(Duration::from_secs(5), duration), None);
+    }
+
+    /// Answers one request on `listener` with `response`, returning the request's head.
+    async fn serve_once(listener: &TcpListener, response: &str) -> String {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let request = read_head(&mut stream).await;
+        stream.write_all(response.as_bytes()).await.unwrap();
+
+        request.to_ascii_lowercase()
+    }
+
+    async fn read_head(stream: &mut TcpStream) -> String {
+        let mut head = vec![];
+        while !head.ends_with(b"\r\n\r\n") {
+            head.push(stream.read_u8().await.unwrap());
+        }
+
+        String::from_utf8(head).unwrap()
+    }
+
+    #[tokio::test]
+    async fn seeks_request_remaining_bytes() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/audio", listener.local_addr().unwrap());
+        let client = Client::new();
+
+        let server = async {
+            let head = serve_once(
+                &listener,
+                "HTTP/1.1 200 OK\r\nAccept-Ranges: bytes\r\nContent-Length: 1000\r\nConnection: close\r\n\r\n",
+            )
+            .await;
+            let get = serve_once(
+                &listener,
+                "HTTP/1.1 206 Partial Content\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            )
+            .await;
+
+            (head, get)
+        };
+        let client_side = async {
+            let support = range_support(&client, &url).await;
+            let offset = support.offset_for(Duration::from_secs(3), Some(Duration::from_secs(10)));
+            let response = fetch(&client, &url, offset).await.unwrap();
+
+            (support, offset, response.status())
+        };
+
+        let ((head, get), (support, offset, status)) = tokio::join!(server, client_side);
+
+        assert!(head.starts_with("head /audio"));
+        assert_eq!(
+            support,
+            RangeSupport {
+                ranges: true,
+                len: Some(1_000)
+            }
+        );
+        assert_eq!(offset, Some(300));
+        assert!(get.starts_with("get /audio"));
+        assert!(get.contains("range: bytes=300-\r\n"));
+        assert_eq!(status, StatusCode::PARTIAL_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn servers_without_ranges_are_refetched_from_start() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/audio", listener.local_addr().unwrap());
+        let client = Client::new();
+
+        let server = serve_once(
+            &listener,
+            "HTTP/1.1 405 Method Not Allowed\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        );
+        let client_side = range_support(&client, &url);
+        let (_, support) = tokio::join!(server, client_side);
+
+        assert_eq!(support, RangeSupport::default());
+        assert_eq!(
+            support.offset_for(Duration::from_secs(3), Some(Duration::from_secs(10))),
+            None
+        );
+    }
+}
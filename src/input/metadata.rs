@@ -27,6 +27,11 @@ pub struct Metadata {
     /// The reported duration of this stream.
     pub duration: Option<Duration>,
     /// The sample rate of this stream.
+    ///
+    /// For sources which convert their audio while decoding, such as [`ffmpeg`],
+    /// this is the rate of the original file rather than of the decoded output.
+    ///
+    /// [`ffmpeg`]: crate::input::ffmpeg
     pub sample_rate: Option<u32>,
     /// The source url of this stream.
     pub source_url: Option<String>,
@@ -34,6 +39,51 @@ pub struct Metadata {
     pub title: Option<String>,
     /// The thumbnail url of this stream.
     pub thumbnail: Option<String>,
+    /// Chapters marked within this stream, in order of their start times.
+    ///
+    /// This is empty if the source has no chapter information.
+    pub chapters: Vec<Chapter>,
+}
+
+/// A named section of an [`Input`], such as a podcast segment or a track within a mix.
+///
+/// [`Input`]: crate::input::Input
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Chapter {
+    /// The title of this chapter, if one was given.
+    pub title: Option<String>,
+    /// The position at which this chapter begins.
+    pub start: Duration,
+    /// The position at which this chapter ends, if known.
+    pub end: Option<Duration>,
+}
+
+fn parse_chapters<'a>(
+    chapters: Option<&'a Value>,
+    time: impl Fn(&'a Value, &str) -> Option<f64>,
+    title: impl Fn(&'a Value) -> Option<&'a str>,
+) -> Vec<Chapter> {
+    let mut out: Vec<Chapter> = chapters
+        .and_then(Value::as_array)
+        .map(|chapters| {
+            chapters
+                .iter()
+                .filter_map(|chapter| {
+                    let start = time(chapter, "start_time")?;
+
+                    Some(Chapter {
+                        title: title(chapter).map(str::to_string),
+                        start: Duration::from_secs_f64(start.max(0.0)),
+                        end: time(chapter, "end_time").map(|t| Duration::from_secs_f64(t.max(0.0))),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    out.sort_by_key(|chapter| chapter.start);
+
+    out
 }
 
 impl Metadata {
@@ -91,6 +141,23 @@ impl Metadata {
             .and_then(|v| v.parse::<u64>().ok())
             .map(|v| v as u32);
 
+        // ffprobe reports times as strings, and chapter titles as tags.
+        let chapters = parse_chapters(
+            value.as_object().and_then(|m| m.get("chapters")),
+            |chapter, key| {
+                chapter
+                    .get(key)
+                    .and_then(Value::as_str)
+                    .and_then(|v| v.parse::<f64>().ok())
+            },
+            |chapter| {
+                chapter
+                    .get("tags")
+                    .and_then(|m| m.get("title"))
+                    .and_then(Value::as_str)
+            },
+        );
+
         Self {
             track,
             artist,
@@ -100,6 +167,7 @@ impl Metadata {
             start_time,
             duration,
             sample_rate,
+            chapters,
 
             ..Default::default()
         }
@@ -161,6 +229,12 @@ impl Metadata {
             .and_then(Value::as_str)
             .map(str::to_string);
 
+        let chapters = parse_chapters(
+            obj.and_then(|m| m.get("chapters")),
+            |chapter, key| chapter.get(key).and_then(Value::as_f64),
+            |chapter| chapter.get("title").and_then(Value::as_str),
+        );
+
         Self {
             track,
             artist,
@@ -173,11 +247,37 @@ impl Metadata {
             source_url,
             title,
             thumbnail,
+            chapters,
 
             ..Default::default()
         }
     }
 
+    /// Fills any fields which are unknown in `self` from `other`.
+    pub(crate) fn fill_from(&mut self, other: &Metadata) {
+        fn fill<T: Clone>(field: &mut Option<T>, other: &Option<T>) {
+            if field.is_none() {
+                *field = other.clone();
+            }
+        }
+
+        fill(&mut self.track, &other.track);
+        fill(&mut self.artist, &other.artist);
+        fill(&mut self.date, &other.date);
+        fill(&mut self.channels, &other.channels);
+        fill(&mut self.channel, &other.channel);
+        fill(&mut self.start_time, &other.start_time);
+        fill(&mut self.duration, &other.duration);
+        fill(&mut self.sample_rate, &other.sample_rate);
+        fill(&mut self.source_url, &other.source_url);
+        fill(&mut self.title, &other.title);
+        fill(&mut self.thumbnail, &other.thumbnail);
+
+        if self.chapters.is_empty() {
+            self.chapters = other.chapters.clone();
+        }
+    }
+
     /// Move all fields from a `Metadata` object into a new one.
     pub fn take(&mut self) -> Self {
         Self {
@@ -193,6 +293,7 @@ impl Metadata {
             source_url: self.source_url.take(),
             title: self.title.take(),
             thumbnail: self.thumbnail.take(),
+            chapters: std::mem::take(&mut self.chapters),
         }
     }
 }
@@ -7,6 +7,8 @@
 //!
 //! When used as a [`Read`], the output bytestream will be a floating-point
 //! PCM stream at 48kHz, matching the channel count of the input source.
+//! PCM bytestreams at other sample rates must be declared using
+//! [`Input::with_sample_rate`], which resamples them as they are read.
 //!
 //! ## Opus frame passthrough.
 //! Some sources, such as [`Compressed`] or the output of [`dca`], support
@@ -21,6 +23,7 @@
 //!  * and that track's volume is set to `1.0`.
 //!
 //! [`Input`]: Input
+//! [`Input::with_sample_rate`]: Input::with_sample_rate
 //! [`Reader`]: reader::Reader
 //! [`Container`]: Container
 //! [`Codec`]: Codec
@@ -28,28 +31,53 @@
 //! [`Compressed`]: cached::Compressed
 //! [`dca`]: dca()
 
+#[cfg(feature = "attachment")]
+mod attachment;
 pub mod cached;
 mod child;
 pub mod codec;
 mod container;
 mod dca;
+mod downmix;
 pub mod error;
 mod ffmpeg_src;
+mod file;
+#[cfg(feature = "http")]
+mod http_src;
 mod metadata;
+mod playlist;
+mod preview;
+mod probe;
+mod read_ahead;
 pub mod reader;
+mod reader_src;
+mod resample;
 pub mod restartable;
+mod seek_accuracy;
+mod silence;
 pub mod utils;
 mod ytdl_src;
 
+#[cfg(feature = "attachment")]
+pub use self::attachment::{attachment, ATTACHMENT_HOSTS};
 pub use self::{
     child::*,
     codec::{Codec, CodecType},
     container::{Container, Frame},
     dca::dca,
+    downmix::{ChannelLayout, Downmix, DownmixMatrix},
     ffmpeg_src::*,
-    metadata::Metadata,
+    file::FileSource,
+    metadata::{Chapter, Metadata},
+    playlist::{playlist, Playlist},
+    preview::Preview,
+    probe::{probe, ProbeResult},
+    read_ahead::ReadAhead,
     reader::Reader,
+    reader_src::*,
     restartable::Restartable,
+    seek_accuracy::SeekAccuracy,
+    silence::silence,
     ytdl_src::*,
 };
 
@@ -57,7 +85,9 @@ use crate::constants::*;
 use audiopus::coder::GenericCtl;
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use cached::OpusCompressor;
+use codec::OpusDecoderState;
 use error::{Error, Result};
+use resample::Resampler;
 use tokio::runtime::Handle;
 
 use std::{
@@ -95,6 +125,7 @@ pub struct Input {
     /// Framing strategy needed to identify frames of compressed audio.
     pub container: Container,
     pos: usize,
+    resampler: Option<Resampler>,
 }
 
 impl Input {
@@ -107,6 +138,7 @@ impl Input {
             kind: Codec::FloatPcm,
             container: Container::Raw,
             pos: 0,
+            resampler: None,
         }
     }
 
@@ -125,9 +157,175 @@ impl Input {
             kind,
             container,
             pos: 0,
+            resampler: None,
         }
     }
 
+    /// Reads up to `bytes` of this input's source ahead of the mixer, using
+    /// a background thread.
+    ///
+    /// This smooths over jitter when reading from slow disks or network
+    /// streams, which would otherwise be read in many small blocking reads.
+    /// A value of `0` (the default) disables read-ahead.
+    ///
+    /// Only piped, file and user-provided sources are affected: cached and
+    /// [`Restartable`] sources are returned unchanged.
+    ///
+    /// [`Restartable`]: restartable::Restartable
+    pub fn with_read_ahead(mut self, bytes: usize) -> Self {
+        if bytes > 0
+            && matches!(
+                self.reader,
+                Reader::Pipe(_) | Reader::File(_) | Reader::Extension(_)
+            )
+        {
+            let reader = mem::replace(&mut self.reader, Reader::from_memory(vec![]));
+            self.reader = Reader::ReadAhead(ReadAhead::new(reader, bytes));
+        }
+
+        self
+    }
+
+    /// Declares that this input's [`Reader`] holds interleaved PCM audio in the given
+    /// multichannel `layout`, folding it into stereo as it is read.
+    ///
+    /// Channels are combined using the default [`DownmixMatrix`], which may be changed
+    /// while playing via [`Track::set_downmix`]. The layout is reported as part of each
+    /// track's [`TrackState`]. The [`ffmpeg`] source applies this automatically to
+    /// quad, 5.1, and 7.1 files.
+    ///
+    /// Only uncompressed, unframed sources (i.e., [`Codec::Pcm`] or [`Codec::FloatPcm`]
+    /// in a [`Container::Raw`]) can be downmixed: other inputs, and mono or stereo
+    /// layouts, are returned unchanged.
+    ///
+    /// [`Reader`]: Reader
+    /// [`DownmixMatrix`]: DownmixMatrix
+    /// [`Track::set_downmix`]: crate::tracks::Track::set_downmix
+    /// [`TrackState`]: crate::tracks::TrackState
+    /// [`ffmpeg`]: ffmpeg
+    /// [`Codec::Pcm`]: Codec::Pcm
+    /// [`Codec::FloatPcm`]: Codec::FloatPcm
+    /// [`Container::Raw`]: Container::Raw
+    pub fn with_channel_layout(mut self, layout: ChannelLayout) -> Self {
+        let float = match self.kind {
+            Codec::Pcm => false,
+            Codec::FloatPcm => true,
+            _ => return self,
+        };
+
+        if layout.is_surround() && matches!(self.container, Container::Raw) {
+            let reader = mem::replace(&mut self.reader, Reader::from_memory(vec![]));
+            self.reader = Reader::Downmix(Box::new(Downmix::new(reader, layout, float)));
+            self.kind = Codec::FloatPcm;
+            self.stereo = true;
+            self.metadata.channels = Some(layout.channels() as u8);
+        }
+
+        self
+    }
+
+    /// Returns the channel layout of this input's source.
+    ///
+    /// See [`with_channel_layout`].
+    ///
+    /// [`with_channel_layout`]: Input::with_channel_layout
+    pub fn channel_layout(&self) -> ChannelLayout {
+        match &self.reader {
+            Reader::Downmix(d) => d.layout(),
+            _ if self.stereo => ChannelLayout::Stereo,
+            _ => ChannelLayout::Mono,
+        }
+    }
+
+    /// Changes how a multichannel source is folded into stereo, returning whether
+    /// this input is downmixed.
+    pub(crate) fn set_downmix(&mut self, matrix: DownmixMatrix) -> bool {
+        match &mut self.reader {
+            Reader::Downmix(d) => {
+                d.set_matrix(matrix);
+                true
+            },
+            _ => false,
+        }
+    }
+
+    /// Declares the sample rate of the PCM audio read from this input's [`Reader`],
+    /// resampling it to 48kHz as it is read if needed.
+    ///
+    /// The mixer expects all audio at 48kHz: PCM sources at any other rate would
+    /// otherwise play back at the wrong pitch and speed. Resampling uses linear
+    /// interpolation, and seeking remains available if the source supports it.
+    /// The rate is also recorded in [`Metadata::sample_rate`], and tracks playing a
+    /// resampled input fire [`TrackEvent::Resampled`] once they begin.
+    ///
+    /// Opus sources are always decoded at 48kHz, and are unaffected. Sources such as
+    /// [`ffmpeg`] and [`ytdl`] already convert their output to 48kHz, regardless
+    /// of the rate reported in their metadata.
+    ///
+    /// # Panics
+    /// Panics if `rate` is `0`.
+    ///
+    /// [`Reader`]: Reader
+    /// [`Metadata::sample_rate`]: Metadata::sample_rate
+    /// [`TrackEvent::Resampled`]: crate::events::TrackEvent::Resampled
+    /// [`ffmpeg`]: ffmpeg
+    /// [`ytdl`]: ytdl
+    pub fn with_sample_rate(mut self, rate: u32) -> Self {
+        assert!(rate > 0, "Sample rate must be positive.");
+
+        self.metadata.sample_rate = Some(rate);
+        self.resampler = (rate != SAMPLE_RATE_RAW as u32 && !matches!(self.kind, Codec::Opus(_)))
+            .then(|| Resampler::new(rate, self.stereo));
+
+        self
+    }
+
+    /// Returns the sample rate this input is being resampled from, if it is not
+    /// already at 48kHz.
+    ///
+    /// See [`with_sample_rate`].
+    ///
+    /// [`with_sample_rate`]: Input::with_sample_rate
+    pub fn resampled_from(&self) -> Option<u32> {
+        self.resampler.as_ref().map(Resampler::source_rate)
+    }
+
+    /// Creates an independent copy of this input, with its own read position
+    /// starting from the beginning of the source.
+    ///
+    /// This allows one cached source, such as a local file loaded into a
+    /// [`Memory`] or [`Compressed`] cache, to be played on several drivers
+    /// at once without decoding it again. Inputs read directly from a local
+    /// file (e.g., [`dca`]) share the open file instead. Sources which cannot
+    /// be cloned (e.g., live streams or child processes) return `None`.
+    ///
+    /// Opus sources receive a fresh decoder, so that playback of the copy
+    /// does not disturb the original.
+    ///
+    /// [`Memory`]: cached::Memory
+    /// [`Compressed`]: cached::Compressed
+    pub fn try_clone(&self) -> Option<Input> {
+        let reader = self.reader.try_clone()?;
+        let kind = match &self.kind {
+            Codec::Opus(state) => {
+                let mut new_state = OpusDecoderState::new().ok()?;
+                new_state.allow_passthrough = state.allow_passthrough;
+                Codec::Opus(new_state)
+            },
+            other => other.clone(),
+        };
+
+        Some(Input {
+            metadata: self.metadata.clone(),
+            stereo: self.stereo,
+            reader,
+            kind,
+            container: self.container,
+            pos: 0,
+            resampler: self.resampler.as_ref().map(Resampler::fresh),
+        })
+    }
+
     /// Returns whether the inner [`Reader`] implements [`Seek`].
     ///
     /// [`Reader`]: reader::Reader
@@ -136,6 +334,15 @@ impl Input {
         self.reader.is_seekable()
     }
 
+    /// Returns how precisely this source can seek.
+    ///
+    /// See [`Reader::seek_accuracy`].
+    ///
+    /// [`Reader::seek_accuracy`]: Reader::seek_accuracy
+    pub fn seek_accuracy(&self) -> SeekAccuracy {
+        self.reader.seek_accuracy()
+    }
+
     /// Returns whether the read audio signal is stereo (or mono).
     pub fn is_stereo(&self) -> bool {
         self.stereo
@@ -151,8 +358,89 @@ impl Input {
     /// Mixes the output of this stream into a 20ms stereo audio buffer.
     #[inline]
     pub fn mix(&mut self, float_buffer: &mut [f32; STEREO_FRAME_SIZE], volume: f32) -> usize {
+        self.try_mix(float_buffer, volume).unwrap_or(0)
+    }
+
+    /// Mixes the output of this stream into a 20ms stereo audio buffer, returning
+    /// `None` if the underlying stream failed (rather than simply ending).
+    #[inline]
+    pub(crate) fn try_mix(
+        &mut self,
+        float_buffer: &mut [f32; STEREO_FRAME_SIZE],
+        volume: f32,
+    ) -> Option<usize> {
         self.add_float_pcm_frame(float_buffer, self.stereo, volume)
-            .unwrap_or(0)
+    }
+
+    /// Reads one 20ms frame of this stream without converting or mixing its samples.
+    ///
+    /// Returns the same length as [`try_mix`] would have, or `None` if the stream failed.
+    /// Opus and resampled streams must still be decoded, as their frame boundaries
+    /// cannot be found otherwise.
+    ///
+    /// [`try_mix`]: Input::try_mix
+    pub(crate) fn skip_frame(&mut self) -> Option<usize> {
+        if self.resampler.is_some() || matches!(self.kind, Codec::Opus(_)) {
+            let mut scratch = [0f32; STEREO_FRAME_SIZE];
+            return self.try_mix(&mut scratch, 0.0);
+        }
+
+        let (frame_len, scale) = if self.stereo {
+            (STEREO_FRAME_BYTE_SIZE, 1)
+        } else {
+            (MONO_FRAME_BYTE_SIZE, 2)
+        };
+
+        match self.cheap_consume(frame_len) {
+            Ok(len) => Some(len * scale),
+            Err(e) if e.kind() == IoErrorKind::UnexpectedEof => Some(0),
+            Err(e) => {
+                error!("Input died unexpectedly: {:?}", e);
+                None
+            },
+        }
+    }
+
+    /// Mixes the output of this stream into `float_buffer`, which holds interleaved
+    /// stereo samples and may be shorter than a full frame.
+    ///
+    /// Returns the number of samples mixed, which is less than the length of
+    /// `float_buffer` only if the stream ended or failed.
+    pub(crate) fn mix_partial(&mut self, float_buffer: &mut [f32], volume: f32) -> usize {
+        const SAMPLE_LEN: usize = mem::size_of::<f32>();
+        let channels = if self.stereo { 2 } else { 1 };
+        let frame_len = channels * SAMPLE_LEN;
+
+        let mut bytes = [0u8; STEREO_FRAME_BYTE_SIZE];
+        let wanted = ((float_buffer.len() / 2) * frame_len).min(bytes.len());
+        let mut filled = 0;
+
+        while filled < wanted {
+            match self.read(&mut bytes[filled..wanted]) {
+                Ok(0) => break,
+                Ok(len) => filled += len,
+                Err(e) if e.kind() == IoErrorKind::Interrupted => {},
+                Err(_) => break,
+            }
+        }
+
+        let whole = filled - filled % frame_len;
+        for (out, raw) in float_buffer
+            .chunks_exact_mut(2)
+            .zip(bytes[..whole].chunks_exact(frame_len))
+        {
+            let left = f32::from_le_bytes(raw[..SAMPLE_LEN].try_into().unwrap());
+            let right = if self.stereo {
+                f32::from_le_bytes(raw[SAMPLE_LEN..].try_into().unwrap())
+            } else {
+                left
+            };
+
+            out[0] += volume * left;
+            out[1] += volume * right;
+        }
+
+        2 * (whole / frame_len)
     }
 
     /// Seeks the stream to the given time, if possible.
@@ -294,6 +582,59 @@ impl Input {
         Ok(done)
     }
 
+    fn seek_source(&mut self, pos: SeekFrom) -> IoResult<u64> {
+        let mut target = self.pos;
+        match pos {
+            SeekFrom::Start(pos) => {
+                target = pos as usize;
+            },
+            SeekFrom::Current(rel) => {
+                target = target.wrapping_add(rel as usize);
+            },
+            SeekFrom::End(_pos) => return Err(end_seek_unsupported()),
+        }
+
+        debug!("Seeking to {:?}", pos);
+
+        (if target == self.pos {
+            Ok(0)
+        } else if let Some(conversion) = self.container.try_seek_trivial(self.get_type()) {
+            let inside_target = (target * conversion) / mem::size_of::<f32>();
+            Seek::seek(&mut self.reader, SeekFrom::Start(inside_target as u64)).map(|inner_dest| {
+                let outer_dest = ((inner_dest as usize) * mem::size_of::<f32>()) / conversion;
+                self.pos = outer_dest;
+                outer_dest
+            })
+        } else if target > self.pos {
+            // seek in the next amount, disabling decoding if need be.
+            let shift = target - self.pos;
+            self.cheap_consume(shift)
+        } else {
+            // start from scratch, then seek in...
+            Seek::seek(
+                &mut self.reader,
+                SeekFrom::Start(self.container.input_start() as u64),
+            )?;
+
+            self.pos = 0;
+            self.cheap_consume(target)
+        })
+        .map(|_| self.pos as u64)
+    }
+
+    /// Fills in any of this input's unknown metadata which its source has since
+    /// discovered, returning whether anything changed.
+    pub(crate) fn refresh_metadata(&mut self) -> bool {
+        match self.reader.live_metadata() {
+            Some(live) => {
+                let old = self.metadata.clone();
+                self.metadata.fill_from(live);
+                self.metadata != old
+            },
+            None => false,
+        }
+    }
+
     pub(crate) fn supports_passthrough(&self) -> bool {
         match &self.kind {
             Codec::Opus(state) => state.allow_passthrough,
@@ -337,52 +678,50 @@ impl Input {
 
 impl Read for Input {
     fn read(&mut self, buffer: &mut [u8]) -> IoResult<usize> {
-        self.read_inner(buffer, false)
+        match self.resampler.take() {
+            Some(mut resampler) => {
+                let out = resampler.read(buffer, |buf| self.read_inner(buf, false));
+                self.resampler = Some(resampler);
+                out
+            },
+            None => self.read_inner(buffer, false),
+        }
     }
 }
 
 impl Seek for Input {
     fn seek(&mut self, pos: SeekFrom) -> IoResult<u64> {
-        let mut target = self.pos;
-        match pos {
-            SeekFrom::Start(pos) => {
-                target = pos as usize;
-            },
-            SeekFrom::Current(rel) => {
-                target = target.wrapping_add(rel as usize);
-            },
-            SeekFrom::End(_pos) => unimplemented!(),
-        }
+        let mut resampler = match self.resampler.take() {
+            Some(resampler) => resampler,
+            None => return self.seek_source(pos),
+        };
 
-        debug!("Seeking to {:?}", pos);
+        // Positions are given in resampled output, so must be mapped onto the source.
+        let target = match pos {
+            SeekFrom::Start(pos) => pos,
+            SeekFrom::Current(rel) => resampler.position().wrapping_add(rel as u64),
+            SeekFrom::End(_pos) => {
+                self.resampler = Some(resampler);
+                return Err(end_seek_unsupported());
+            },
+        };
 
-        (if target == self.pos {
-            Ok(0)
-        } else if let Some(conversion) = self.container.try_seek_trivial(self.get_type()) {
-            let inside_target = (target * conversion) / mem::size_of::<f32>();
-            Seek::seek(&mut self.reader, SeekFrom::Start(inside_target as u64)).map(|inner_dest| {
-                let outer_dest = ((inner_dest as usize) * mem::size_of::<f32>()) / conversion;
-                self.pos = outer_dest;
-                outer_dest
-            })
-        } else if target > self.pos {
-            // seek in the next amount, disabling decoding if need be.
-            let shift = target - self.pos;
-            self.cheap_consume(shift)
-        } else {
-            // start from scratch, then seek in...
-            Seek::seek(
-                &mut self.reader,
-                SeekFrom::Start(self.container.input_start() as u64),
-            )?;
+        let out = self
+            .seek_source(SeekFrom::Start(resampler.source_offset(target)))
+            .map(|source| resampler.reset(source));
+        self.resampler = Some(resampler);
 
-            self.pos = 0;
-            self.cheap_consume(target)
-        })
-        .map(|_| self.pos as u64)
+        out
     }
 }
 
+fn end_seek_unsupported() -> IoError {
+    IoError::new(
+        IoErrorKind::Unsupported,
+        "Inputs cannot be seeked relative to their end.",
+    )
+}
+
 /// Extension trait to pull frames of audio from a byte source.
 pub(crate) trait ReadAudioExt {
     fn add_float_pcm_frame(
@@ -517,6 +856,67 @@ mod tests {
     use super::*;
     use crate::test_utils::*;
 
+    fn check_resampled_tone(rate: u32) {
+        const FREQ: f64 = 1000.0;
+
+        let data: Vec<u8> = (0..rate)
+            .map(|i| (2.0 * std::f64::consts::PI * FREQ * f64::from(i) / f64::from(rate)).sin())
+            .flat_map(|v| (v as f32).to_le_bytes())
+            .collect();
+        let mut input = Input::new(false, data.into(), Codec::FloatPcm, Container::Raw, None)
+            .with_sample_rate(rate);
+        assert_eq!(input.resampled_from(), Some(rate));
+
+        let mut out_vec = vec![];
+        input.read_to_end(&mut out_vec).unwrap();
+        let out: Vec<f32> = out_vec
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+
+        // One second of audio, with a 1kHz tone rising through zero 1000 times.
+        assert!((out.len() as i64 - SAMPLE_RATE_RAW as i64).abs() <= 2);
+        let rises = out.windows(2).filter(|w| w[0] < 0.0 && w[1] >= 0.0).count();
+        assert!((rises as i64 - FREQ as i64).abs() <= 1);
+
+        let reached = input.seek_time(Duration::from_millis(500)).unwrap();
+        assert!((reached.as_secs_f64() - 0.5).abs() < 0.001);
+
+        let mut rest = vec![];
+        input.read_to_end(&mut rest).unwrap();
+        assert!((rest.len() as i64 / 4 - SAMPLE_RATE_RAW as i64 / 2).abs() <= 2);
+    }
+
+    #[test]
+    fn resampled_44k_input_keeps_pitch_and_duration() {
+        check_resampled_tone(44_100);
+    }
+
+    #[test]
+    fn resampled_22k_input_keeps_pitch_and_duration() {
+        check_resampled_tone(22_050);
+    }
+
+    #[test]
+    fn seeking_from_end_is_unsupported() {
+        let data = make_sine(50 * MONO_FRAME_SIZE, true);
+        let plain = Input::new(
+            true,
+            data.clone().into(),
+            Codec::FloatPcm,
+            Container::Raw,
+            None,
+        );
+        let resampled = Input::new(true, data.into(), Codec::FloatPcm, Container::Raw, None)
+            .with_sample_rate(44_100);
+
+        for mut input in [plain, resampled] {
+            let err = input.seek(SeekFrom::End(0)).unwrap_err();
+            assert_eq!(err.kind(), IoErrorKind::Unsupported);
+            assert!(input.seek(SeekFrom::Start(0)).is_ok());
+        }
+    }
+
     #[test]
     fn float_pcm_input_unchanged_mono() {
         let data = make_sine(50 * MONO_FRAME_SIZE, false);
@@ -592,4 +992,230 @@ mod tests {
             assert!(diff.abs() < f32::EPSILON);
         }
     }
+
+    #[test]
+    fn silence_input_has_exact_length() {
+        let mut input = silence(Duration::from_secs(1));
+
+        let mut out_vec = vec![];
+        let len = input.read_to_end(&mut out_vec).unwrap();
+
+        assert_eq!(len, 48_000 * mem::size_of::<f32>());
+        assert!(out_vec.iter().all(|b| *b == 0));
+        assert!(input.is_seekable());
+    }
+
+    #[test]
+    fn read_ahead_input_unchanged_and_seekable() {
+        let data = make_sine(50 * MONO_FRAME_SIZE, true);
+        let mut input = Input::new(
+            true,
+            data.clone().into(),
+            Codec::FloatPcm,
+            Container::Raw,
+            None,
+        )
+        .with_read_ahead(STEREO_FRAME_BYTE_SIZE);
+
+        assert!(matches!(input.reader, Reader::ReadAhead(_)));
+
+        let mut out_vec = vec![];
+        let len = input.read_to_end(&mut out_vec).unwrap();
+        assert_eq!(out_vec[..len], data[..]);
+
+        input.seek(SeekFrom::Start(0)).unwrap();
+        out_vec.clear();
+        let len = input.read_to_end(&mut out_vec).unwrap();
+        assert_eq!(out_vec[..len], data[..]);
+    }
+
+    #[test]
+    fn surround_input_downmixes_to_stereo() {
+        // FL, FR, FC, LFE, SL, SR.
+        let frame = [0.5f32, -0.5, 0.2, 1.0, 0.1, -0.1];
+        let mut data = vec![];
+        for _ in 0..MONO_FRAME_SIZE {
+            for sample in frame {
+                data.write_f32::<LittleEndian>(sample).unwrap();
+            }
+        }
+
+        let mut input = Input::new(false, data.into(), Codec::FloatPcm, Container::Raw, None)
+            .with_channel_layout(ChannelLayout::Surround51);
+
+        assert_eq!(input.channel_layout(), ChannelLayout::Surround51);
+        assert!(input.is_stereo());
+
+        let mut out_vec = vec![];
+        let len = input.read_to_end(&mut out_vec).unwrap();
+        assert_eq!(len, STEREO_FRAME_BYTE_SIZE);
+
+        let mut window = &out_vec[..];
+        let left = window.read_f32::<LittleEndian>().unwrap();
+        let right = window.read_f32::<LittleEndian>().unwrap();
+        assert!((left - (0.5 + (0.2 + 0.1) * std::f32::consts::FRAC_1_SQRT_2)).abs() < 1e-6);
+        assert!((right - (-0.5 + (0.2 - 0.1) * std::f32::consts::FRAC_1_SQRT_2)).abs() < 1e-6);
+
+        assert!(input.set_downmix(DownmixMatrix::new().center(0.0).surround(0.0).lfe(1.0)));
+        input.seek(SeekFrom::Start(0)).unwrap();
+        let left = input.read_f32::<LittleEndian>().unwrap();
+        assert!((left - 1.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn cloned_memory_input_reads_independently() {
+        let data = make_sine(50 * MONO_FRAME_SIZE, true);
+        let source = Input::new(
+            true,
+            data.clone().into(),
+            Codec::FloatPcm,
+            Container::Raw,
+            None,
+        );
+        let mut input: Input = cached::Memory::new(source).unwrap().try_into().unwrap();
+
+        let mut partial = [0u8; 64];
+        input.read_exact(&mut partial).unwrap();
+
+        let mut clone = input.try_clone().expect("Memory inputs can be cloned.");
+        let mut out_vec = vec![];
+        let len = clone.read_to_end(&mut out_vec).unwrap();
+        assert_eq!(out_vec[..len], data[..]);
+
+        let mut rest = vec![];
+        input.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest[..], data[partial.len()..]);
+    }
+
+    #[test]
+    fn cloned_file_input_reads_independently() {
+        use std::{fs, io::Write};
+
+        let data = make_sine(50 * MONO_FRAME_SIZE, true);
+        let path = std::env::temp_dir().join(format!("songbird-clone-{}.raw", std::process::id()));
+
+        // A header before the audio, as skipped by e.g. DCA sources.
+        let mut file = fs::File::create(&path).unwrap();
+        file.write_all(b"header").unwrap();
+        file.write_all(&data).unwrap();
+        drop(file);
+
+        let mut file = fs::File::open(&path).unwrap();
+        file.read_exact(&mut [0u8; 6]).unwrap();
+        let mut input = Input::float_pcm(true, Reader::from_file(file));
+
+        let mut partial = [0u8; 64];
+        input.read_exact(&mut partial).unwrap();
+
+        let mut clone = input.try_clone().expect("File inputs can be cloned.");
+        let mut out_vec = vec![];
+        clone.read_to_end(&mut out_vec).unwrap();
+        assert_eq!(out_vec[..], data[..]);
+
+        let mut rest = vec![];
+        input.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest[..], data[partial.len()..]);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn async_reader_input_unchanged() {
+        let data = make_sine(50 * MONO_FRAME_SIZE, true);
+        let mut input = from_async_seekable_reader(
+            io::Cursor::new(data.clone()),
+            InputHint::FloatPcm { stereo: true },
+        );
+
+        let out_vec = tokio::task::spawn_blocking(move || {
+            let mut out_vec = vec![];
+            input.read_to_end(&mut out_vec).unwrap();
+
+            assert!(input.is_seekable());
+            out_vec
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(out_vec[..], data[..]);
+    }
+
+    /// Encodes one second of a stereo sine wave as length-prefixed Opus packets.
+    fn opus_frames() -> Vec<u8> {
+        let data = make_sine(50 * MONO_FRAME_SIZE, true);
+        let source = Input::new(true, data.into(), Codec::FloatPcm, Container::Raw, None);
+        let mut compressed =
+            cached::Compressed::new(source, audiopus::Bitrate::BitsPerSecond(128_000)).unwrap();
+
+        let mut out = vec![];
+        compressed.raw.read_to_end(&mut out).unwrap();
+
+        out
+    }
+
+    /// Mixes `input` until it ends, returning the number of frames played.
+    fn count_frames(input: &mut Input) -> usize {
+        let mut frame = [0f32; STEREO_FRAME_SIZE];
+        let mut frames = 0;
+
+        while input.mix(&mut frame, 1.0) > 0 {
+            assert!(frame.iter().any(|s| *s != 0.0));
+            frames += 1;
+        }
+
+        frames
+    }
+
+    #[test]
+    fn opus_reader_input_decodes_and_seeks() {
+        let mut input = from_seekable_reader(
+            io::Cursor::new(opus_frames()),
+            InputHint::Opus { stereo: true },
+        );
+
+        assert!(input.is_seekable());
+        assert!(matches!(input.get_type(), CodecType::Opus));
+        assert_eq!(count_frames(&mut input), 50);
+
+        assert_eq!(input.seek_time(Duration::ZERO), Some(Duration::ZERO));
+        assert_eq!(count_frames(&mut input), 50);
+    }
+
+    #[test]
+    fn opus_reader_input_decodes_without_seeking() {
+        let mut input = from_reader(
+            io::Cursor::new(opus_frames()),
+            InputHint::Opus { stereo: true },
+        );
+
+        assert!(!input.is_seekable());
+        assert_eq!(count_frames(&mut input), 50);
+        assert_eq!(input.seek_time(Duration::ZERO), None);
+    }
+
+    #[tokio::test]
+    async fn preview_decodes_window_and_rewinds() {
+        let data = make_sine(50 * MONO_FRAME_SIZE, true);
+        let input = from_seekable_reader(
+            io::Cursor::new(data.clone()),
+            InputHint::FloatPcm { stereo: true },
+        );
+
+        let preview = input
+            .preview(Duration::from_millis(100), Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(preview.decoded, Duration::from_millis(100));
+
+        let mut input = preview.input;
+        let out_vec = tokio::task::spawn_blocking(move || {
+            let mut out_vec = vec![];
+            input.read_to_end(&mut out_vec).unwrap();
+            out_vec
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(out_vec[..], data[..]);
+    }
 }
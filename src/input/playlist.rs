@@ -0,0 +1,183 @@
+//! Loading of local M3U and PLS playlist files.
+
+use super::{
+    error::{Error, Result},
+    Input,
+    Restartable,
+};
+use std::{
+    ffi::OsString,
+    io::{Error as IoError, ErrorKind as IoErrorKind},
+    path::{Path, PathBuf},
+};
+
+/// The contents of a playlist file, as returned by [`playlist`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct Playlist {
+    /// Sources for every entry which could be opened, in playlist order.
+    pub inputs: Vec<Input>,
+    /// Entries which could not be opened, alongside the reason why.
+    pub skipped: Vec<(String, Error)>,
+}
+
+/// Parses a local M3U or PLS playlist file into a list of lazily-opened inputs.
+///
+/// PLS files are detected by their `.pls` extension or `[playlist]` header;
+/// anything else is read as (extended) M3U. Relative paths are resolved
+/// against the directory containing the playlist, and `http(s)` URLs are
+/// passed to ffmpeg as-is.
+///
+/// Entries which cannot be resolved are reported in [`Playlist::skipped`]
+/// rather than failing the whole load. Each input is a lazy
+/// [`Restartable::ffmpeg`] source: entries are probed as the playlist loads,
+/// but no decoding processes are spawned until a track begins to play.
+///
+/// The inputs are ready to be passed to [`Driver::enqueue_all`].
+///
+/// [`Restartable::ffmpeg`]: crate::input::restartable::Restartable::ffmpeg
+/// [`Driver::enqueue_all`]: crate::driver::Driver::enqueue_all
+pub async fn playlist<P: AsRef<Path>>(path: P) -> Result<Playlist> {
+    let path = path.as_ref();
+    let text = tokio::fs::read_to_string(path).await?;
+    let base = path.parent().unwrap_or_else(|| Path::new(""));
+
+    let mut out = Playlist {
+        inputs: vec![],
+        skipped: vec![],
+    };
+
+    for entry in parse_entries(&text, is_pls(path, &text)) {
+        let input = match locate(base, &entry) {
+            Ok(location) => Restartable::ffmpeg(location, true).await,
+            Err(e) => Err(e),
+        };
+
+        match input {
+            Ok(input) => out.inputs.push(input.into()),
+            Err(e) => out.skipped.push((entry, e)),
+        }
+    }
+
+    Ok(out)
+}
+
+fn is_pls(path: &Path, text: &str) -> bool {
+    path.extension()
+        .map(|ext| ext.eq_ignore_ascii_case("pls"))
+        .unwrap_or(false)
+        || text
+            .trim_start()
+            .to_ascii_lowercase()
+            .starts_with("[playlist]")
+}
+
+fn is_url(entry: &str) -> bool {
+    let lower = entry.to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+/// Finds the file or URL named by a playlist entry, resolving relative paths
+/// against the playlist's directory `base`.
+fn locate(base: &Path, entry: &str) -> Result<OsString> {
+    if is_url(entry) {
+        return Ok(entry.into());
+    }
+
+    let file = resolve(base, entry);
+
+    if file.is_file() {
+        Ok(file.into())
+    } else {
+        Err(Error::Io(IoError::new(
+            IoErrorKind::NotFound,
+            "Playlist entry does not exist.",
+        )))
+    }
+}
+
+fn resolve(base: &Path, entry: &str) -> PathBuf {
+    let entry = entry.strip_prefix("file://").unwrap_or(entry);
+    let entry = Path::new(entry);
+
+    if entry.is_absolute() {
+        entry.to_path_buf()
+    } else {
+        base.join(entry)
+    }
+}
+
+/// Extracts the location of each entry, in order, from a playlist's text.
+fn parse_entries(text: &str, pls: bool) -> Vec<String> {
+    let lines = text
+        .lines()
+        .map(|line| line.trim().trim_start_matches('\u{feff}'))
+        .filter(|line| !line.is_empty());
+
+    if pls {
+        let mut entries: Vec<(usize, String)> = lines
+            .filter_map(|line| {
+                let (key, value) = line.split_once('=')?;
+                let index = key
+                    .get(..4)
+                    .filter(|prefix| prefix.eq_ignore_ascii_case("file"))
+                    .and_then(|_| key[4..].trim().parse().ok())?;
+
+                Some((index, value.trim().to_string()))
+            })
+            .collect();
+
+        entries.sort_by_key(|(index, _)| *index);
+        entries.into_iter().map(|(_, entry)| entry).collect()
+    } else {
+        lines
+            .filter(|line| !line.starts_with('#'))
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn m3u_and_pls_entries_are_extracted_in_order() {
+        let m3u = "#EXTM3U\n#EXTINF:123,Artist - Title\nsong.mp3\n\nhttp://example.com/a.ogg\n";
+        assert_eq!(
+            parse_entries(m3u, false),
+            vec!["song.mp3", "http://example.com/a.ogg"]
+        );
+
+        let pls =
+            "[playlist]\nFile2=second.flac\nTitle1=First\nFile1=first.mp3\nNumberOfEntries=2\n";
+        assert_eq!(parse_entries(pls, true), vec!["first.mp3", "second.flac"]);
+    }
+
+    #[tokio::test]
+    async fn relative_entries_resolve_against_playlist_dir() {
+        let dir = std::env::temp_dir().join(format!("songbird-playlist-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("present.raw"), b"").unwrap();
+        std::fs::write(
+            dir.join("list.m3u"),
+            "#EXTM3U\nmissing.raw\n../missing.raw\n",
+        )
+        .unwrap();
+
+        let present = locate(&dir, "present.raw");
+        let loaded = playlist(dir.join("list.m3u")).await;
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(present.unwrap(), dir.join("present.raw").into_os_string());
+
+        let loaded = loaded.unwrap();
+        let skipped: Vec<&str> = loaded.skipped.iter().map(|(e, _)| &e[..]).collect();
+        assert!(loaded.inputs.is_empty());
+        assert_eq!(skipped, vec!["missing.raw", "../missing.raw"]);
+        assert!(loaded
+            .skipped
+            .iter()
+            .all(|(_, e)| matches!(e, Error::Io(e) if e.kind() == IoErrorKind::NotFound)));
+    }
+}
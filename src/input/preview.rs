@@ -0,0 +1,87 @@
+use super::{error::PreviewError, utils, Input, Metadata};
+use crate::constants::*;
+use std::{
+    io::{Error as IoError, ErrorKind as IoErrorKind, Read},
+    time::Duration,
+};
+use tokio::{runtime::Handle, task, time};
+
+/// A source which decoded successfully during [`Input::preview`].
+///
+/// [`Input::preview`]: Input::preview
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct Preview {
+    /// The previewed source, ready to be played.
+    ///
+    /// Seekable sources are rewound to their start. Others resume after the
+    /// audio decoded during the preview.
+    pub input: Input,
+    /// Metadata of the source, including any discovered while decoding.
+    pub metadata: Metadata,
+    /// Length of the audio decoded during the preview.
+    ///
+    /// This is shorter than the requested window if the source ended early.
+    pub decoded: Duration,
+}
+
+impl Input {
+    /// Decodes up to `window` of this source's audio without playing it, to check
+    /// that it is playable before it is queued.
+    ///
+    /// Decoding runs on a blocking thread of the current Tokio runtime, and fails with
+    /// [`PreviewError::Timeout`] if it does not finish within `timeout`. Sources which
+    /// end before producing any audio fail with [`PreviewError::Empty`].
+    ///
+    /// [`PreviewError::Timeout`]: PreviewError::Timeout
+    /// [`PreviewError::Empty`]: PreviewError::Empty
+    pub async fn preview(
+        mut self,
+        window: Duration,
+        timeout: Duration,
+    ) -> Result<Preview, PreviewError> {
+        self.prep_with_handle(Handle::current());
+
+        let decode = task::spawn_blocking(move || self.decode_preview(window));
+
+        match time::timeout(timeout, decode).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(e)) => Err(PreviewError::Decode(IoError::new(IoErrorKind::Other, e))),
+            Err(_) => Err(PreviewError::Timeout),
+        }
+    }
+
+    fn decode_preview(mut self, window: Duration) -> Result<Preview, PreviewError> {
+        self.reader.make_playable();
+
+        let target = utils::timestamp_to_byte_count(window, self.stereo);
+        let mut buffer = [0u8; STEREO_FRAME_BYTE_SIZE];
+        let mut read = 0;
+
+        while read < target {
+            match self.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(len) => read += len,
+                Err(e) if e.kind() == IoErrorKind::Interrupted => continue,
+                Err(e) => return Err(PreviewError::Decode(e)),
+            }
+        }
+
+        if read == 0 {
+            return Err(PreviewError::Empty);
+        }
+
+        let decoded = utils::byte_count_to_timestamp(read, self.stereo);
+
+        self.refresh_metadata();
+        if self.is_seekable() {
+            self.seek_time(Duration::ZERO);
+        }
+
+        Ok(Preview {
+            metadata: (*self.metadata).clone(),
+            input: self,
+            decoded,
+        })
+    }
+}
@@ -0,0 +1,93 @@
+use super::{
+    error::{Error, Result},
+    ffmpeg_src::ffprobe_json,
+    Metadata,
+};
+use serde_json::Value;
+use std::{ffi::OsStr, time::Duration};
+
+/// Format and codec details of an audio file, as detected by [`probe`].
+///
+/// [`probe`]: probe
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct ProbeResult {
+    /// Name of the file's container format, as reported by `ffprobe`
+    /// (e.g., `"ogg"` or `"matroska,webm"`).
+    pub container: Option<String>,
+    /// Name of the audio stream's codec, as reported by `ffprobe` (e.g., `"opus"`).
+    pub codec: Option<String>,
+    /// Number of channels in the audio stream.
+    pub channels: Option<u8>,
+    /// Sample rate of the audio stream, in Hz.
+    pub sample_rate: Option<u32>,
+    /// Length of the file.
+    pub duration: Option<Duration>,
+    /// Bitrate of the audio stream (or else of the whole file), in bits per second.
+    pub bitrate: Option<u64>,
+    /// Whether the audio is 48kHz Opus with at most two channels.
+    ///
+    /// Such audio may be sent without re-encoding by sources which support
+    /// Opus passthrough, such as [`dca`]. Sources created by [`ffmpeg`] are
+    /// always decoded.
+    ///
+    /// [`dca`]: super::dca
+    /// [`ffmpeg`]: super::ffmpeg
+    pub passthrough_eligible: bool,
+}
+
+impl ProbeResult {
+    fn from_ffprobe_json(value: &Value) -> Result<Self> {
+        let metadata = Metadata::from_ffprobe_json(value);
+        let format = value.get("format");
+        let stream = value
+            .get("streams")
+            .and_then(Value::as_array)
+            .and_then(|v| {
+                v.iter()
+                    .find(|line| line.get("codec_type").and_then(Value::as_str) == Some("audio"))
+            })
+            .ok_or(Error::Streams)?;
+
+        let string = |value: Option<&Value>, key| {
+            value
+                .and_then(|m| m.get(key))
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        };
+
+        let codec = string(Some(stream), "codec_name");
+        let bitrate = string(Some(stream), "bit_rate")
+            .or_else(|| string(format, "bit_rate"))
+            .and_then(|v| v.parse::<u64>().ok());
+
+        let passthrough_eligible = codec.as_deref() == Some("opus")
+            && metadata.sample_rate == Some(48_000)
+            && metadata.channels.map_or(false, |c| c <= 2);
+
+        Ok(Self {
+            container: string(format, "format_name"),
+            codec,
+            channels: metadata.channels,
+            sample_rate: metadata.sample_rate,
+            duration: metadata.duration,
+            bitrate,
+            passthrough_eligible,
+        })
+    }
+}
+
+/// Detects the format and codec of an audio file or URL using `ffprobe`,
+/// without creating a playable [`Input`].
+///
+/// This allows a source to be rejected, or handled differently (e.g., via
+/// passthrough), before committing to it. Fails with [`Error::Streams`] if
+/// the file holds no audio.
+///
+/// [`Input`]: super::Input
+/// [`Error::Streams`]: Error::Streams
+pub async fn probe<P: AsRef<OsStr>>(path: P) -> Result<ProbeResult> {
+    let value = ffprobe_json(path.as_ref()).await?;
+
+    ProbeResult::from_ffprobe_json(&value)
+}
@@ -0,0 +1,221 @@
+//! Background read-ahead for slow input bytestreams.
+
+use super::{Reader, SeekAccuracy};
+use flume::{Receiver, Sender, TryRecvError};
+use std::{
+    fmt::{Debug, Error as FormatError, Formatter},
+    io::{Error as IoError, ErrorKind as IoErrorKind, Read, Result as IoResult, Seek, SeekFrom},
+    result::Result as StdResult,
+    thread,
+};
+
+/// Size of each block read from the inner source by the background thread.
+const CHUNK_LEN: usize = 4096;
+
+enum Chunk {
+    Data(Vec<u8>),
+    Eof,
+    Error(IoError),
+    Seeked(IoResult<u64>),
+}
+
+/// A source whose bytes are read ahead of the mixer by a background thread.
+///
+/// This smooths over jitter from slow disks or networks, at the cost of
+/// holding up to the configured number of bytes in memory. Created via
+/// [`Input::with_read_ahead`].
+///
+/// Supports seeking if the inner source does, discarding any buffered data.
+///
+/// [`Input::with_read_ahead`]: super::Input::with_read_ahead
+pub struct ReadAhead {
+    rx: Receiver<Chunk>,
+    seek_tx: Sender<SeekFrom>,
+    current: Vec<u8>,
+    current_pos: usize,
+    position: u64,
+    finished: bool,
+    seekable: bool,
+    seek_accuracy: SeekAccuracy,
+}
+
+impl ReadAhead {
+    /// Starts reading up to `bytes` ahead of the consumer of `reader`.
+    pub(crate) fn new(reader: Reader, bytes: usize) -> Self {
+        let seekable = reader.is_seekable();
+        let seek_accuracy = reader.seek_accuracy();
+        let (tx, rx) = flume::bounded(1 + bytes / CHUNK_LEN);
+        let (seek_tx, seek_rx) = flume::unbounded();
+
+        thread::spawn(move || runner(reader, tx, seek_rx));
+
+        Self {
+            rx,
+            seek_tx,
+            current: vec![],
+            current_pos: 0,
+            position: 0,
+            finished: false,
+            seekable,
+            seek_accuracy,
+        }
+    }
+
+    /// Returns whether the inner source supports seeking.
+    pub fn is_seekable(&self) -> bool {
+        self.seekable
+    }
+
+    /// Returns how precisely the inner source can seek.
+    pub fn seek_accuracy(&self) -> SeekAccuracy {
+        self.seek_accuracy
+    }
+}
+
+fn runner(mut reader: Reader, tx: Sender<Chunk>, seek_rx: Receiver<SeekFrom>) {
+    let mut at_end = false;
+
+    loop {
+        // Once the source is exhausted, only a seek can produce more data.
+        let seek = if at_end {
+            match seek_rx.recv() {
+                Ok(pos) => Some(pos),
+                Err(_) => break,
+            }
+        } else {
+            match seek_rx.try_recv() {
+                Ok(pos) => Some(pos),
+                Err(TryRecvError::Empty) => None,
+                Err(TryRecvError::Disconnected) => break,
+            }
+        };
+
+        let msg = if let Some(pos) = seek {
+            at_end = false;
+            Chunk::Seeked(reader.seek(pos))
+        } else {
+            let mut buf = vec![0u8; CHUNK_LEN];
+            match reader.read(&mut buf) {
+                Ok(0) => {
+                    at_end = true;
+                    Chunk::Eof
+                },
+                Ok(len) => {
+                    buf.truncate(len);
+                    Chunk::Data(buf)
+                },
+                Err(e) if e.kind() == IoErrorKind::Interrupted => continue,
+                Err(e) => {
+                    at_end = true;
+                    Chunk::Error(e)
+                },
+            }
+        };
+
+        if tx.send(msg).is_err() {
+            break;
+        }
+    }
+}
+
+impl Read for ReadAhead {
+    fn read(&mut self, buffer: &mut [u8]) -> IoResult<usize> {
+        while self.current_pos >= self.current.len() {
+            if self.finished {
+                return Ok(0);
+            }
+
+            match self.rx.recv() {
+                Ok(Chunk::Data(data)) => {
+                    self.current = data;
+                    self.current_pos = 0;
+                },
+                Ok(Chunk::Eof) | Err(_) => {
+                    self.finished = true;
+                },
+                Ok(Chunk::Error(e)) => {
+                    self.finished = true;
+                    return Err(e);
+                },
+                Ok(Chunk::Seeked(_)) => {},
+            }
+        }
+
+        let len = buffer.len().min(self.current.len() - self.current_pos);
+        buffer[..len].copy_from_slice(&self.current[self.current_pos..self.current_pos + len]);
+        self.current_pos += len;
+        self.position += len as u64;
+
+        Ok(len)
+    }
+}
+
+impl Seek for ReadAhead {
+    fn seek(&mut self, pos: SeekFrom) -> IoResult<u64> {
+        if !self.seekable {
+            return Err(IoError::new(
+                IoErrorKind::InvalidInput,
+                "Seeking not supported on Reader of this type.",
+            ));
+        }
+
+        // The background thread has read past our logical position,
+        // so relative seeks must be made absolute.
+        let pos = match pos {
+            SeekFrom::Current(offset) =>
+                SeekFrom::Start(checked_offset(self.position, offset).ok_or_else(|| {
+                    IoError::new(IoErrorKind::InvalidInput, "Invalid seek offset.")
+                })?),
+            other => other,
+        };
+
+        let closed = || IoError::new(IoErrorKind::BrokenPipe, "Read-ahead thread has exited.");
+
+        self.seek_tx.send(pos).map_err(|_| closed())?;
+        self.current.clear();
+        self.current_pos = 0;
+
+        // Discard anything read before the seek took place.
+        loop {
+            match self.rx.recv() {
+                Ok(Chunk::Seeked(result)) => {
+                    match result {
+                        Ok(new_pos) => {
+                            self.position = new_pos;
+                            self.finished = false;
+                        },
+                        Err(_) => {
+                            self.finished = true;
+                        },
+                    }
+
+                    return result;
+                },
+                Ok(_) => {},
+                Err(_) => {
+                    self.finished = true;
+                    return Err(closed());
+                },
+            }
+        }
+    }
+}
+
+fn checked_offset(base: u64, offset: i64) -> Option<u64> {
+    if offset < 0 {
+        base.checked_sub(offset.unsigned_abs())
+    } else {
+        base.checked_add(offset as u64)
+    }
+}
+
+impl Debug for ReadAhead {
+    fn fmt(&self, f: &mut Formatter<'_>) -> StdResult<(), FormatError> {
+        f.debug_struct("ReadAhead")
+            .field("position", &self.position)
+            .field("buffered_chunks", &self.rx.len())
+            .field("finished", &self.finished)
+            .field("seekable", &self.seekable)
+            .finish()
+    }
+}
@@ -19,6 +19,9 @@ use std::{
 use streamcatcher::{Catcher, TxCatcher};
 pub use symphonia_core::io::MediaSource;
 
+/// Expected precision of seeks made by restarting a source's underlying process.
+const RESTART_SEEK_TOLERANCE: Duration = Duration::from_millis(50);
+
 /// Usable data/byte sources for an audio stream.
 ///
 /// Users may define their own data sources using [`Extension`].
@@ -43,6 +46,18 @@ pub enum Reader {
     ///
     /// Supports seeking.
     Restartable(Restartable),
+    /// A piped or user-provided source, read ahead of the mixer by a background thread.
+    ///
+    /// Seeking support depends on the wrapped source.
+    ReadAhead(ReadAhead),
+    /// A multichannel source, folded into stereo as it is read.
+    ///
+    /// Seeking support depends on the wrapped source.
+    Downmix(Box<Downmix>),
+    /// A source contained in a local file.
+    ///
+    /// Supports seeking.
+    File(FileSource),
     /// A basic user-provided source.
     ///
     /// Seeking support depends on underlying `MediaSource` implementation.
@@ -59,15 +74,71 @@ impl Reader {
     pub fn is_seekable(&self) -> bool {
         use Reader::*;
         match self {
-            Restartable(_) | Compressed(_) | Memory(_) => true,
+            Restartable(_) | Compressed(_) | Memory(_) | File(_) => true,
+            ReadAhead(source) => source.is_seekable(),
+            Downmix(source) => source.is_seekable(),
             Extension(source) => source.is_seekable(),
             _ => false,
         }
     }
 
+    /// Returns how precisely this source can seek.
+    ///
+    /// Cached and seekable user-provided sources are [`SeekAccuracy::Exact`].
+    /// [`Restartable`] sources restart ffmpeg at the requested time, which
+    /// typically lands within a codec frame or two of the target: these are
+    /// reported as [`SeekAccuracy::Approximate`].
+    ///
+    /// [`SeekAccuracy::Exact`]: SeekAccuracy::Exact
+    /// [`SeekAccuracy::Approximate`]: SeekAccuracy::Approximate
+    /// [`Restartable`]: Reader::Restartable
+    pub fn seek_accuracy(&self) -> SeekAccuracy {
+        use Reader::*;
+        match self {
+            Compressed(_) | Memory(_) | File(_) => SeekAccuracy::Exact,
+            Restartable(_) => SeekAccuracy::Approximate(RESTART_SEEK_TOLERANCE),
+            ReadAhead(source) => source.seek_accuracy(),
+            Downmix(source) => source.inner().seek_accuracy(),
+            Extension(source) if source.is_seekable() => SeekAccuracy::Exact,
+            _ => SeekAccuracy::Unsupported,
+        }
+    }
+
+    /// Creates an independent view of this source, with its own read
+    /// cursor starting from the beginning of the stream.
+    ///
+    /// Only the cached [`Memory`] and [`Compressed`] sources and local
+    /// [`File`]s support this, as the cloned view shares the cached data or
+    /// open file rather than recreating it. Piped, restartable, and
+    /// user-provided sources return `None`.
+    ///
+    /// [`Memory`]: Reader::Memory
+    /// [`Compressed`]: Reader::Compressed
+    /// [`File`]: Reader::File
+    pub fn try_clone(&self) -> Option<Self> {
+        use Reader::*;
+        match self {
+            Memory(c) => Some(Memory(c.new_handle())),
+            Compressed(c) => Some(Compressed(c.new_handle())),
+            File(f) => Some(File(f.new_handle())),
+            _ => None,
+        }
+    }
+
     /// A source contained in a local file.
+    ///
+    /// Regular files become a [`File`] source, which can be cloned with
+    /// [`try_clone`]. Other files, such as named pipes, are read as
+    /// an [`Extension`].
+    ///
+    /// [`File`]: Reader::File
+    /// [`try_clone`]: Reader::try_clone
+    /// [`Extension`]: Reader::Extension
     pub fn from_file(file: File) -> Self {
-        Self::Extension(Box::new(file))
+        match FileSource::new(file) {
+            Ok(source) => Self::File(source),
+            Err(file) => Self::Extension(Box::new(file)),
+        }
     }
 
     /// A source contained as an array in memory.
@@ -80,15 +151,45 @@ impl Reader {
         use Reader::*;
         match self {
             Restartable(r) => r.prep_with_handle(handle),
+            Downmix(d) => d.inner_mut().prep_with_handle(handle),
             _ => {},
         }
     }
 
+    /// Attempts to recover from an error by recreating the underlying source.
+    ///
+    /// Only [`Restartable`] sources support this.
+    ///
+    /// [`Restartable`]: Reader::Restartable
+    pub(crate) fn recreate(&mut self) -> IoResult<()> {
+        match self {
+            Self::Restartable(r) => r.recreate(),
+            _ => Err(IoError::new(
+                IoErrorKind::InvalidInput,
+                "Recreation not supported on Reader of this type.",
+            )),
+        }
+    }
+
+    /// Returns metadata discovered by this source since it was created, if any.
+    ///
+    /// Only lazily-initialised [`Restartable`] sources support this.
+    ///
+    /// [`Restartable`]: Reader::Restartable
+    pub(crate) fn live_metadata(&self) -> Option<&Metadata> {
+        match self {
+            Self::Restartable(r) => r.live_metadata(),
+            Self::Downmix(d) => d.inner().live_metadata(),
+            _ => None,
+        }
+    }
+
     #[allow(clippy::single_match)]
     pub(crate) fn make_playable(&mut self) {
         use Reader::*;
         match self {
             Restartable(r) => r.make_playable(),
+            Downmix(d) => d.inner_mut().make_playable(),
             _ => {},
         }
     }
@@ -102,6 +203,9 @@ impl Read for Reader {
             Memory(a) => Read::read(a, buffer),
             Compressed(a) => Read::read(a, buffer),
             Restartable(a) => Read::read(a, buffer),
+            ReadAhead(a) => Read::read(a, buffer),
+            Downmix(a) => Read::read(a, buffer),
+            File(a) => Read::read(a, buffer),
             Extension(a) => a.read(buffer),
         }
     }
@@ -118,6 +222,9 @@ impl Seek for Reader {
             Memory(a) => Seek::seek(a, pos),
             Compressed(a) => Seek::seek(a, pos),
             Restartable(a) => Seek::seek(a, pos),
+            ReadAhead(a) => Seek::seek(a, pos),
+            Downmix(a) => Seek::seek(a, pos),
+            File(a) => Seek::seek(a, pos),
             Extension(a) =>
                 if a.is_seekable() {
                     a.seek(pos)
@@ -139,6 +246,9 @@ impl Debug for Reader {
             Memory(a) => format!("{:?}", a),
             Compressed(a) => format!("{:?}", a),
             Restartable(a) => format!("{:?}", a),
+            ReadAhead(a) => format!("{:?}", a),
+            Downmix(a) => format!("{:?}", a),
+            File(a) => format!("{:?}", a),
             Extension(_) => "Extension".to_string(),
         };
         f.debug_tuple("Reader").field(&field).finish()
@@ -0,0 +1,311 @@
+use super::{codec::OpusDecoderState, Codec, Container, Input, Metadata, Reader};
+use flume::{Receiver, Sender};
+use parking_lot::Mutex;
+use std::{
+    io::{Error as IoError, ErrorKind as IoErrorKind, Read, Result as IoResult, Seek, SeekFrom},
+    pin::Pin,
+    task::{Context, Poll},
+};
+use symphonia_core::io::MediaSource;
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, ReadBuf},
+    runtime::Handle,
+};
+
+/// Audio format of a bytestream passed to [`from_reader`] or [`from_async_reader`],
+/// and their seekable variants.
+///
+/// Each format describes 48kHz audio.
+///
+/// [`from_reader`]: from_reader
+/// [`from_async_reader`]: from_async_reader
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum InputHint {
+    /// Raw little-endian `i16` samples, interleaved if `stereo`.
+    Pcm {
+        /// Whether the stream holds two channels, rather than one.
+        stereo: bool,
+    },
+    /// Raw little-endian `f32` samples, interleaved if `stereo`.
+    FloatPcm {
+        /// Whether the stream holds two channels, rather than one.
+        stereo: bool,
+    },
+    /// Opus packets, each prefixed by its length as a little-endian `i16`.
+    ///
+    /// This is the framing used by the body of a DCA file, after its header:
+    /// see [`dca`] for reading complete DCA files.
+    ///
+    /// [`dca`]: super::dca
+    Opus {
+        /// Whether the packets were encoded from two channels, rather than one.
+        stereo: bool,
+    },
+}
+
+impl InputHint {
+    fn into_input(self, reader: Reader) -> Input {
+        let (stereo, kind, container) = match self {
+            Self::Pcm { stereo } => (stereo, Codec::Pcm, Container::Raw),
+            Self::FloatPcm { stereo } => (stereo, Codec::FloatPcm, Container::Raw),
+            Self::Opus { stereo } => (
+                stereo,
+                Codec::Opus(
+                    OpusDecoderState::new().expect("Stereo 48kHz Opus decoders are always valid."),
+                ),
+                Container::Dca { first_frame: 0 },
+            ),
+        };
+
+        let metadata = Metadata {
+            channels: Some(if stereo { 2 } else { 1 }),
+            sample_rate: Some(48_000),
+            ..Default::default()
+        };
+
+        Input::new(stereo, reader, kind, container, Some(metadata))
+    }
+}
+
+/// Creates an [`Input`] from any blocking [`Read`], such as a pipe or a socket.
+///
+/// The returned input does not support seeking: see [`from_seekable_reader`].
+///
+/// Reads take place on the mixer thread, so slow sources should be wrapped
+/// using [`Input::with_read_ahead`].
+///
+/// [`Input`]: Input
+/// [`Read`]: std::io::Read
+/// [`from_seekable_reader`]: from_seekable_reader
+/// [`Input::with_read_ahead`]: Input::with_read_ahead
+pub fn from_reader<R>(reader: R, hint: InputHint) -> Input
+where
+    R: Read + Send + 'static,
+{
+    hint.into_input(Reader::Extension(Box::new(BlockingSource {
+        inner: Mutex::new(NoSeek(reader)),
+        seekable: false,
+    })))
+}
+
+/// Creates a seekable [`Input`] from any blocking [`Read`] + [`Seek`].
+///
+/// [`Input`]: Input
+/// [`Read`]: std::io::Read
+/// [`Seek`]: std::io::Seek
+pub fn from_seekable_reader<R>(reader: R, hint: InputHint) -> Input
+where
+    R: Read + Seek + Send + 'static,
+{
+    hint.into_input(Reader::Extension(Box::new(BlockingSource {
+        inner: Mutex::new(reader),
+        seekable: true,
+    })))
+}
+
+/// Creates an [`Input`] from any [`AsyncRead`], such as an object storage
+/// download or an async pipe.
+///
+/// The reader is driven by a task on the current Tokio runtime, and the returned
+/// input does not support seeking: see [`from_async_seekable_reader`].
+///
+/// # Panics
+/// Panics if called outside of a Tokio runtime.
+///
+/// [`Input`]: Input
+/// [`AsyncRead`]: tokio::io::AsyncRead
+/// [`from_async_seekable_reader`]: from_async_seekable_reader
+pub fn from_async_reader<R>(reader: R, hint: InputHint) -> Input
+where
+    R: AsyncRead + Send + 'static,
+{
+    hint.into_input(Reader::Extension(Box::new(AsyncSource::new(
+        NoSeek(Box::pin(reader)),
+        false,
+    ))))
+}
+
+/// Creates a seekable [`Input`] from any [`AsyncRead`] + [`AsyncSeek`].
+///
+/// # Panics
+/// Panics if called outside of a Tokio runtime.
+///
+/// [`Input`]: Input
+/// [`AsyncRead`]: tokio::io::AsyncRead
+/// [`AsyncSeek`]: tokio::io::AsyncSeek
+pub fn from_async_seekable_reader<R>(reader: R, hint: InputHint) -> Input
+where
+    R: AsyncRead + AsyncSeek + Send + 'static,
+{
+    hint.into_input(Reader::Extension(Box::new(AsyncSource::new(
+        Box::pin(reader),
+        true,
+    ))))
+}
+
+fn unseekable() -> IoError {
+    IoError::new(
+        IoErrorKind::InvalidInput,
+        "Seeking not supported on Reader of this type.",
+    )
+}
+
+/// Adapter giving readers which cannot seek a [`Seek`] implementation which always fails.
+struct NoSeek<R>(R);
+
+impl<R: Read> Read for NoSeek<R> {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl<R> Seek for NoSeek<R> {
+    fn seek(&mut self, _pos: SeekFrom) -> IoResult<u64> {
+        Err(unseekable())
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for NoSeek<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<IoResult<()>> {
+        Pin::new(&mut self.get_mut().0).poll_read(cx, buf)
+    }
+}
+
+impl<R: Unpin> AsyncSeek for NoSeek<R> {
+    fn start_seek(self: Pin<&mut Self>, _position: SeekFrom) -> IoResult<()> {
+        Err(unseekable())
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<IoResult<u64>> {
+        Poll::Ready(Err(unseekable()))
+    }
+}
+
+// `MediaSource` requires `Sync`, which plain readers need not be.
+struct BlockingSource<R> {
+    inner: Mutex<R>,
+    seekable: bool,
+}
+
+impl<R: Read> Read for BlockingSource<R> {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        self.inner.get_mut().read(buf)
+    }
+}
+
+impl<R: Seek> Seek for BlockingSource<R> {
+    fn seek(&mut self, pos: SeekFrom) -> IoResult<u64> {
+        self.inner.get_mut().seek(pos)
+    }
+}
+
+impl<R: Read + Seek + Send> MediaSource for BlockingSource<R> {
+    fn is_seekable(&self) -> bool {
+        self.seekable
+    }
+
+    fn byte_len(&self) -> Option<u64> {
+        None
+    }
+}
+
+enum Request {
+    Read(usize),
+    Seek(SeekFrom),
+}
+
+enum Response {
+    Read(IoResult<Vec<u8>>),
+    Seek(IoResult<u64>),
+}
+
+/// Blocking view of an async reader, which is driven by a separate task.
+struct AsyncSource {
+    tx: Sender<Request>,
+    rx: Receiver<Response>,
+    seekable: bool,
+}
+
+impl AsyncSource {
+    fn new<R>(reader: R, seekable: bool) -> Self
+    where
+        R: AsyncRead + AsyncSeek + Send + Unpin + 'static,
+    {
+        let (tx, req_rx) = flume::bounded(1);
+        let (resp_tx, rx) = flume::bounded(1);
+
+        Handle::current().spawn(async_runner(reader, req_rx, resp_tx));
+
+        Self { tx, rx, seekable }
+    }
+
+    fn request(&self, req: Request) -> IoResult<Response> {
+        let closed = || IoError::new(IoErrorKind::BrokenPipe, "Async reader task has exited.");
+
+        self.tx.send(req).map_err(|_| closed())?;
+        self.rx.recv().map_err(|_| closed())
+    }
+}
+
+async fn async_runner<R>(mut reader: R, rx: Receiver<Request>, tx: Sender<Response>)
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    while let Ok(req) = rx.recv_async().await {
+        let resp = match req {
+            Request::Read(len) => {
+                let mut buf = vec![0u8; len];
+                Response::Read(reader.read(&mut buf).await.map(|len| {
+                    buf.truncate(len);
+                    buf
+                }))
+            },
+            Request::Seek(pos) => Response::Seek(reader.seek(pos).await),
+        };
+
+        if tx.send_async(resp).await.is_err() {
+            break;
+        }
+    }
+}
+
+impl Read for AsyncSource {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        match self.request(Request::Read(buf.len()))? {
+            Response::Read(data) => {
+                let data = data?;
+                buf[..data.len()].copy_from_slice(&data);
+                Ok(data.len())
+            },
+            Response::Seek(_) => unreachable!("Reads are always answered in order."),
+        }
+    }
+}
+
+impl Seek for AsyncSource {
+    fn seek(&mut self, pos: SeekFrom) -> IoResult<u64> {
+        if !self.seekable {
+            return Err(unseekable());
+        }
+
+        match self.request(Request::Seek(pos))? {
+            Response::Seek(result) => result,
+            Response::Read(_) => unreachable!("Seeks are always answered in order."),
+        }
+    }
+}
+
+impl MediaSource for AsyncSource {
+    fn is_seekable(&self) -> bool {
+        self.seekable
+    }
+
+    fn byte_len(&self) -> Option<u64> {
+        None
+    }
+}
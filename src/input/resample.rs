@@ -0,0 +1,165 @@
+//! Conversion of PCM sources at other sample rates to the mixer's 48kHz.
+
+use crate::constants::SAMPLE_RATE_RAW;
+use std::{
+    io::{ErrorKind as IoErrorKind, Result as IoResult},
+    mem,
+};
+
+const SAMPLE_LEN: usize = mem::size_of::<f32>();
+
+/// Size of each block read from the source when more samples are needed.
+const CHUNK_LEN: usize = 4096;
+
+/// Streaming linear-interpolation resampler, converting interleaved `f32` samples
+/// at `source_rate` into 48kHz.
+#[derive(Debug)]
+pub(crate) struct Resampler {
+    source_rate: u32,
+    channels: usize,
+    /// Source frames per output frame.
+    step: f64,
+    /// Decoded source samples, interleaved.
+    frames: Vec<f32>,
+    /// Bytes of an incomplete sample read from the source.
+    partial: Vec<u8>,
+    /// Position of the next output frame, in source frames from the start of `frames`.
+    phase: f64,
+    /// Channel of the next output sample.
+    channel: usize,
+    finished: bool,
+    /// Output position, in bytes.
+    position: u64,
+}
+
+impl Resampler {
+    pub(crate) fn new(source_rate: u32, stereo: bool) -> Self {
+        Self {
+            source_rate,
+            channels: if stereo { 2 } else { 1 },
+            step: f64::from(source_rate) / SAMPLE_RATE_RAW as f64,
+            frames: Vec::new(),
+            partial: Vec::new(),
+            phase: 0.0,
+            channel: 0,
+            finished: false,
+            position: 0,
+        }
+    }
+
+    /// Creates a resampler with the same parameters, starting from the beginning of a source.
+    pub(crate) fn fresh(&self) -> Self {
+        Self::new(self.source_rate, self.channels == 2)
+    }
+
+    pub(crate) fn source_rate(&self) -> u32 {
+        self.source_rate
+    }
+
+    /// Returns the number of output bytes produced since the start of the source.
+    pub(crate) fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Converts an output byte offset into the (frame-aligned) source offset holding that audio.
+    pub(crate) fn source_offset(&self, output: u64) -> u64 {
+        let frame_len = (self.channels * SAMPLE_LEN) as u64;
+        let frames = ((output / frame_len) as f64 * self.step) as u64;
+
+        frames * frame_len
+    }
+
+    /// Clears all buffered audio after the source has been moved to `source` bytes,
+    /// returning the matching output position.
+    pub(crate) fn reset(&mut self, source: u64) -> u64 {
+        let frame_len = (self.channels * SAMPLE_LEN) as u64;
+        let frames = ((source / frame_len) as f64 / self.step).ceil() as u64;
+
+        self.frames.clear();
+        self.partial.clear();
+        self.phase = 0.0;
+        self.channel = 0;
+        self.finished = false;
+        self.position = frames * frame_len;
+
+        self.position
+    }
+
+    /// Fills `buffer` with little-endian `f32` samples at 48kHz, reading
+    /// source samples from `src` as needed.
+    pub(crate) fn read(
+        &mut self,
+        buffer: &mut [u8],
+        mut src: impl FnMut(&mut [u8]) -> IoResult<usize>,
+    ) -> IoResult<usize> {
+        let ch = self.channels;
+        let mut written = 0;
+
+        'output: while buffer.len() - written >= SAMPLE_LEN {
+            let idx = self.phase as usize;
+
+            while !self.finished && self.frames.len() < (idx + 2) * ch {
+                match self.fill(&mut src) {
+                    Ok(()) => {},
+                    Err(e) if e.kind() == IoErrorKind::Interrupted => {},
+                    // Return the audio produced so far: a persistent error recurs on the next read.
+                    Err(_) if written > 0 => break 'output,
+                    Err(e) => return Err(e),
+                }
+            }
+
+            let available = self.frames.len() / ch;
+            if idx >= available {
+                break;
+            }
+
+            let a = self.frames[idx * ch + self.channel];
+            let b = if idx + 1 < available {
+                self.frames[(idx + 1) * ch + self.channel]
+            } else {
+                a
+            };
+            let frac = (self.phase - idx as f64) as f32;
+
+            buffer[written..written + SAMPLE_LEN]
+                .copy_from_slice(&(a + (b - a) * frac).to_le_bytes());
+            written += SAMPLE_LEN;
+
+            self.channel += 1;
+            if self.channel == ch {
+                self.channel = 0;
+                self.phase += self.step;
+            }
+        }
+
+        // Discard source frames which no later output sample will need.
+        let consumed = (self.phase as usize).min(self.frames.len() / ch);
+        self.frames.drain(..consumed * ch);
+        self.phase -= consumed as f64;
+
+        self.position += written as u64;
+        Ok(written)
+    }
+
+    fn fill(&mut self, src: &mut impl FnMut(&mut [u8]) -> IoResult<usize>) -> IoResult<()> {
+        let mut scratch = [0u8; CHUNK_LEN];
+        let len = src(&mut scratch)?;
+
+        if len == 0 {
+            self.finished = true;
+            return Ok(());
+        }
+
+        self.partial.extend_from_slice(&scratch[..len]);
+
+        let whole = self.partial.len() - self.partial.len() % SAMPLE_LEN;
+        self.frames.extend(
+            self.partial[..whole]
+                .chunks_exact(SAMPLE_LEN)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+        );
+        self.partial.drain(..whole);
+
+        Ok(())
+    }
+}
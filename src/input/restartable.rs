@@ -117,6 +117,21 @@ impl Restartable {
         Self::new(FfmpegRestarter { path }, lazy).await
     }
 
+    /// Create a new restartable ffmpeg source for a remote file served over HTTP(S).
+    ///
+    /// The server is first sent a `HEAD` request. If it advertises
+    /// `Accept-Ranges: bytes` and a `Content-Length`, each seek maps the target
+    /// time to a byte offset from the file's duration (approximate for VBR audio),
+    /// and fetches only the remainder of the file via a range request. Servers
+    /// without range support still work, but each seek will re-download the file
+    /// and decode it up to the target.
+    ///
+    /// Requires the `"http"` feature.
+    #[cfg(feature = "http")]
+    pub async fn http(url: impl Into<String>, lazy: bool) -> Result<Self> {
+        Self::new(super::http_src::HttpRestarter::new(url.into()), lazy).await
+    }
+
     /// Create a new restartable ytdl source.
     ///
     /// The cost of restarting and seeking will probably be *very* high:
@@ -140,6 +155,39 @@ impl Restartable {
         self.async_handle = Some(handle);
     }
 
+    /// Replaces a live source with a fresh instance, resuming from the current position.
+    ///
+    /// This is used to recover from errors raised mid-stream by the inner source.
+    pub(crate) fn recreate(&mut self) -> IoResult<()> {
+        if let LazyProgress::Live(input, rec) = &mut self.source {
+            if let Some(rec) = rec.take() {
+                self.source = regenerate_channel(
+                    rec,
+                    self.position,
+                    input.stereo,
+                    input.kind.clone(),
+                    input.container,
+                    self.async_handle.clone(),
+                )?;
+
+                return Ok(());
+            }
+        }
+
+        Err(IoError::new(
+            IoErrorKind::InvalidInput,
+            "Restartable can only be recreated while live.",
+        ))
+    }
+
+    /// Returns the metadata of the current inner source, if it is live.
+    pub(crate) fn live_metadata(&self) -> Option<&Metadata> {
+        match &self.source {
+            LazyProgress::Live(input, _) => Some(&input.metadata),
+            _ => None,
+        }
+    }
+
     pub(crate) fn make_playable(&mut self) {
         if matches!(self.source, LazyProgress::Dead(_, _, _, _)) {
             // This read triggers creation of a source, and is guaranteed not to modify any internals.
@@ -311,7 +359,7 @@ impl Read for Restartable {
                     },
                     Ok(Err(source_error)) => {
                         let e = Err(IoError::new(
-                            IoErrorKind::UnexpectedEof,
+                            IoErrorKind::Other,
                             format!("Failed to create new reader: {:?}.", source_error),
                         ));
                         (e, false, None)
@@ -325,7 +373,7 @@ impl Read for Restartable {
                     },
                     Err(_) => {
                         let e = Err(IoError::new(
-                            IoErrorKind::UnexpectedEof,
+                            IoErrorKind::Other,
                             "Failed to create new reader: dropped.",
                         ));
                         (e, false, None)
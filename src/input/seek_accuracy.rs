@@ -0,0 +1,21 @@
+use std::time::Duration;
+
+/// How precisely an [`Input`] can seek to a requested position.
+///
+/// [`Input`]: super::Input
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum SeekAccuracy {
+    /// Seeks land on the exact sample requested.
+    ///
+    /// In-memory and user-provided seekable sources are sample-accurate.
+    Exact,
+    /// Seeks land within the given distance of the requested position.
+    ///
+    /// Restartable sources seek by restarting their underlying process
+    /// (e.g., ffmpeg) at the requested time, whose accuracy depends on
+    /// the source's container and codec.
+    Approximate(Duration),
+    /// The source cannot seek.
+    Unsupported,
+}
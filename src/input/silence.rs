@@ -0,0 +1,91 @@
+use super::{utils, Codec, Container, Input, Metadata, Reader};
+use std::{
+    io::{Error as IoError, ErrorKind as IoErrorKind, Read, Result as IoResult, Seek, SeekFrom},
+    time::Duration,
+};
+use symphonia_core::io::MediaSource;
+
+/// Creates an [`Input`] which plays silence for `duration`, and then ends.
+///
+/// Enqueuing one of these between other tracks inserts a deliberate gap
+/// into a playlist. The returned source is seekable, and reports its
+/// length via [`Metadata::duration`].
+///
+/// [`Input`]: Input
+/// [`Metadata::duration`]: Metadata::duration
+pub fn silence(duration: Duration) -> Input {
+    let byte_len = utils::timestamp_to_byte_count(duration, false) as u64;
+
+    let metadata = Metadata {
+        channels: Some(1),
+        duration: Some(duration),
+        sample_rate: Some(48_000),
+        ..Default::default()
+    };
+
+    Input::new(
+        false,
+        Reader::Extension(Box::new(Silence { byte_len, pos: 0 })),
+        Codec::FloatPcm,
+        Container::Raw,
+        Some(metadata),
+    )
+}
+
+struct Silence {
+    byte_len: u64,
+    pos: u64,
+}
+
+impl Read for Silence {
+    fn read(&mut self, buffer: &mut [u8]) -> IoResult<usize> {
+        let remaining = self.byte_len.saturating_sub(self.pos);
+        let len = buffer.len().min(remaining as usize);
+
+        for el in buffer[..len].iter_mut() {
+            *el = 0;
+        }
+        self.pos += len as u64;
+
+        Ok(len)
+    }
+}
+
+impl Seek for Silence {
+    fn seek(&mut self, pos: SeekFrom) -> IoResult<u64> {
+        let target = match pos {
+            SeekFrom::Start(n) => Some(n),
+            SeekFrom::End(n) => checked_offset(self.byte_len, n),
+            SeekFrom::Current(n) => checked_offset(self.pos, n),
+        };
+
+        let target = target.ok_or_else(|| {
+            IoError::new(
+                IoErrorKind::InvalidInput,
+                "Tried to seek before the start of the stream.",
+            )
+        })?;
+
+        self.pos = target.min(self.byte_len);
+
+        Ok(self.pos)
+    }
+}
+
+fn checked_offset(base: u64, offset: i64) -> Option<u64> {
+    if offset < 0 {
+        base.checked_sub(offset.unsigned_abs())
+    } else {
+        base.checked_add(offset as u64)
+    }
+}
+
+impl MediaSource for Silence {
+    fn is_seekable(&self) -> bool {
+        true
+    }
+
+    fn byte_len(&self) -> Option<u64> {
+        Some(self.byte_len)
+    }
+}
@@ -371,15 +371,21 @@ impl Songbird {
                 }
             },
             TwilightEvent::VoiceStateUpdate(v) => {
-                if v.0.user_id.get() != self.client_data.read().user_id.0 {
-                    return;
-                }
-
                 let call = v.0.guild_id.map(GuildId::from).and_then(|id| self.get(id));
 
                 if let Some(call) = call {
                     let mut handler = call.lock().await;
-                    handler.update_state(v.0.session_id.clone(), v.0.channel_id);
+
+                    if v.0.user_id.get() == self.client_data.read().user_id.0 {
+                        handler.update_state(v.0.session_id.clone(), v.0.channel_id);
+                    } else {
+                        #[cfg(feature = "driver-core")]
+                        handler.update_voice_state(
+                            v.0.user_id,
+                            v.0.channel_id,
+                            v.0.member.as_ref().map_or(false, |m| m.user.bot),
+                        );
+                    }
                 }
             },
             _ => {},
@@ -427,13 +433,19 @@ impl VoiceGatewayManager for Songbird {
     }
 
     async fn state_update(&self, guild_id: SerenityGuild, voice_state: &VoiceState) {
-        if voice_state.user_id.0 != self.client_data.read().user_id.0 {
-            return;
-        }
-
         if let Some(call) = self.get(guild_id) {
             let mut handler = call.lock().await;
-            handler.update_state(voice_state.session_id.clone(), voice_state.channel_id);
+
+            if voice_state.user_id.0 == self.client_data.read().user_id.0 {
+                handler.update_state(voice_state.session_id.clone(), voice_state.channel_id);
+            } else {
+                #[cfg(feature = "driver-core")]
+                handler.update_voice_state(
+                    voice_state.user_id,
+                    voice_state.channel_id,
+                    voice_state.member.as_ref().map_or(false, |m| m.user.bot),
+                );
+            }
         }
     }
 }
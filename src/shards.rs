@@ -154,6 +154,22 @@ impl Clone for Shard {
     }
 }
 
+/// Additional, unofficial flags sent as part of a voice state update.
+///
+/// **Experimental**: these fields are not documented as part of the bot API, and
+/// Discord may ignore or reject them. They are sent only through Serenity shards
+/// and [`VoiceUpdate`] implementors which override
+/// [`update_voice_state_with_flags`]; Twilight shards ignore them.
+///
+/// [`update_voice_state_with_flags`]: VoiceUpdate::update_voice_state_with_flags
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct VoiceFlags {
+    /// Whether the current user is sharing video.
+    pub self_video: bool,
+    /// Whether the current user is streaming (i.e., "Go Live").
+    pub self_stream: bool,
+}
+
 #[async_trait]
 impl VoiceUpdate for Shard {
     async fn update_voice_state(
@@ -162,6 +178,24 @@ impl VoiceUpdate for Shard {
         channel_id: Option<ChannelId>,
         self_deaf: bool,
         self_mute: bool,
+    ) -> JoinResult<()> {
+        self.update_voice_state_with_flags(
+            guild_id,
+            channel_id,
+            self_deaf,
+            self_mute,
+            Default::default(),
+        )
+        .await
+    }
+
+    async fn update_voice_state_with_flags(
+        &self,
+        guild_id: GuildId,
+        channel_id: Option<ChannelId>,
+        self_deaf: bool,
+        self_mute: bool,
+        flags: VoiceFlags,
     ) -> JoinResult<()> {
         let nz_guild_id = NonZeroU64::new(guild_id.0).ok_or(JoinError::IllegalGuild)?;
 
@@ -180,6 +214,8 @@ impl VoiceUpdate for Shard {
                         "guild_id": guild_id.0,
                         "self_deaf": self_deaf,
                         "self_mute": self_mute,
+                        "self_video": flags.self_video,
+                        "self_stream": flags.self_stream,
                     }
                 });
 
@@ -201,7 +237,7 @@ impl VoiceUpdate for Shard {
                 Ok(())
             },
             Shard::Generic(g) =>
-                g.update_voice_state(guild_id, channel_id, self_deaf, self_mute)
+                g.update_voice_state_with_flags(guild_id, channel_id, self_deaf, self_mute, flags)
                     .await,
         }
     }
@@ -228,6 +264,24 @@ pub trait VoiceUpdate {
         self_deaf: bool,
         self_mute: bool,
     ) -> JoinResult<()>;
+
+    /// Send a voice update message to the inner shard handle, including
+    /// additional [`VoiceFlags`].
+    ///
+    /// The default implementation ignores `flags`, and calls [`update_voice_state`].
+    ///
+    /// [`update_voice_state`]: VoiceUpdate::update_voice_state
+    async fn update_voice_state_with_flags(
+        &self,
+        guild_id: GuildId,
+        channel_id: Option<ChannelId>,
+        self_deaf: bool,
+        self_mute: bool,
+        _flags: VoiceFlags,
+    ) -> JoinResult<()> {
+        self.update_voice_state(guild_id, channel_id, self_deaf, self_mute)
+            .await
+    }
 }
 
 #[cfg(feature = "serenity")]
@@ -0,0 +1,82 @@
+/// Frames quieter than this level (in dBFS) are treated as silence, and
+/// leave the current gain unchanged.
+const SILENCE_GATE_DBFS: f32 = -60.0;
+
+/// Largest boost (in dB) which will be applied to quiet audio.
+const MAX_BOOST_DB: f32 = 20.0;
+
+/// Proportion of the distance to the target gain covered each frame.
+///
+/// At 50 frames per second, this settles within roughly half a second.
+const SMOOTHING: f32 = 0.1;
+
+/// Running loudness estimate used to steer a [`Track`] towards a target level.
+///
+/// [`Track`]: super::Track
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct AutoGain {
+    target_dbfs: f32,
+    gain: f32,
+}
+
+impl AutoGain {
+    pub(crate) fn new(target_dbfs: f32) -> Self {
+        Self {
+            target_dbfs,
+            gain: 1.0,
+        }
+    }
+
+    /// Measures the level of one frame of (unscaled) audio, and returns the
+    /// smoothed gain to apply to it.
+    pub(crate) fn process(&mut self, frame: &[f32]) -> f32 {
+        if frame.is_empty() {
+            return self.gain;
+        }
+
+        let mean_square = frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32;
+        let level_dbfs = 10.0 * mean_square.log10();
+
+        if level_dbfs > SILENCE_GATE_DBFS {
+            let correction_db = (self.target_dbfs - level_dbfs).min(MAX_BOOST_DB);
+            let desired = 10f32.powf(correction_db / 20.0);
+
+            self.gain += SMOOTHING * (desired - self.gain);
+        }
+
+        self.gain
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{constants::STEREO_FRAME_SIZE, input::Input, tracks};
+
+    #[test]
+    fn manual_volume_disengages_auto_gain() {
+        let (mut track, _handle) =
+            tracks::create_player(Input::float_pcm(true, Vec::<u8>::new().into()));
+
+        track.set_auto_gain(-14.0);
+        assert!(track.auto_gain.is_some());
+
+        track.set_volume(0.5);
+        assert!(track.auto_gain.is_none());
+    }
+
+    #[test]
+    fn gain_converges_on_target_level() {
+        // A constant level of 0.1 sits at -20 dBFS.
+        let frame = [0.1; STEREO_FRAME_SIZE];
+        let mut auto_gain = AutoGain::new(-10.0);
+
+        let mut gain = 1.0;
+        for _ in 0..100 {
+            gain = auto_gain.process(&frame);
+        }
+
+        let output_dbfs = 20.0 * (0.1 * gain).log10();
+        assert!((output_dbfs - -10.0).abs() < 0.01);
+    }
+}
@@ -0,0 +1,82 @@
+use crate::events::EventId;
+use std::time::Duration;
+
+/// A linear change in a [`Track`]'s volume, over a span of its play time.
+///
+/// [`Track`]: super::Track
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct VolumeRamp {
+    from: f32,
+    to: f32,
+    start: Duration,
+    duration: Duration,
+}
+
+impl VolumeRamp {
+    pub(crate) fn new(from: f32, to: f32, start: Duration, duration: Duration) -> Self {
+        Self {
+            from,
+            to,
+            start,
+            duration,
+        }
+    }
+
+    /// Returns the volume at play time `now`, and whether the ramp has finished.
+    pub(crate) fn volume_at(&self, now: Duration) -> (f32, bool) {
+        let progress = now.saturating_sub(self.start).as_secs_f32() / self.duration.as_secs_f32();
+
+        if progress >= 1.0 {
+            (self.to, true)
+        } else {
+            (self.from + progress * (self.to - self.from), false)
+        }
+    }
+}
+
+/// Volume keyframes over a [`Track`]'s timeline, interpolated linearly as it plays.
+///
+/// [`Track`]: super::Track
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct VolumeAutomation {
+    pub(crate) id: EventId,
+    pub(crate) keyframes: Vec<(Duration, f32)>,
+}
+
+impl VolumeAutomation {
+    /// Sorts `keyframes` by time, and clamps their volumes to be non-negative.
+    ///
+    /// Returns `None` if no keyframes are given.
+    pub(crate) fn new(id: EventId, mut keyframes: Vec<(Duration, f32)>) -> Option<Self> {
+        if keyframes.is_empty() {
+            return None;
+        }
+
+        keyframes.sort_by_key(|(time, _)| *time);
+        for (_, volume) in keyframes.iter_mut() {
+            *volume = volume.max(0.0);
+        }
+
+        Some(Self { id, keyframes })
+    }
+
+    /// Returns the volume at `position`.
+    ///
+    /// Positions before the first keyframe or after the last keep that keyframe's volume.
+    pub(crate) fn volume_at(&self, position: Duration) -> f32 {
+        let next = self
+            .keyframes
+            .partition_point(|(time, _)| *time <= position);
+
+        match (next.checked_sub(1), self.keyframes.get(next)) {
+            (Some(i), Some(&(end, end_vol))) => {
+                let (start, start_vol) = self.keyframes[i];
+                let progress = (position - start).as_secs_f32() / (end - start).as_secs_f32();
+
+                start_vol + progress * (end_vol - start_vol)
+            },
+            (Some(i), None) => self.keyframes[i].1,
+            (None, _) => self.keyframes[0].1,
+        }
+    }
+}
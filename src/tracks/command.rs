@@ -1,8 +1,16 @@
 use super::*;
-use crate::events::EventData;
+use crate::{
+    events::{EventData, EventId},
+    input::DownmixMatrix,
+};
 use flume::Sender;
 use std::time::Duration;
 
+/// Callback type used by [`TrackHandle::add_frame_tap`].
+///
+/// [`TrackHandle::add_frame_tap`]: TrackHandle::add_frame_tap
+pub type FrameTapFn = dyn FnMut(&[i16]) + Send + 'static;
+
 /// A request from external code using a [`TrackHandle`] to modify
 /// or act upon an [`Track`] object.
 ///
@@ -18,20 +26,74 @@ pub enum TrackCommand {
     Stop,
     /// Set the track's volume.
     Volume(f32),
+    /// Set the volume keyframes of this track.
+    VolumeAutomation(EventId, Vec<(Duration, f32)>),
+    /// Move the track's volume to a new value over the given duration.
+    VolumeRamp {
+        /// Volume reached at the end of the ramp.
+        target: f32,
+        /// Length of the ramp.
+        duration: Duration,
+    },
+    /// Set the track's pitch shift, in semitones.
+    Pitch(f32),
+    /// Set the track's playback speed.
+    Speed(f32),
+    /// Set the output channels the track is played through.
+    SetRoute(Route),
+    /// Set the track's position in the stereo field.
+    Pan(f32),
+    /// Mute or unmute this track.
+    SetMuted(bool),
+    /// Fade this track in over the given duration of playback.
+    FadeIn(Duration),
+    /// Fade this track out over the given duration before its end.
+    FadeOut(Duration),
+    /// Set the order in which the track's effects are applied.
+    SetEffectOrder(Vec<EffectKind>),
+    /// Set the bands of the track's equalizer.
+    Equalizer(Vec<EqBand>),
+    /// Set the Opus bandwidth hinted for the track.
+    SetBandwidth(Bandwidth),
+    /// Set how the track's multichannel source is folded into stereo.
+    SetDownmix(DownmixMatrix),
     /// Seek to the given duration.
     ///
     /// On unsupported input types, this can be fatal.
     Seek(Duration),
+    /// Seek to the given duration, replying with the position reached.
+    ///
+    /// On unsupported input types, this can be fatal.
+    SeekRequest(Duration, Sender<TrackResult<Duration>>),
+    /// Seek to the start of this track, and unpause it if needed.
+    ///
+    /// On unsupported input types, this can be fatal.
+    Replay,
     /// Register an event on this track.
     AddEvent(EventData),
+    /// Remove a previously registered event from this track.
+    RemoveEvent(EventId),
+    /// Register a named cue point on this track.
+    AddCue(EventId, Duration, String),
+    /// Request a list of this track's pending scheduled actions.
+    Scheduled(Sender<Vec<ScheduledAction>>),
+    /// Cancel the scheduled action with the given identifier.
+    CancelScheduled(EventId),
     /// Run some closure on this track, with direct access to the core object.
     Do(Box<dyn FnOnce(&mut Track) + Send + Sync + 'static>),
     /// Request a copy of this track's state.
     Request(Sender<TrackState>),
+    /// Update the handle's metadata from any newly discovered by the input,
+    /// replying once complete.
+    RefreshMetadata(Sender<()>),
+    /// Decode the next frames of a paused track, replying with their samples.
+    CaptureFrames(usize, Sender<TrackResult<Vec<Vec<f32>>>>),
     /// Change the loop count/strategy of this track.
     Loop(LoopState),
     /// Prompts a track's input to become live and usable, if it is not already.
     MakePlayable,
+    /// Register a callback to receive each frame of this track's decoded audio.
+    AddFrameTap(Box<FrameTapFn>),
 }
 
 impl std::fmt::Debug for TrackCommand {
@@ -45,12 +107,38 @@ impl std::fmt::Debug for TrackCommand {
                 Pause => "Pause".to_string(),
                 Stop => "Stop".to_string(),
                 Volume(vol) => format!("Volume({})", vol),
+                VolumeAutomation(id, keyframes) =>
+                    format!("VolumeAutomation({:?}, {:?})", id, keyframes),
+                VolumeRamp { target, duration } => format!(
+                    "VolumeRamp {{ target: {}, duration: {:?} }}",
+                    target, duration
+                ),
+                Pitch(semitones) => format!("Pitch({})", semitones),
+                Speed(speed) => format!("Speed({})", speed),
+                SetRoute(route) => format!("SetRoute({:?})", route),
+                Pan(pan) => format!("Pan({})", pan),
+                SetMuted(muted) => format!("SetMuted({})", muted),
+                FadeIn(duration) => format!("FadeIn({:?})", duration),
+                FadeOut(duration) => format!("FadeOut({:?})", duration),
+                SetEffectOrder(order) => format!("SetEffectOrder({:?})", order),
+                Equalizer(bands) => format!("Equalizer({:?})", bands),
+                SetBandwidth(bandwidth) => format!("SetBandwidth({:?})", bandwidth),
+                SetDownmix(matrix) => format!("SetDownmix({:?})", matrix),
                 Seek(d) => format!("Seek({:?})", d),
+                SeekRequest(d, tx) => format!("SeekRequest({:?}, {:?})", d, tx),
                 AddEvent(evt) => format!("AddEvent({:?})", evt),
+                RemoveEvent(id) => format!("RemoveEvent({:?})", id),
+                AddCue(id, at, name) => format!("AddCue({:?}, {:?}, {:?})", id, at, name),
+                Scheduled(tx) => format!("Scheduled({:?})", tx),
+                CancelScheduled(id) => format!("CancelScheduled({:?})", id),
                 Do(_f) => "Do([function])".to_string(),
                 Request(tx) => format!("Request({:?})", tx),
+                Replay => "Replay".to_string(),
+                RefreshMetadata(tx) => format!("RefreshMetadata({:?})", tx),
+                CaptureFrames(n, tx) => format!("CaptureFrames({}, {:?})", n, tx),
                 Loop(loops) => format!("Loop({:?})", loops),
                 MakePlayable => "MakePlayable".to_string(),
+                AddFrameTap(_f) => "AddFrameTap([function])".to_string(),
             }
         )
     }
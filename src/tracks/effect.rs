@@ -0,0 +1,143 @@
+use std::f32::consts::{FRAC_PI_4, SQRT_2};
+
+/// A stage of the per-track audio processing applied in the mixer.
+///
+/// The order in which these are applied can be changed using [`Track::set_effect_order`].
+///
+/// [`Track::set_effect_order`]: super::Track::set_effect_order
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum EffectKind {
+    /// Parametric equalization, set by [`Track::set_equalizer`].
+    ///
+    /// [`Track::set_equalizer`]: super::Track::set_equalizer
+    Equalizer,
+    /// Pitch shifting, set by [`Track::set_pitch`].
+    ///
+    /// [`Track::set_pitch`]: super::Track::set_pitch
+    Pitch,
+    /// Output channel routing, set by [`Track::set_route`].
+    ///
+    /// [`Track::set_route`]: super::Track::set_route
+    Route,
+    /// Automatic gain control, set by [`Track::set_auto_gain`].
+    ///
+    /// [`Track::set_auto_gain`]: super::Track::set_auto_gain
+    AutoGain,
+    /// Volume, set by [`Track::set_volume`].
+    ///
+    /// [`Track::set_volume`]: super::Track::set_volume
+    Volume,
+    /// Stereo panning, set by [`Track::set_pan`].
+    ///
+    /// [`Track::set_pan`]: super::Track::set_pan
+    Pan,
+}
+
+/// Order in which each [`EffectKind`] is applied to a new track.
+///
+/// [`EffectKind`]: EffectKind
+pub const DEFAULT_EFFECT_ORDER: [EffectKind; 6] = [
+    EffectKind::Equalizer,
+    EffectKind::Pitch,
+    EffectKind::Route,
+    EffectKind::AutoGain,
+    EffectKind::Volume,
+    EffectKind::Pan,
+];
+
+/// Removes duplicates from `order`, and appends any missing effects in their default order.
+pub(crate) fn complete_effect_order(order: Vec<EffectKind>) -> Vec<EffectKind> {
+    let mut out = Vec::with_capacity(DEFAULT_EFFECT_ORDER.len());
+
+    for effect in order.into_iter().chain(DEFAULT_EFFECT_ORDER) {
+        if !out.contains(&effect) {
+            out.push(effect);
+        }
+    }
+
+    out
+}
+
+/// Scales the interleaved stereo samples in `frame` by a gain which moves
+/// linearly from `start` to `end`.
+pub(crate) fn apply_gain_ramp(frame: &mut [f32], start: f32, end: f32) {
+    let pairs = frame.len() / 2;
+
+    for (i, pair) in frame.chunks_exact_mut(2).enumerate() {
+        let gain = start + (end - start) * (i as f32 / pairs as f32);
+        pair.iter_mut().for_each(|s| *s *= gain);
+    }
+}
+
+/// Pans the interleaved stereo samples in `frame`, where `pan` runs from `-1.0`
+/// (full left) to `1.0` (full right).
+///
+/// For `mono` sources, whose channels are identical, both channels follow a
+/// constant-power (sine) law: the near channel is boosted by up to √2 as the
+/// opposite channel fades, so that loudness stays even across the stereo field.
+/// For stereo sources, the near channel is left untouched and the opposite
+/// channel is attenuated linearly to preserve the balance of the original mix.
+pub(crate) fn apply_pan(frame: &mut [f32], pan: f32, mono: bool) {
+    if pan == 0.0 {
+        return;
+    }
+
+    let (near, far) = if mono {
+        let angle = (1.0 - pan.abs()) * FRAC_PI_4;
+        (SQRT_2 * angle.cos(), SQRT_2 * angle.sin())
+    } else {
+        (1.0, 1.0 - pan.abs())
+    };
+    let attenuated = usize::from(pan < 0.0);
+
+    for pair in frame.chunks_exact_mut(2) {
+        pair[attenuated] *= far;
+        pair[1 - attenuated] *= near;
+    }
+}
+
+/// Scales every sample in `frame` by `gain`.
+pub(crate) fn apply_gain(frame: &mut [f32], gain: f32) {
+    if (gain - 1.0).abs() > f32::EPSILON {
+        frame.iter_mut().for_each(|s| *s *= gain);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn centred_pan_is_exact_pass_through() {
+        let original = [0.3, -0.7, 0.1, 0.9];
+
+        for mono in [true, false] {
+            let mut frame = original;
+            apply_pan(&mut frame, 0.0, mono);
+            assert_eq!(frame, original);
+        }
+    }
+
+    #[test]
+    fn full_pan_silences_opposite_channel() {
+        for mono in [true, false] {
+            let mut right = [0.5; 4];
+            apply_pan(&mut right, 1.0, mono);
+            assert!(right.chunks_exact(2).all(|p| p[0] == 0.0 && p[1] > 0.0));
+
+            let mut left = [0.5; 4];
+            apply_pan(&mut left, -1.0, mono);
+            assert!(left.chunks_exact(2).all(|p| p[1] == 0.0 && p[0] > 0.0));
+        }
+    }
+
+    #[test]
+    fn mono_pan_keeps_constant_power() {
+        for pan in [-1.0, -0.5, 0.25, 1.0] {
+            let mut frame = [1.0, 1.0];
+            apply_pan(&mut frame, pan, true);
+            assert!((frame[0].powi(2) + frame[1].powi(2) - 2.0).abs() < 1e-5);
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use crate::constants::SAMPLE_RATE_RAW;
+use std::f32::consts::PI;
+
+/// One band of a [`Track`]'s parametric equalizer, boosting or cutting the
+/// frequencies around `freq`.
+///
+/// See [`Track::set_equalizer`].
+///
+/// [`Track`]: super::Track
+/// [`Track::set_equalizer`]: super::Track::set_equalizer
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct EqBand {
+    /// Centre frequency of the band, in Hz.
+    ///
+    /// This is clamped between 20Hz and 20kHz.
+    pub freq: f32,
+    /// Gain applied at the centre frequency, in decibels.
+    ///
+    /// Positive values boost the band, and negative values cut it.
+    pub gain_db: f32,
+    /// Quality factor of the band, where higher values affect a narrower
+    /// range of frequencies.
+    ///
+    /// This is raised to at least `0.1`.
+    pub q: f32,
+}
+
+impl EqBand {
+    /// Creates a band centred on `freq` Hz, with the given gain and quality factor.
+    pub fn new(freq: f32, gain_db: f32, q: f32) -> Self {
+        Self { freq, gain_db, q }
+    }
+
+    /// Returns whether this band has any effect on audio.
+    fn is_engaged(&self) -> bool {
+        self.freq.is_finite()
+            && self.q.is_finite()
+            && self.gain_db.is_finite()
+            && self.gain_db != 0.0
+    }
+}
+
+/// Peaking biquad filter for one [`EqBand`], holding separate state for
+/// each stereo channel.
+#[derive(Clone, Debug)]
+struct Biquad {
+    b: [f32; 3],
+    a: [f32; 2],
+    state: [[f32; 2]; 2],
+}
+
+impl Biquad {
+    fn new(band: &EqBand) -> Self {
+        let mut out = Self {
+            b: [1.0, 0.0, 0.0],
+            a: [0.0, 0.0],
+            state: [[0.0; 2]; 2],
+        };
+        out.set_band(band);
+
+        out
+    }
+
+    /// Recomputes the filter's coefficients, keeping its state so that the
+    /// band can change without a discontinuity.
+    fn set_band(&mut self, band: &EqBand) {
+        let freq = band.freq.clamp(20.0, 20_000.0);
+        let q = band.q.max(0.1);
+
+        let amp = 10f32.powf(band.gain_db / 40.0);
+        let w0 = 2.0 * PI * freq / SAMPLE_RATE_RAW as f32;
+        let alpha = w0.sin() / (2.0 * q);
+        let cos_w0 = w0.cos();
+
+        let a0 = 1.0 + alpha / amp;
+        self.b = [
+            (1.0 + alpha * amp) / a0,
+            (-2.0 * cos_w0) / a0,
+            (1.0 - alpha * amp) / a0,
+        ];
+        self.a = [(-2.0 * cos_w0) / a0, (1.0 - alpha / amp) / a0];
+    }
+
+    fn process(&mut self, frame: &mut [f32]) {
+        for pair in frame.chunks_exact_mut(2) {
+            for (sample, state) in pair.iter_mut().zip(self.state.iter_mut()) {
+                let x = *sample;
+                let y = self.b[0] * x + state[0];
+
+                state[0] = self.b[1] * x - self.a[0] * y + state[1];
+                state[1] = self.b[2] * x - self.a[1] * y;
+                *sample = y;
+            }
+        }
+    }
+}
+
+/// Chain of peaking filters applied to a [`Track`], one per [`EqBand`].
+///
+/// [`Track`]: super::Track
+#[derive(Clone, Debug)]
+pub(crate) struct Equalizer {
+    bands: Vec<EqBand>,
+    filters: Vec<Biquad>,
+}
+
+impl Equalizer {
+    /// Creates an equalizer from `bands`, or returns `None` if no band would
+    /// affect the audio.
+    pub(crate) fn new(bands: &[EqBand]) -> Option<Self> {
+        let mut out = Self {
+            bands: Vec::new(),
+            filters: Vec::new(),
+        };
+
+        if out.set_bands(bands) {
+            Some(out)
+        } else {
+            None
+        }
+    }
+
+    /// Replaces this equalizer's bands, keeping the state of existing filters.
+    ///
+    /// Returns whether any band affects the audio.
+    pub(crate) fn set_bands(&mut self, bands: &[EqBand]) -> bool {
+        let engaged: Vec<EqBand> = bands.iter().copied().filter(EqBand::is_engaged).collect();
+
+        self.filters.truncate(engaged.len());
+        for (i, band) in engaged.iter().enumerate() {
+            match self.filters.get_mut(i) {
+                Some(filter) => filter.set_band(band),
+                None => self.filters.push(Biquad::new(band)),
+            }
+        }
+
+        self.bands = bands.to_vec();
+
+        !self.filters.is_empty()
+    }
+
+    pub(crate) fn bands(&self) -> &[EqBand] {
+        &self.bands
+    }
+
+    /// Filters one frame of interleaved stereo audio, in place.
+    pub(crate) fn process(&mut self, frame: &mut [f32]) {
+        for filter in self.filters.iter_mut() {
+            filter.process(frame);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flat_bands_bypass_equalizer() {
+        assert!(Equalizer::new(&[]).is_none());
+        assert!(
+            Equalizer::new(&[EqBand::new(100.0, 0.0, 1.0), EqBand::new(5_000.0, 0.0, 2.0)])
+                .is_none()
+        );
+        assert!(Equalizer::new(&[EqBand::new(100.0, 3.0, 1.0)]).is_some());
+    }
+
+    #[test]
+    fn filter_state_persists_across_buffers() {
+        let bands = [
+            EqBand::new(200.0, 6.0, 0.7),
+            EqBand::new(4_000.0, -4.0, 1.5),
+        ];
+        let signal: Vec<f32> = (0..1920)
+            .map(|i| ((i as f32) * 0.05).sin() * 0.5 + ((i as f32) * 0.7).cos() * 0.25)
+            .collect();
+
+        let mut whole = signal.clone();
+        Equalizer::new(&bands).unwrap().process(&mut whole);
+
+        let mut halves = signal;
+        let mut eq = Equalizer::new(&bands).unwrap();
+        let (first, second) = halves.split_at_mut(960);
+        eq.process(first);
+        eq.process(second);
+
+        assert_eq!(whole, halves);
+    }
+}
@@ -1,4 +1,5 @@
-use std::{error::Error, fmt};
+use std::{borrow::Cow, error::Error, fmt};
+use uuid::Uuid;
 
 /// Errors associated with control and manipulation of tracks.
 ///
@@ -17,6 +18,12 @@ pub enum TrackError {
     ///
     /// [`Input`]: crate::input::Input
     SeekUnsupported,
+    /// The supplied volume was NaN or infinite.
+    InvalidVolume,
+    /// The operation is only valid while the track is paused.
+    NotPaused,
+    /// The track's metadata did not list a chapter with the requested index.
+    NoSuchChapter,
 }
 
 impl fmt::Display for TrackError {
@@ -28,6 +35,9 @@ impl fmt::Display for TrackError {
                 write!(f, "given event listener can't be fired on a track")
             },
             TrackError::SeekUnsupported => write!(f, "track did not support seeking"),
+            TrackError::InvalidVolume => write!(f, "volume must be a finite number"),
+            TrackError::NotPaused => write!(f, "track must be paused"),
+            TrackError::NoSuchChapter => write!(f, "track has no chapter with that index"),
         }
     }
 }
@@ -38,3 +48,92 @@ impl Error for TrackError {}
 ///
 /// [`TrackHandle`]: super::TrackHandle
 pub type TrackResult<T> = Result<T, TrackError>;
+
+/// Errors associated with control and manipulation of a [`TrackQueue`].
+///
+/// [`TrackQueue`]: super::TrackQueue
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum QueueError {
+    /// The given index does not refer to any track in the queue.
+    IndexOutOfRange {
+        /// The requested index.
+        index: usize,
+        /// The length of the queue at the time of the request.
+        len: usize,
+    },
+    /// The queue already holds its maximum number of tracks.
+    ///
+    /// See [`TrackQueue::set_max_len`].
+    ///
+    /// [`TrackQueue::set_max_len`]: super::TrackQueue::set_max_len
+    Full {
+        /// The queue's configured maximum length.
+        max_len: usize,
+    },
+    /// The queue's [`EnqueueGuard`] refused to accept a track, for the given reason.
+    ///
+    /// See [`TrackQueue::set_enqueue_guard`].
+    ///
+    /// [`EnqueueGuard`]: super::EnqueueGuard
+    /// [`TrackQueue::set_enqueue_guard`]: super::TrackQueue::set_enqueue_guard
+    Rejected(Cow<'static, str>),
+    /// The track with the given UUID cannot seek, so cannot be repeated by a
+    /// queue.
+    ///
+    /// See [`TrackQueue::set_repeat`].
+    ///
+    /// [`TrackQueue::set_repeat`]: super::TrackQueue::set_repeat
+    NotSeekable(Uuid),
+    /// The currently playing track cannot be moved, nor can other tracks be
+    /// moved in front of it.
+    ///
+    /// See [`TrackQueue::move_track`].
+    ///
+    /// [`TrackQueue::move_track`]: super::TrackQueue::move_track
+    CurrentTrack,
+    /// A command could not be sent to a queued track.
+    Track(TrackError),
+}
+
+impl fmt::Display for QueueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to operate on track queue: ")?;
+        match self {
+            QueueError::IndexOutOfRange { index, len } => write!(
+                f,
+                "index {} out of range for queue of length {}",
+                index, len
+            ),
+            QueueError::Full { max_len } => {
+                write!(f, "queue is full (maximum of {} tracks)", max_len)
+            },
+            QueueError::Rejected(reason) => write!(f, "track rejected ({})", reason),
+            QueueError::NotSeekable(uuid) => {
+                write!(f, "track {} cannot seek, so cannot be repeated", uuid)
+            },
+            QueueError::CurrentTrack => write!(f, "cannot move the current track"),
+            QueueError::Track(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for QueueError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            QueueError::Track(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<TrackError> for QueueError {
+    fn from(e: TrackError) -> Self {
+        QueueError::Track(e)
+    }
+}
+
+/// Alias for fallible calls to a [`TrackQueue`].
+///
+/// [`TrackQueue`]: super::TrackQueue
+pub type QueueResult<T> = Result<T, QueueError>;
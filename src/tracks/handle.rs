@@ -1,14 +1,31 @@
 use super::*;
 use crate::{
-    events::{Event, EventData, EventHandler},
-    input::Metadata,
+    events::{Event, EventContext, EventData, EventHandler, EventId, TrackEvent},
+    input::{Chapter, Metadata, SeekAccuracy},
+};
+use async_trait::async_trait;
+use flume::{Receiver, Sender};
+use parking_lot::{Mutex, RwLock as PRwLock};
+use std::{
+    any::Any,
+    fmt,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
 };
-use flume::Sender;
-use std::{fmt, sync::Arc, time::Duration};
 use tokio::sync::RwLock;
 use typemap_rev::TypeMap;
 use uuid::Uuid;
 
+/// Arbitrary, user-defined data attached to a track.
+///
+/// See [`TrackHandle::user_data`].
+///
+/// [`TrackHandle::user_data`]: TrackHandle::user_data
+pub type UserData = Arc<dyn Any + Send + Sync>;
+
 #[derive(Clone, Debug)]
 /// Handle for safe control of a [`Track`] from other threads, outside
 /// of the audio mixing and voice handling context.
@@ -28,9 +45,14 @@ pub struct TrackHandle {
 struct InnerHandle {
     command_channel: Sender<TrackCommand>,
     seekable: bool,
+    seek_accuracy: SeekAccuracy,
     uuid: Uuid,
-    metadata: Box<Metadata>,
+    metadata: PRwLock<Arc<Metadata>>,
     typemap: RwLock<TypeMap>,
+    user_data: Mutex<Option<UserData>>,
+    position_nanos: AtomicU64,
+    passthrough: AtomicBool,
+    first_frame_latency: Mutex<Option<Duration>>,
 }
 
 impl fmt::Debug for InnerHandle {
@@ -38,9 +60,14 @@ impl fmt::Debug for InnerHandle {
         f.debug_struct("InnerHandle")
             .field("command_channel", &self.command_channel)
             .field("seekable", &self.seekable)
+            .field("seek_accuracy", &self.seek_accuracy)
             .field("uuid", &self.uuid)
-            .field("metadata", &self.metadata)
+            .field("metadata", &*self.metadata.read())
             .field("typemap", &"<LOCK>")
+            .field("user_data", &self.user_data.lock().is_some())
+            .field("position_nanos", &self.position_nanos)
+            .field("passthrough", &self.passthrough)
+            .field("first_frame_latency", &*self.first_frame_latency.lock())
             .finish()
     }
 }
@@ -55,13 +82,33 @@ impl TrackHandle {
         seekable: bool,
         uuid: Uuid,
         metadata: Box<Metadata>,
+    ) -> Self {
+        let seek_accuracy = if seekable {
+            SeekAccuracy::Exact
+        } else {
+            SeekAccuracy::Unsupported
+        };
+
+        Self::new_with_seek_accuracy(command_channel, seek_accuracy, uuid, metadata)
+    }
+
+    pub(crate) fn new_with_seek_accuracy(
+        command_channel: Sender<TrackCommand>,
+        seek_accuracy: SeekAccuracy,
+        uuid: Uuid,
+        metadata: Box<Metadata>,
     ) -> Self {
         let inner = Arc::new(InnerHandle {
             command_channel,
-            seekable,
+            seekable: seek_accuracy != SeekAccuracy::Unsupported,
+            seek_accuracy,
             uuid,
-            metadata,
+            metadata: PRwLock::new(Arc::new(*metadata)),
             typemap: RwLock::new(TypeMap::new()),
+            user_data: Mutex::new(None),
+            position_nanos: AtomicU64::new(0),
+            passthrough: AtomicBool::new(false),
+            first_frame_latency: Mutex::new(None),
         });
 
         Self { inner }
@@ -88,10 +135,160 @@ impl TrackHandle {
     }
 
     /// Sets the volume of an audio track.
+    ///
+    /// Fails with [`TrackError::InvalidVolume`] if `volume` is NaN or infinite.
+    ///
+    /// [`TrackError::InvalidVolume`]: TrackError::InvalidVolume
     pub fn set_volume(&self, volume: f32) -> TrackResult<()> {
+        if !volume.is_finite() {
+            return Err(TrackError::InvalidVolume);
+        }
+
         self.send(TrackCommand::Volume(volume))
     }
 
+    /// Moves the volume of an audio track to `target` over `duration`, rather than
+    /// changing it at once.
+    ///
+    /// See [`Track::set_volume_over`] for details.
+    ///
+    /// Fails with [`TrackError::InvalidVolume`] if `target` is NaN or infinite.
+    ///
+    /// [`Track::set_volume_over`]: Track::set_volume_over
+    /// [`TrackError::InvalidVolume`]: TrackError::InvalidVolume
+    pub fn set_volume_over(&self, target: f32, duration: Duration) -> TrackResult<()> {
+        if !target.is_finite() {
+            return Err(TrackError::InvalidVolume);
+        }
+
+        self.send(TrackCommand::VolumeRamp { target, duration })
+    }
+
+    /// Sets the volume of an audio track from a list of `(position, volume)` keyframes.
+    ///
+    /// See [`Track::set_volume_automation`] for details.
+    ///
+    /// Returns an identifier which may be passed to [`cancel_scheduled`].
+    ///
+    /// Fails with [`TrackError::InvalidVolume`] if any volume is NaN or infinite.
+    ///
+    /// [`Track::set_volume_automation`]: Track::set_volume_automation
+    /// [`cancel_scheduled`]: TrackHandle::cancel_scheduled
+    /// [`TrackError::InvalidVolume`]: TrackError::InvalidVolume
+    pub fn set_volume_automation(&self, keyframes: Vec<(Duration, f32)>) -> TrackResult<EventId> {
+        if keyframes.iter().any(|(_, volume)| !volume.is_finite()) {
+            return Err(TrackError::InvalidVolume);
+        }
+
+        let id = EventId::next();
+        self.send(TrackCommand::VolumeAutomation(id, keyframes))
+            .map(|_| id)
+    }
+
+    /// Hints the Opus bandwidth used to encode an audio track.
+    ///
+    /// See [`Track::set_bandwidth`] for details.
+    ///
+    /// [`Track::set_bandwidth`]: Track::set_bandwidth
+    pub fn set_bandwidth(&self, bandwidth: Bandwidth) -> TrackResult<()> {
+        self.send(TrackCommand::SetBandwidth(bandwidth))
+    }
+
+    /// Sets how an audio track's multichannel source is folded into stereo.
+    ///
+    /// See [`Track::set_downmix`] for details.
+    ///
+    /// [`Track::set_downmix`]: Track::set_downmix
+    pub fn set_downmix(&self, matrix: DownmixMatrix) -> TrackResult<()> {
+        self.send(TrackCommand::SetDownmix(matrix))
+    }
+
+    /// Fades an audio track in from silence over its first `duration` of playback.
+    ///
+    /// See [`Track::set_fade_in`] for details.
+    ///
+    /// [`Track::set_fade_in`]: Track::set_fade_in
+    pub fn set_fade_in(&self, duration: Duration) -> TrackResult<()> {
+        self.send(TrackCommand::FadeIn(duration))
+    }
+
+    /// Fades an audio track out to silence over the last `duration` of its input.
+    ///
+    /// See [`Track::set_fade_out`] for details.
+    ///
+    /// [`Track::set_fade_out`]: Track::set_fade_out
+    pub fn set_fade_out(&self, duration: Duration) -> TrackResult<()> {
+        self.send(TrackCommand::FadeOut(duration))
+    }
+
+    /// Mutes or unmutes an audio track, such as to reveal a track pre-started
+    /// with [`Track::unmute_at`].
+    ///
+    /// See [`Track::set_muted`] for details.
+    ///
+    /// [`Track::unmute_at`]: Track::unmute_at
+    /// [`Track::set_muted`]: Track::set_muted
+    pub fn set_muted(&self, muted: bool) -> TrackResult<()> {
+        self.send(TrackCommand::SetMuted(muted))
+    }
+
+    /// Sets which output channels an audio track is played through.
+    ///
+    /// See [`Track::set_route`] for details.
+    ///
+    /// [`Track::set_route`]: Track::set_route
+    pub fn set_route(&self, route: Route) -> TrackResult<()> {
+        self.send(TrackCommand::SetRoute(route))
+    }
+
+    /// Positions an audio track in the stereo field, from `-1.0` (full left)
+    /// to `1.0` (full right).
+    ///
+    /// See [`Track::set_pan`] for details.
+    ///
+    /// [`Track::set_pan`]: Track::set_pan
+    pub fn set_pan(&self, pan: f32) -> TrackResult<()> {
+        self.send(TrackCommand::Pan(pan))
+    }
+
+    /// Sets the order in which an audio track's effects are applied.
+    ///
+    /// See [`Track::set_effect_order`] for details.
+    ///
+    /// [`Track::set_effect_order`]: Track::set_effect_order
+    pub fn set_effect_order(&self, order: Vec<EffectKind>) -> TrackResult<()> {
+        self.send(TrackCommand::SetEffectOrder(order))
+    }
+
+    /// Sets the bands of an audio track's equalizer, which may be changed while
+    /// the track plays.
+    ///
+    /// See [`Track::set_equalizer`] for details.
+    ///
+    /// [`Track::set_equalizer`]: Track::set_equalizer
+    pub fn set_equalizer(&self, bands: &[EqBand]) -> TrackResult<()> {
+        self.send(TrackCommand::Equalizer(bands.to_vec()))
+    }
+
+    /// Shifts the pitch of an audio track by the given number of semitones,
+    /// without changing its speed.
+    ///
+    /// See [`Track::set_pitch`] for the latency this introduces.
+    ///
+    /// [`Track::set_pitch`]: Track::set_pitch
+    pub fn set_pitch(&self, semitones: f32) -> TrackResult<()> {
+        self.send(TrackCommand::Pitch(semitones))
+    }
+
+    /// Plays an audio track faster or slower by the given factor, changing its pitch.
+    ///
+    /// See [`Track::set_playback_speed`] for the range of speeds allowed.
+    ///
+    /// [`Track::set_playback_speed`]: Track::set_playback_speed
+    pub fn set_playback_speed(&self, speed: f32) -> TrackResult<()> {
+        self.send(TrackCommand::Speed(speed))
+    }
+
     /// Ready a track for playing if it is lazily initialised.
     ///
     /// Currently, only [`Restartable`] sources support lazy setup.
@@ -107,12 +304,25 @@ impl TrackHandle {
     /// If this returns `false`, all calls to [`seek_time`] will fail, and the track is
     /// incapable of looping.
     ///
+    /// This is recorded when the track is created, so it can be checked without
+    /// contacting the driver (e.g., to disable seek controls for live streams).
+    ///
     /// [`seek_time`]: TrackHandle::seek_time
     /// [`Input`]: crate::input::Input
     pub fn is_seekable(&self) -> bool {
         self.inner.seekable
     }
 
+    /// Returns how precisely the underlying [`Input`] can seek.
+    ///
+    /// This may be used to warn users that seeks on some sources
+    /// will not land on the exact position requested.
+    ///
+    /// [`Input`]: crate::input::Input
+    pub fn seek_accuracy(&self) -> SeekAccuracy {
+        self.inner.seek_accuracy
+    }
+
     /// Seeks along the track to the specified position.
     ///
     /// If the underlying [`Input`] does not support seeking,
@@ -128,22 +338,201 @@ impl TrackHandle {
         }
     }
 
+    /// Seeks forward along the track by `offset` from its current position.
+    ///
+    /// The current position is the last reported by the mixer, so is accurate to
+    /// within one frame (20ms).
+    ///
+    /// If the underlying [`Input`] does not support seeking,
+    /// then all calls will fail with [`TrackError::SeekUnsupported`].
+    ///
+    /// [`Input`]: crate::input::Input
+    /// [`TrackError::SeekUnsupported`]: TrackError::SeekUnsupported
+    pub fn seek_forward(&self, offset: Duration) -> TrackResult<()> {
+        self.seek_time(self.cached_position().saturating_add(offset))
+    }
+
+    /// Seeks backward along the track by `offset` from its current position,
+    /// stopping at the start of the track.
+    ///
+    /// See [`seek_forward`] for how the current position is found.
+    ///
+    /// If the underlying [`Input`] does not support seeking,
+    /// then all calls will fail with [`TrackError::SeekUnsupported`].
+    ///
+    /// [`seek_forward`]: TrackHandle::seek_forward
+    /// [`Input`]: crate::input::Input
+    /// [`TrackError::SeekUnsupported`]: TrackError::SeekUnsupported
+    pub fn seek_backward(&self, offset: Duration) -> TrackResult<()> {
+        self.seek_time(self.cached_position().saturating_sub(offset))
+    }
+
+    /// Seeks to the start of the track and plays it, as a "play again" control.
+    ///
+    /// Both steps take effect together, and the track's [`LoopState`] is unchanged.
+    ///
+    /// If the underlying [`Input`] does not support seeking,
+    /// then all calls will fail with [`TrackError::SeekUnsupported`].
+    ///
+    /// [`LoopState`]: LoopState
+    /// [`Input`]: crate::input::Input
+    /// [`TrackError::SeekUnsupported`]: TrackError::SeekUnsupported
+    pub fn replay(&self) -> TrackResult<()> {
+        if self.is_seekable() {
+            self.send(TrackCommand::Replay)
+        } else {
+            Err(TrackError::SeekUnsupported)
+        }
+    }
+
+    /// Seeks along the track to the specified position, waiting until the
+    /// seek has been performed.
+    ///
+    /// Returns the position actually reached, which may differ from the one
+    /// requested (e.g., if the input can only seek to certain boundaries).
+    ///
+    /// If the underlying [`Input`] does not support seeking,
+    /// then all calls will fail with [`TrackError::SeekUnsupported`].
+    ///
+    /// [`Input`]: crate::input::Input
+    /// [`TrackError::SeekUnsupported`]: TrackError::SeekUnsupported
+    pub async fn seek_time_async(&self, position: Duration) -> TrackResult<Duration> {
+        if !self.is_seekable() {
+            return Err(TrackError::SeekUnsupported);
+        }
+
+        let (tx, rx) = flume::bounded(1);
+        self.send(TrackCommand::SeekRequest(position, tx))?;
+
+        rx.recv_async().await.map_err(|_| TrackError::Finished)?
+    }
+
+    /// Returns the chapters listed in this track's [`metadata`], in order of their start times.
+    ///
+    /// This is empty if the input exposed no chapter information.
+    ///
+    /// [`metadata`]: TrackHandle::metadata
+    pub fn chapters(&self) -> Vec<Chapter> {
+        self.inner.metadata.read().chapters.clone()
+    }
+
+    /// Seeks to the start of the chapter at `index` within [`chapters`].
+    ///
+    /// Fails with [`TrackError::NoSuchChapter`] if there is no such chapter (including
+    /// when the input exposed no chapter information), and otherwise behaves as
+    /// [`seek_time`].
+    ///
+    /// [`chapters`]: TrackHandle::chapters
+    /// [`TrackError::NoSuchChapter`]: TrackError::NoSuchChapter
+    /// [`seek_time`]: TrackHandle::seek_time
+    pub fn seek_chapter(&self, index: usize) -> TrackResult<()> {
+        let start = self
+            .inner
+            .metadata
+            .read()
+            .chapters
+            .get(index)
+            .map(|chapter| chapter.start)
+            .ok_or(TrackError::NoSuchChapter)?;
+
+        self.seek_time(start)
+    }
+
     /// Attach an event handler to an audio track. These will receive [`EventContext::Track`].
     ///
+    /// Returns an identifier which may be passed to [`remove_event`] to later
+    /// detach the handler.
+    ///
     /// Events which can only be fired by the global context return [`TrackError::InvalidTrackEvent`]
     ///
     /// [`Track`]: Track
     /// [`EventContext::Track`]: crate::events::EventContext::Track
     /// [`TrackError::InvalidTrackEvent`]: TrackError::InvalidTrackEvent
-    pub fn add_event<F: EventHandler + 'static>(&self, event: Event, action: F) -> TrackResult<()> {
-        let cmd = TrackCommand::AddEvent(EventData::new(event, action));
+    /// [`remove_event`]: TrackHandle::remove_event
+    pub fn add_event<F: EventHandler + 'static>(
+        &self,
+        event: Event,
+        action: F,
+    ) -> TrackResult<EventId> {
+        let data = EventData::new(event, action);
+        let id = data.id();
+
         if event.is_global_only() {
             Err(TrackError::InvalidTrackEvent)
         } else {
-            self.send(cmd)
+            self.send(TrackCommand::AddEvent(data)).map(|_| id)
         }
     }
 
+    /// Detach an event handler previously attached via [`add_event`].
+    ///
+    /// Removing a handler which has already been removed (or which has
+    /// since fired and expired) has no effect.
+    ///
+    /// [`add_event`]: TrackHandle::add_event
+    pub fn remove_event(&self, id: EventId) -> TrackResult<()> {
+        self.send(TrackCommand::RemoveEvent(id))
+    }
+
+    /// Start (unpause) `other` once this track ends.
+    ///
+    /// This allows simple sequences of tracks, such as an intro followed by a
+    /// looping section, without needing a full [`TrackQueue`]. `other` should
+    /// typically be added to the driver in a paused state.
+    ///
+    /// If this track has already ended, `other` is started immediately.
+    ///
+    /// [`TrackQueue`]: super::TrackQueue
+    pub fn play_after(&self, other: &TrackHandle) -> TrackResult<()> {
+        match self.add_event(Event::Track(TrackEvent::End), PlayAfter(other.clone())) {
+            Err(TrackError::Finished) => other.play(),
+            res => res.map(|_| ()),
+        }
+    }
+
+    /// Register a named cue point at `at` in this track.
+    ///
+    /// Once playback reaches this position, any handlers attached to
+    /// [`TrackEvent::Cue`] receive [`EventContext::Cue`] carrying `name`.
+    /// Each cue fires exactly once: it will not fire again if the track
+    /// loops or seeks back past it. Seeking beyond a cue fires it on the next tick.
+    ///
+    /// Returns an identifier which may be passed to [`cancel_scheduled`].
+    ///
+    /// [`TrackEvent::Cue`]: crate::events::TrackEvent::Cue
+    /// [`EventContext::Cue`]: crate::events::EventContext::Cue
+    /// [`cancel_scheduled`]: TrackHandle::cancel_scheduled
+    pub fn add_cue(&self, at: Duration, name: impl Into<String>) -> TrackResult<EventId> {
+        let id = EventId::next();
+        self.send(TrackCommand::AddCue(id, at, name.into()))
+            .map(|_| id)
+    }
+
+    /// Lists the actions still waiting to take place on this track: timed events,
+    /// cues, a delayed start, and volume automation.
+    ///
+    /// Long-lived tracks can use this with [`cancel_scheduled`] to clean up
+    /// actions which are no longer wanted.
+    ///
+    /// [`cancel_scheduled`]: TrackHandle::cancel_scheduled
+    pub async fn scheduled(&self) -> TrackResult<Vec<ScheduledAction>> {
+        let (tx, rx) = flume::bounded(1);
+        self.send(TrackCommand::Scheduled(tx))?;
+
+        rx.recv_async().await.map_err(|_| TrackError::Finished)
+    }
+
+    /// Cancels the scheduled action with the given identifier, as listed by [`scheduled`].
+    ///
+    /// This also accepts any identifier returned by [`add_event`]. Cancelling an
+    /// action which has already taken place (or been cancelled) has no effect.
+    ///
+    /// [`scheduled`]: TrackHandle::scheduled
+    /// [`add_event`]: TrackHandle::add_event
+    pub fn cancel_scheduled(&self, id: EventId) -> TrackResult<()> {
+        self.send(TrackCommand::CancelScheduled(id))
+    }
+
     /// Perform an arbitrary synchronous action on a raw [`Track`] object.
     ///
     /// Users **must** ensure that no costly work or blocking occurs
@@ -158,7 +547,50 @@ impl TrackHandle {
         self.send(TrackCommand::Do(Box::new(action)))
     }
 
+    /// Attach a callback which receives this track's decoded audio every frame
+    /// (20ms), before volume or any other per-track processing is applied.
+    ///
+    /// Audio is given as interleaved stereo, 16-bit PCM at 48kHz. This is useful
+    /// for analysis such as beat detection or visualisation.
+    ///
+    /// Taps run directly on the mixing thread, so users **must** ensure that no
+    /// costly work or blocking occurs within the supplied closure. *Taking excess
+    /// time could prevent timely sending of packets, causing audio glitches and delays*.
+    /// [`add_frame_tap_channel`] offers a safer alternative.
+    ///
+    /// Tracks with a frame tap are not eligible for Opus passthrough.
+    ///
+    /// [`add_frame_tap_channel`]: TrackHandle::add_frame_tap_channel
+    pub fn add_frame_tap<F>(&self, tap: F) -> TrackResult<()>
+    where
+        F: FnMut(&[i16]) + Send + 'static,
+    {
+        self.send(TrackCommand::AddFrameTap(Box::new(tap)))
+    }
+
+    /// Attach a frame tap as in [`add_frame_tap`], which forwards each frame
+    /// of audio over a channel holding at most `capacity` frames.
+    ///
+    /// Frames are dropped, rather than blocking the mixer, if the receiver
+    /// falls behind.
+    ///
+    /// [`add_frame_tap`]: TrackHandle::add_frame_tap
+    pub fn add_frame_tap_channel(&self, capacity: usize) -> TrackResult<Receiver<Vec<i16>>> {
+        let (tx, rx) = flume::bounded(capacity);
+
+        self.add_frame_tap(move |frame| {
+            let _ = tx.try_send(frame.to_vec());
+        })
+        .map(|_| rx)
+    }
+
     /// Request playback information and state from the audio context.
+    ///
+    /// The state is read by the mixer as it next processes commands, so reflects
+    /// the latest mixed position. If the track has ended, or ends before it can
+    /// reply, this fails with [`TrackError::Finished`] rather than waiting forever.
+    ///
+    /// [`TrackError::Finished`]: TrackError::Finished
     pub async fn get_info(&self) -> TrackResult<TrackState> {
         let (tx, rx) = flume::bounded(1);
         self.send(TrackCommand::Request(tx))?;
@@ -218,13 +650,127 @@ impl TrackHandle {
 
     /// Returns the metadata stored in the handle.
     ///
-    /// Metadata is cloned from the inner [`Input`] at
-    /// the time a track/handle is created, and is effectively
-    /// read-only from then on.
+    /// Metadata is cloned from the inner [`Input`] at the time a track/handle
+    /// is created, and only changes if updated by [`refresh_metadata`].
+    ///
+    /// [`Input`]: crate::input::Input
+    /// [`refresh_metadata`]: TrackHandle::refresh_metadata
+    pub fn metadata(&self) -> Arc<Metadata> {
+        self.inner.metadata.read().clone()
+    }
+
+    /// Fills in any metadata which was unknown when this track was created, but
+    /// has since been discovered by its [`Input`], returning the updated metadata.
+    ///
+    /// This is useful for lazily-initialised [`Restartable`] sources, whose duration
+    /// or title may only be known once they have started. If any field changes,
+    /// the cached copy returned by [`metadata`] is updated and [`TrackEvent::MetadataUpdate`]
+    /// fires. Known fields are never overwritten.
+    ///
+    /// [`Input`]: crate::input::Input
+    /// [`Restartable`]: crate::input::Restartable
+    /// [`metadata`]: TrackHandle::metadata
+    /// [`TrackEvent::MetadataUpdate`]: crate::events::TrackEvent::MetadataUpdate
+    pub async fn refresh_metadata(&self) -> TrackResult<Arc<Metadata>> {
+        let (tx, rx) = flume::bounded(1);
+        self.send(TrackCommand::RefreshMetadata(tx))?;
+
+        rx.recv_async().await.map_err(|_| TrackError::Finished)?;
+
+        Ok(self.metadata())
+    }
+
+    /// Decodes the next `n` frames of this track's audio, returning them once ready.
+    ///
+    /// Each frame holds 20ms of interleaved stereo `f32` samples, before volume
+    /// or effects are applied. The track's position advances past the captured
+    /// audio, which will not be played when the track is resumed. Fewer than `n`
+    /// frames are returned if the input ends.
+    ///
+    /// The track must be paused, or else [`TrackError::NotPaused`] is returned.
+    ///
+    /// [`TrackError::NotPaused`]: TrackError::NotPaused
+    pub async fn capture_next_frames(&self, n: usize) -> TrackResult<Vec<Vec<f32>>> {
+        let (tx, rx) = flume::bounded(1);
+        self.send(TrackCommand::CaptureFrames(n, tx))?;
+
+        rx.recv_async().await.map_err(|_| TrackError::Finished)?
+    }
+
+    /// Replaces the cached metadata, as seen by the mixer.
+    pub(crate) fn set_metadata(&self, metadata: Metadata) {
+        *self.inner.metadata.write() = Arc::new(metadata);
+    }
+
+    /// Returns the time left until this track reaches the end of its [`Input`].
+    ///
+    /// This is computed from the duration in the track's [`metadata`] and the
+    /// last playback position reported by the mixer, so it is accurate to
+    /// within one frame (20ms) and does not require a round-trip to the driver.
+    /// Loops are not taken into account.
+    ///
+    /// Returns `None` for sources of unknown length, or live sources
+    /// which cannot be seeked.
     ///
     /// [`Input`]: crate::input::Input
-    pub fn metadata(&self) -> &Metadata {
-        &self.inner.metadata
+    /// [`metadata`]: TrackHandle::metadata
+    pub fn time_remaining(&self) -> Option<Duration> {
+        if !self.is_seekable() {
+            return None;
+        }
+
+        let position = self.cached_position();
+
+        self.inner
+            .metadata
+            .read()
+            .duration
+            .map(|duration| duration.saturating_sub(position))
+    }
+
+    /// Returns the last playback position reported by the mixer.
+    fn cached_position(&self) -> Duration {
+        Duration::from_nanos(self.inner.position_nanos.load(Ordering::Relaxed))
+    }
+
+    /// Updates the cached playback position, as seen by the mixer.
+    pub(crate) fn set_position(&self, position: Duration) {
+        self.inner
+            .position_nanos
+            .store(position.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    /// Returns how long this track took to produce its first frame of audio,
+    /// measured from when the mixer first tried to play it.
+    ///
+    /// This includes any time spent by the [`Input`] connecting, spawning processes,
+    /// or decoding before audio was ready, and is useful to diagnose slow starts.
+    /// Returns `None` until the first frame has been played.
+    ///
+    /// [`Input`]: crate::input::Input
+    pub fn first_frame_latency(&self) -> Option<Duration> {
+        *self.inner.first_frame_latency.lock()
+    }
+
+    pub(crate) fn set_first_frame_latency(&self, latency: Duration) {
+        *self.inner.first_frame_latency.lock() = Some(latency);
+    }
+
+    /// Returns whether the mixer most recently forwarded this track's Opus frames
+    /// directly, without decoding or re-encoding them.
+    ///
+    /// This is updated every frame, and becomes `false` as soon as any change
+    /// (e.g., to volume, or another track being added) disengages passthrough.
+    /// See the [`input`] module for the conditions required.
+    ///
+    /// [`input`]: crate::input
+    pub fn is_passthrough(&self) -> bool {
+        self.inner.passthrough.load(Ordering::Relaxed)
+    }
+
+    /// Updates whether this track is being forwarded via Opus passthrough.
+    pub(crate) fn set_passthrough(&self, passthrough: bool) {
+        self.inner.passthrough.store(passthrough, Ordering::Relaxed);
     }
 
     /// Allows access to this track's attached TypeMap.
@@ -238,6 +784,26 @@ impl TrackHandle {
         &self.inner.typemap
     }
 
+    /// Returns the user data attached to this track, if any.
+    ///
+    /// This is a single value shared by all handles, such as the ID of the user
+    /// who requested the track. Unlike the [`typemap`], it can be read without
+    /// awaiting a lock. Use [`Arc::downcast`] to recover its concrete type.
+    ///
+    /// [`typemap`]: TrackHandle::typemap
+    pub fn user_data(&self) -> Option<UserData> {
+        self.inner.user_data.lock().clone()
+    }
+
+    /// Attaches user data to this track, replacing any existing value.
+    ///
+    /// See [`user_data`] for details.
+    ///
+    /// [`user_data`]: TrackHandle::user_data
+    pub fn set_user_data(&self, data: UserData) {
+        *self.inner.user_data.lock() = Some(data);
+    }
+
     #[inline]
     /// Send a raw command to the [`Track`] object.
     ///
@@ -251,3 +817,15 @@ impl TrackHandle {
             .map_err(|_e| TrackError::Finished)
     }
 }
+
+/// Event handler which starts another track once its parent ends.
+struct PlayAfter(TrackHandle);
+
+#[async_trait]
+impl EventHandler for PlayAfter {
+    async fn act(&self, _ctx: &EventContext<'_>) -> Option<Event> {
+        let _ = self.0.play();
+
+        Some(Event::Cancel)
+    }
+}
@@ -14,19 +14,55 @@
 //! [`TrackHandle`]: struct.TrackHandle.html
 //! [`create_player`]: fn.create_player.html
 
+mod auto_gain;
+mod automation;
 mod command;
+mod effect;
+mod equalizer;
 mod error;
 mod handle;
 mod looping;
 mod mode;
+mod pitch;
 mod queue;
+mod route;
+mod scheduled;
+mod speed;
 mod state;
+mod tap;
 
-pub use self::{command::*, error::*, handle::*, looping::*, mode::*, queue::*, state::*};
+use self::{
+    auto_gain::AutoGain,
+    automation::{VolumeAutomation, VolumeRamp},
+    effect::{apply_gain, apply_gain_ramp, apply_pan, complete_effect_order},
+    equalizer::Equalizer,
+    pitch::PitchShift,
+    speed::SpeedChange,
+    tap::FrameTap,
+};
+pub use self::{
+    command::*,
+    effect::{EffectKind, DEFAULT_EFFECT_ORDER},
+    equalizer::EqBand,
+    error::*,
+    handle::*,
+    looping::*,
+    mode::*,
+    queue::*,
+    route::*,
+    scheduled::*,
+    state::*,
+};
 
-use crate::{constants::*, driver::tasks::message::*, events::EventStore, input::Input};
-use flume::{Receiver, TryRecvError};
-use std::time::Duration;
+use crate::{
+    constants::*,
+    driver::{tasks::message::*, VolumeCurve},
+    events::{EventId, EventStore, TrackEvent},
+    input::{ChannelLayout, DownmixMatrix, Input},
+};
+use audiopus::Bandwidth;
+use flume::{Receiver, Sender, TryRecvError};
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
 /// Control object for audio playback.
@@ -51,7 +87,7 @@ use uuid::Uuid;
 ///
 /// audio.set_volume(0.5);
 ///
-/// handler.play_only(audio);
+/// let _ = handler.play_only(audio);
 ///
 /// // Future access occurs via audio_handle.
 /// # };
@@ -71,6 +107,10 @@ pub struct Track {
     /// [`pause`]: Track::pause
     pub(crate) playing: PlayMode,
 
+    /// Whether this track was paused by the driver because no listeners remained
+    /// in its voice channel, and should resume once one returns.
+    pub(crate) paused_alone: bool,
+
     /// The desired volume for playback.
     ///
     /// Sensible values fall between `0.0` and `1.0`.
@@ -80,6 +120,78 @@ pub struct Track {
     /// [`volume`]: Track::volume
     pub(crate) volume: f32,
 
+    /// Automatic gain control applied on top of [`volume`], if engaged.
+    ///
+    /// Set with [`set_auto_gain`], and disengaged by [`set_volume`].
+    ///
+    /// [`volume`]: Track::volume
+    /// [`set_auto_gain`]: Track::set_auto_gain
+    /// [`set_volume`]: Track::set_volume
+    pub(crate) auto_gain: Option<AutoGain>,
+
+    /// Volume keyframes which set [`volume`] as the track plays, if engaged.
+    ///
+    /// Set with [`set_volume_automation`], and disengaged by [`set_volume`].
+    ///
+    /// [`volume`]: Track::volume
+    /// [`set_volume_automation`]: Track::set_volume_automation
+    /// [`set_volume`]: Track::set_volume
+    pub(crate) volume_automation: Option<VolumeAutomation>,
+
+    /// Gradual change of [`volume`] in progress, if any.
+    ///
+    /// Set with [`set_volume_over`], and disengaged by any other change of volume.
+    ///
+    /// [`volume`]: Track::volume
+    /// [`set_volume_over`]: Track::set_volume_over
+    pub(crate) volume_ramp: Option<VolumeRamp>,
+
+    /// Length of the fade from silence at the start of playback, if any.
+    pub(crate) fade_in_duration: Option<Duration>,
+
+    /// Length of the fade to silence before the end of the input, if any.
+    pub(crate) fade_out_duration: Option<Duration>,
+
+    /// Callbacks receiving this track's decoded audio each frame.
+    pub(crate) frame_taps: Vec<FrameTap>,
+
+    /// Equalizer applied to this track, if any.
+    ///
+    /// This is `None` when no band is engaged, so that no filtering occurs.
+    pub(crate) equalizer: Option<Equalizer>,
+
+    /// Pitch shift applied to this track, if any.
+    ///
+    /// This is `None` when the pitch is unchanged, so that no processing occurs.
+    pub(crate) pitch: Option<PitchShift>,
+
+    /// Playback speed of this track, if changed.
+    ///
+    /// This is `None` at normal speed, so that no resampling occurs.
+    pub(crate) speed: Option<SpeedChange>,
+
+    /// Output channels which this track is mixed into.
+    pub(crate) route: Route,
+
+    /// Stereo position of this track, from `-1.0` (full left) to `1.0` (full right).
+    pub(crate) pan: f32,
+
+    /// Whether this track is decoded and advanced as normal, but not heard.
+    pub(crate) muted: bool,
+
+    /// Position at which a muted track is automatically unmuted, if any.
+    pub(crate) unmute_at: Option<Duration>,
+
+    /// Order in which this track's effects are applied.
+    ///
+    /// This always holds every [`EffectKind`] exactly once.
+    ///
+    /// [`EffectKind`]: EffectKind
+    pub(crate) effect_order: Vec<EffectKind>,
+
+    /// Opus bandwidth requested from the encoder while this is the only playing track.
+    pub(crate) bandwidth: Bandwidth,
+
     /// Underlying data access object.
     ///
     /// *Calling code is not expected to use this.*
@@ -91,6 +203,18 @@ pub struct Track {
     /// The total length of time this track has been active.
     pub(crate) play_time: Duration,
 
+    /// Time at which this track was created.
+    pub(crate) created_at: Instant,
+
+    /// Time at which the mixer first tried to play this track.
+    pub(crate) first_mix_at: Option<Instant>,
+
+    /// Time at which this track first played a frame of audio.
+    pub(crate) started_at: Option<Instant>,
+
+    /// Time at which this track was stopped, ended, or errored.
+    pub(crate) ended_at: Option<Instant>,
+
     /// List of events attached to this audio track.
     ///
     /// This may be used to add additional events to a track
@@ -113,6 +237,48 @@ pub struct Track {
     /// Count of remaining loops.
     pub loops: LoopState,
 
+    /// Repeat mode of the [`TrackQueue`] holding this track, if any, which applies
+    /// once the track's own loops have run out.
+    ///
+    /// [`TrackQueue`]: TrackQueue
+    pub(crate) queue_repeat: Option<SharedRepeat>,
+
+    /// Number of times the driver may try to recreate this track's input
+    /// after it fails mid-stream, before the track is ended with an error.
+    ///
+    /// Only [`Restartable`] sources support recovery in this manner.
+    ///
+    /// [`Restartable`]: crate::input::restartable::Restartable
+    pub(crate) error_retries: usize,
+
+    /// Length of the fade applied on either side of a seek, in interleaved samples.
+    ///
+    /// This is set by the mixer from its [`Config`].
+    ///
+    /// [`Config`]: crate::Config
+    pub(crate) seek_fade: usize,
+
+    /// Whether the next mixed frame should fade in, following a seek.
+    pub(crate) fade_in: bool,
+
+    /// A seek deferred until the current frame has faded out.
+    pub(crate) pending_seek: Option<PendingSeek>,
+
+    /// Instant before which this track is held silent, as set by [`Driver::play_at`].
+    ///
+    /// [`Driver::play_at`]: crate::driver::Driver::play_at
+    pub(crate) start_at: Option<Instant>,
+
+    /// Identifier of the delayed start in [`start_at`], as listed by [`TrackHandle::scheduled`].
+    ///
+    /// [`start_at`]: Track::start_at
+    /// [`TrackHandle::scheduled`]: TrackHandle::scheduled
+    pub(crate) start_id: EventId,
+
+    /// Number of stereo samples played since the input looped back to its start,
+    /// if it did so partway through the last mixed frame.
+    pub(crate) loop_tail: Option<usize>,
+
     /// Unique identifier for this track.
     pub(crate) uuid: Uuid,
 }
@@ -129,14 +295,42 @@ impl Track {
 
         Self {
             playing: Default::default(),
+            paused_alone: false,
             volume: 1.0,
+            auto_gain: None,
+            volume_automation: None,
+            volume_ramp: None,
+            fade_in_duration: None,
+            fade_out_duration: None,
+            frame_taps: Vec::new(),
+            equalizer: None,
+            pitch: None,
+            speed: None,
+            route: Default::default(),
+            pan: 0.0,
+            muted: false,
+            unmute_at: None,
+            effect_order: DEFAULT_EFFECT_ORDER.to_vec(),
+            bandwidth: Bandwidth::Auto,
             source,
             position: Default::default(),
             play_time: Default::default(),
+            created_at: Instant::now(),
+            first_mix_at: None,
+            started_at: None,
+            ended_at: None,
             events: Some(EventStore::new_local()),
             commands,
             handle,
             loops: LoopState::Finite(0),
+            queue_repeat: None,
+            error_retries: 0,
+            seek_fade: 0,
+            fade_in: false,
+            pending_seek: None,
+            start_at: None,
+            start_id: EventId::next(),
+            loop_tail: None,
             uuid,
         }
     }
@@ -146,6 +340,29 @@ impl Track {
         self.set_playing(PlayMode::Play)
     }
 
+    /// Registers a cue at the start of each of this track's chapters, firing
+    /// [`TrackEvent::Cue`] with the chapter's title as playback reaches it.
+    ///
+    /// Untitled chapters are named by their (1-indexed) position, e.g. `"Chapter 2"`.
+    /// This is a no-op if the source has no chapter information in its [`Metadata`].
+    ///
+    /// [`TrackEvent::Cue`]: crate::events::TrackEvent::Cue
+    /// [`Metadata`]: crate::input::Metadata
+    pub fn cue_chapters(&mut self) -> &mut Self {
+        let events = self.events.get_or_insert_with(EventStore::new_local);
+
+        for (i, chapter) in self.source.metadata.chapters.iter().enumerate() {
+            let name = chapter
+                .title
+                .clone()
+                .unwrap_or_else(|| format!("Chapter {}", i + 1));
+
+            events.add_cue(chapter.start, name);
+        }
+
+        self
+    }
+
     /// Pauses a track if it is playing.
     pub fn pause(&mut self) -> &mut Self {
         self.set_playing(PlayMode::Pause)
@@ -163,9 +380,23 @@ impl Track {
         self.set_playing(PlayMode::End)
     }
 
+    /// Handles a failure of this track's input, either recreating it
+    /// (if retries remain) or ending the track with an error.
+    pub(crate) fn fail(&mut self) -> &mut Self {
+        if self.error_retries > 0 && self.source.reader.recreate().is_ok() {
+            self.error_retries -= 1;
+            self
+        } else {
+            self.set_playing(PlayMode::Errored)
+        }
+    }
+
     #[inline]
     fn set_playing(&mut self, new_state: PlayMode) -> &mut Self {
         self.playing = self.playing.change_to(new_state);
+        if self.playing.is_done() {
+            self.ended_at.get_or_insert_with(Instant::now);
+        }
 
         self
     }
@@ -177,13 +408,368 @@ impl Track {
 
     /// Sets [`volume`] in a manner that allows method chaining.
     ///
+    /// This disengages any automatic gain control set by [`set_auto_gain`],
+    /// any keyframes set by [`set_volume_automation`], and any ramp begun by
+    /// [`set_volume_over`].
+    ///
     /// [`volume`]: Track::volume
+    /// [`set_auto_gain`]: Track::set_auto_gain
+    /// [`set_volume_automation`]: Track::set_volume_automation
+    /// [`set_volume_over`]: Track::set_volume_over
     pub fn set_volume(&mut self, volume: f32) -> &mut Self {
         self.volume = volume;
+        self.auto_gain = None;
+        self.volume_automation = None;
+        self.volume_ramp = None;
+
+        self
+    }
+
+    /// Moves [`volume`] linearly to `target` over `duration` of playback, rather than
+    /// changing it at once, to avoid audible clicks.
+    ///
+    /// The volume is updated each frame, and reports its interpolated value while
+    /// the ramp is in progress. The [`TrackState`] given to event handlers is updated
+    /// once the ramp finishes or is cancelled. The ramp pauses along with the track,
+    /// and is cancelled by any later change of volume. As with [`set_volume`], this disengages
+    /// automatic gain control and volume keyframes.
+    ///
+    /// [`volume`]: Track::volume
+    /// [`set_volume`]: Track::set_volume
+    pub fn set_volume_over(&mut self, target: f32, duration: Duration) -> &mut Self {
+        if duration.is_zero() {
+            return self.set_volume(target);
+        }
+
+        let ramp = VolumeRamp::new(self.volume, target, self.play_time, duration);
+
+        self.set_volume(self.volume);
+        self.volume_ramp = Some(ramp);
+
+        self
+    }
+
+    /// Sets this track's [`volume`] from a list of `(position, volume)` keyframes,
+    /// interpolating linearly between them as the track plays.
+    ///
+    /// Keyframes are sorted by position, and negative volumes are raised to `0.0`.
+    /// Before the first keyframe and after the last, that keyframe's volume is kept.
+    /// This generalises fades into arbitrary envelopes, and is disengaged by any
+    /// later call to [`set_volume`]. An empty list disengages automation.
+    ///
+    /// [`volume`]: Track::volume
+    /// [`set_volume`]: Track::set_volume
+    pub fn set_volume_automation(&mut self, keyframes: Vec<(Duration, f32)>) -> &mut Self {
+        self.volume_automation = VolumeAutomation::new(EventId::next(), keyframes);
+        self.volume_ramp = None;
+
+        self
+    }
+
+    /// Continuously adjusts this track's gain so that its loudness tends towards
+    /// `target_dbfs`, in decibels relative to full scale (e.g., `-20.0`).
+    ///
+    /// Loudness is measured each frame in the mixer, and changes in gain are smoothed
+    /// to avoid audible pumping. Near-silent audio is not boosted. This is best suited
+    /// to voice or microphone-like inputs whose level varies over time.
+    ///
+    /// The gain is applied on top of the current [`volume`], and is disengaged
+    /// by any later call to [`set_volume`].
+    ///
+    /// [`volume`]: Track::volume
+    /// [`set_volume`]: Track::set_volume
+    pub fn set_auto_gain(&mut self, target_dbfs: f32) -> &mut Self {
+        self.auto_gain = Some(AutoGain::new(target_dbfs));
 
         self
     }
 
+    /// Fades this track in from silence over its first `duration` of playback.
+    ///
+    /// The fade follows [`play_time`], so it is paused along with the track
+    /// and is not repeated by loops or seeks. A `duration` of zero disables it.
+    ///
+    /// [`play_time`]: Track::play_time
+    pub fn set_fade_in(&mut self, duration: Duration) -> &mut Self {
+        self.fade_in_duration = Some(duration).filter(|d| !d.is_zero());
+
+        self
+    }
+
+    /// Fades this track out to silence over the last `duration` of its input.
+    ///
+    /// This relies on the input's [`Metadata::duration`], and has no effect if
+    /// the length of the input is unknown. A `duration` of zero disables it.
+    ///
+    /// [`Metadata::duration`]: crate::input::Metadata::duration
+    pub fn set_fade_out(&mut self, duration: Duration) -> &mut Self {
+        self.fade_out_duration = Some(duration).filter(|d| !d.is_zero());
+
+        self
+    }
+
+    /// Returns the gain of this track's fades at the given point in its playback.
+    fn fade_gain(&self, play_time: Duration, position: Duration) -> f32 {
+        let fade_in = self
+            .fade_in_duration
+            .map(|len| play_time.as_secs_f32() / len.as_secs_f32());
+
+        let fade_out = self
+            .fade_out_duration
+            .zip(self.source.metadata.duration)
+            .map(|(len, end)| end.saturating_sub(position).as_secs_f32() / len.as_secs_f32());
+
+        fade_in
+            .into_iter()
+            .chain(fade_out)
+            .fold(1.0f32, f32::min)
+            .max(0.0)
+    }
+
+    /// Returns the fade gains at the start and end of the next frame, if either fade applies.
+    fn fade_envelope(&self) -> Option<(f32, f32)> {
+        let start = self.fade_gain(self.play_time, self.position);
+        let end = self.fade_gain(
+            self.play_time + TIMESTEP_LENGTH,
+            self.position + TIMESTEP_LENGTH,
+        );
+
+        if start < 1.0 || end < 1.0 {
+            Some((start, end))
+        } else {
+            None
+        }
+    }
+
+    /// Shapes the tone of this track with a parametric equalizer, applying a
+    /// peaking filter for each of the given `bands`.
+    ///
+    /// Replacing the bands of a playing track keeps each filter's state, so that
+    /// the change causes no discontinuity. Bands with a gain of `0.0` (or which are
+    /// not finite) are ignored, and an empty list disables the equalizer entirely.
+    pub fn set_equalizer(&mut self, bands: &[EqBand]) -> &mut Self {
+        let updated = match &mut self.equalizer {
+            Some(equalizer) => equalizer.set_bands(bands),
+            None => false,
+        };
+
+        if !updated {
+            self.equalizer = Equalizer::new(bands);
+        }
+
+        self
+    }
+
+    /// Returns the bands of this track's equalizer.
+    pub fn equalizer(&self) -> &[EqBand] {
+        self.equalizer
+            .as_ref()
+            .map(Equalizer::bands)
+            .unwrap_or_default()
+    }
+
+    /// Shifts this track's pitch by the given number of `semitones`
+    /// (e.g., `12.0` for one octave up), without changing its speed.
+    ///
+    /// Pitch shifting is comparatively expensive, and delays this track's audio
+    /// by up to 40ms. Setting a shift of `0.0` disables it entirely.
+    ///
+    /// This is independent of [`set_playback_speed`], which changes pitch along
+    /// with speed: the two may be combined to change speed at the original pitch.
+    ///
+    /// [`set_playback_speed`]: Track::set_playback_speed
+    pub fn set_pitch(&mut self, semitones: f32) -> &mut Self {
+        self.pitch = if semitones == 0.0 || !semitones.is_finite() {
+            None
+        } else {
+            Some(PitchShift::new(semitones))
+        };
+
+        self
+    }
+
+    /// Returns the current pitch shift, in semitones.
+    pub fn pitch(&self) -> f32 {
+        self.pitch
+            .as_ref()
+            .map(PitchShift::semitones)
+            .unwrap_or(0.0)
+    }
+
+    /// Plays this track faster or slower by the given factor (e.g., `1.5` to
+    /// play 50% faster), which also raises or lowers its pitch.
+    ///
+    /// The speed is clamped between `0.5` and `2.0`, and a speed of `1.0` disables
+    /// resampling entirely. The track's position follows its input, so advances by
+    /// `speed` times real time, while its play time counts real time as normal.
+    pub fn set_playback_speed(&mut self, speed: f32) -> &mut Self {
+        let speed = speed.clamp(0.5, 2.0);
+
+        self.speed = if (speed - 1.0).abs() < f32::EPSILON || !speed.is_finite() {
+            None
+        } else {
+            Some(SpeedChange::new(speed))
+        };
+
+        self
+    }
+
+    /// Returns the current playback speed, where `1.0` is normal speed.
+    pub fn playback_speed(&self) -> f32 {
+        self.speed.as_ref().map(SpeedChange::speed).unwrap_or(1.0)
+    }
+
+    /// Mutes or unmutes this track.
+    ///
+    /// A muted track plays as normal, reading its input and advancing its position,
+    /// but is not heard (and, where possible, is not decoded). Tracks may then be
+    /// started muted ahead of time, so that their sources are already running and
+    /// they can be revealed with no gap in audio.
+    ///
+    /// This cancels any unmuting scheduled by [`unmute_at`].
+    ///
+    /// [`unmute_at`]: Track::unmute_at
+    pub fn set_muted(&mut self, muted: bool) -> &mut Self {
+        self.muted = muted;
+        self.unmute_at = None;
+
+        self
+    }
+
+    /// Mutes this track until its position reaches `position`, at which point
+    /// it is heard from the start of that frame onwards.
+    ///
+    /// See [`set_muted`] for details.
+    ///
+    /// [`set_muted`]: Track::set_muted
+    pub fn unmute_at(&mut self, position: Duration) -> &mut Self {
+        self.muted = true;
+        self.unmute_at = Some(position);
+
+        self
+    }
+
+    /// Returns whether this track is currently muted.
+    pub fn is_muted(&self) -> bool {
+        self.muted
+    }
+
+    /// Sets which output channels this track is played through.
+    ///
+    /// Routing a track to a single side downmixes it to mono, and plays it
+    /// at full level in that channel only (rather than panning it).
+    pub fn set_route(&mut self, route: Route) -> &mut Self {
+        self.route = route;
+
+        self
+    }
+
+    /// Returns which output channels this track is played through.
+    pub fn route(&self) -> Route {
+        self.route
+    }
+
+    /// Positions this track in the stereo field, from `-1.0` (full left) to
+    /// `1.0` (full right), clamping values outside this range.
+    ///
+    /// Mono sources follow a constant-power law, boosting the near channel as the
+    /// opposite channel fades, so that loudness stays even across the stereo field.
+    /// Stereo sources instead have their opposite channel attenuated linearly,
+    /// leaving the near channel untouched to keep the balance of their mix.
+    /// The default of `0.0` leaves audio unchanged.
+    pub fn set_pan(&mut self, pan: f32) -> &mut Self {
+        self.pan = if pan.is_nan() {
+            0.0
+        } else {
+            pan.clamp(-1.0, 1.0)
+        };
+
+        self
+    }
+
+    /// Returns this track's position in the stereo field.
+    pub fn pan(&self) -> f32 {
+        self.pan
+    }
+
+    /// Sets the order in which this track's effects are applied, as order changes
+    /// the resulting sound (e.g., auto gain measures audio before or after pitch shifting).
+    ///
+    /// Effects missing from `order` are applied afterwards, in the order given by
+    /// [`DEFAULT_EFFECT_ORDER`], and any repeated effects are ignored. Effects which
+    /// are not engaged (such as a pitch shift of `0.0`) are skipped, wherever they appear.
+    ///
+    /// [`DEFAULT_EFFECT_ORDER`]: DEFAULT_EFFECT_ORDER
+    pub fn set_effect_order(&mut self, order: Vec<EffectKind>) -> &mut Self {
+        self.effect_order = complete_effect_order(order);
+
+        self
+    }
+
+    /// Returns the order in which this track's effects are applied.
+    pub fn effect_order(&self) -> &[EffectKind] {
+        &self.effect_order
+    }
+
+    /// Hints the audio bandwidth the Opus encoder should use for this track,
+    /// e.g., [`Bandwidth::Wideband`] for speech or [`Bandwidth::Fullband`] for music.
+    ///
+    /// As all tracks share one encoder, this hint only applies while this is the
+    /// only playing track: otherwise, the encoder chooses its bandwidth automatically.
+    /// Tracks sent via Opus passthrough are not re-encoded, and are unaffected.
+    ///
+    /// Defaults to [`Bandwidth::Auto`].
+    ///
+    /// [`Bandwidth::Wideband`]: crate::driver::opus::Bandwidth::Wideband
+    /// [`Bandwidth::Fullband`]: crate::driver::opus::Bandwidth::Fullband
+    /// [`Bandwidth::Auto`]: crate::driver::opus::Bandwidth::Auto
+    pub fn set_bandwidth(&mut self, bandwidth: Bandwidth) -> &mut Self {
+        self.bandwidth = bandwidth;
+
+        self
+    }
+
+    /// Sets how this track's multichannel source is folded into stereo, such as
+    /// the gain of its LFE or rear channels.
+    ///
+    /// This only applies to sources created with a surround [`ChannelLayout`], which
+    /// is reported in [`TrackState::layout`]: other tracks are unaffected. Such sources
+    /// use the default (ITU-R BS.775) [`DownmixMatrix`] until this is called.
+    ///
+    /// [`ChannelLayout`]: ChannelLayout
+    /// [`TrackState::layout`]: TrackState::layout
+    /// [`DownmixMatrix`]: DownmixMatrix
+    pub fn set_downmix(&mut self, matrix: DownmixMatrix) -> &mut Self {
+        self.source.set_downmix(matrix);
+
+        self
+    }
+
+    /// Returns the channel layout of this track's source.
+    pub fn channel_layout(&self) -> ChannelLayout {
+        self.source.channel_layout()
+    }
+
+    /// Returns the Opus bandwidth hinted for this track.
+    pub fn bandwidth(&self) -> Bandwidth {
+        self.bandwidth
+    }
+
+    /// Attaches user data to this track in a manner that allows method chaining,
+    /// retrievable from any of its handles via [`TrackHandle::user_data`].
+    ///
+    /// [`TrackHandle::user_data`]: TrackHandle::user_data
+    pub fn set_user_data(&mut self, data: UserData) -> &mut Self {
+        self.handle.set_user_data(data);
+
+        self
+    }
+
+    /// Returns the user data attached to this track, if any.
+    pub fn user_data(&self) -> Option<UserData> {
+        self.handle.user_data()
+    }
+
     /// Returns the current volume.
     pub fn volume(&self) -> f32 {
         self.volume
@@ -201,6 +787,9 @@ impl Track {
 
     /// Set an audio track to loop a set number of times.
     ///
+    /// Loops are sample-accurate: the input restarts from its first sample
+    /// immediately after its last, even partway through a frame.
+    ///
     /// If the underlying [`Input`] does not support seeking,
     /// then all calls will fail with [`TrackError::SeekUnsupported`].
     ///
@@ -215,10 +804,18 @@ impl Track {
         }
     }
 
+    /// Returns the repeat mode of this track's queue, or [`QueueRepeat::Off`] if it
+    /// isn't queued.
+    pub(crate) fn queue_repeat(&self) -> QueueRepeat {
+        self.queue_repeat
+            .as_ref()
+            .map_or(QueueRepeat::Off, SharedRepeat::get)
+    }
+
     pub(crate) fn do_loop(&mut self) -> bool {
         match self.loops {
             LoopState::Infinite => true,
-            LoopState::Finite(0) => false,
+            LoopState::Finite(0) => self.queue_repeat() == QueueRepeat::Track,
             LoopState::Finite(ref mut n) => {
                 *n -= 1;
                 true
@@ -226,10 +823,266 @@ impl Track {
         }
     }
 
+    /// Mixes one frame of this track's audio into `mix_buffer`, applying its
+    /// volume (limited to `clamp`, then mapped through `curve`) and any per-track processing.
+    ///
+    /// Returns the number of samples read from the underlying [`Input`], or `None`
+    /// if it failed.
+    ///
+    /// [`Input`]: crate::input::Input
+    pub(crate) fn mix(
+        &mut self,
+        mix_buffer: &mut [f32; STEREO_FRAME_SIZE],
+        clamp: (f32, f32),
+        curve: VolumeCurve,
+    ) -> Option<usize> {
+        if let Some(ramp) = self.volume_ramp {
+            let (volume, done) = ramp.volume_at(self.play_time);
+            self.volume = volume;
+
+            if done {
+                self.volume_ramp = None;
+            }
+        }
+
+        if let Some(automation) = &self.volume_automation {
+            self.volume = automation.volume_at(self.position);
+        }
+
+        // `mix_tracks` sees `muted` change, and reports it to the event thread.
+        if self.unmute_at.map_or(false, |at| self.position >= at) {
+            self.muted = false;
+            self.unmute_at = None;
+        }
+
+        let volume = self.volume.clamp(clamp.0, clamp.1);
+        let fade_in = std::mem::take(&mut self.fade_in) && self.seek_fade > 0;
+        let fade_out = self.pending_seek.is_some();
+        let envelope = self.fade_envelope();
+
+        // Inaudible tracks are read to keep their position, but not decoded where possible.
+        if (self.muted || curve.gain(volume) == 0.0)
+            && self.frame_taps.is_empty()
+            && self.speed.is_none()
+        {
+            let len = self.source.skip_frame()?;
+            let mut scratch = [0f32; STEREO_FRAME_SIZE];
+
+            return Some(self.continue_loop(&mut scratch, len, 0.0));
+        }
+
+        if !fade_in
+            && !fade_out
+            && envelope.is_none()
+            && self.auto_gain.is_none()
+            && self.frame_taps.is_empty()
+            && self.equalizer.is_none()
+            && self.pitch.is_none()
+            && self.speed.is_none()
+            && self.route == Route::Both
+            && self.pan == 0.0
+            && !self.muted
+        {
+            let gain = curve.gain(volume);
+            let len = self.source.try_mix(mix_buffer, gain)?;
+
+            return Some(self.continue_loop(mix_buffer, len, gain));
+        }
+
+        let mut frame = [0f32; STEREO_FRAME_SIZE];
+        let len = match self.speed.take() {
+            Some(mut speed) => {
+                let len = speed.process(&mut frame, |chunk| self.read_frame(chunk));
+                self.speed = Some(speed);
+                len?
+            },
+            None => self.read_frame(&mut frame)?,
+        };
+
+        if !self.frame_taps.is_empty() {
+            let mut pcm = [0i16; STEREO_FRAME_SIZE];
+            for (out, sample) in pcm.iter_mut().zip(&frame[..]) {
+                *out = (sample.clamp(-1.0, 1.0) * f32::from(i16::MAX)) as i16;
+            }
+
+            let samples = len / std::mem::size_of::<f32>();
+            for tap in self.frame_taps.iter_mut() {
+                tap.call(&pcm[..samples]);
+            }
+        }
+
+        for effect in &self.effect_order {
+            match effect {
+                EffectKind::Equalizer =>
+                    if let Some(equalizer) = &mut self.equalizer {
+                        equalizer.process(&mut frame[..]);
+                    },
+                EffectKind::Pitch =>
+                    if let Some(pitch) = &mut self.pitch {
+                        pitch.process(&mut frame[..]);
+                    },
+                EffectKind::Route => self.route.apply(&mut frame[..]),
+                EffectKind::AutoGain =>
+                    if let Some(auto_gain) = &mut self.auto_gain {
+                        let gain = auto_gain.process(&frame[..]);
+                        apply_gain(&mut frame[..], gain);
+                    },
+                EffectKind::Volume => match envelope {
+                    Some((start, end)) => {
+                        let gain = curve.gain(volume);
+                        apply_gain_ramp(&mut frame[..], gain * start, gain * end);
+                    },
+                    None => apply_gain(&mut frame[..], curve.gain(volume)),
+                },
+                EffectKind::Pan => apply_pan(&mut frame[..], self.pan, !self.source.stereo),
+            }
+        }
+
+        let samples = (len / std::mem::size_of::<f32>()).min(STEREO_FRAME_SIZE);
+        apply_seek_fade(&mut frame[..samples], self.seek_fade, fade_in, fade_out);
+
+        if self.muted {
+            return Some(len);
+        }
+
+        for (out, sample) in mix_buffer.iter_mut().zip(&frame[..]) {
+            *out += sample;
+        }
+
+        Some(len)
+    }
+
+    /// Reads one frame of this track's input at unit gain, continuing into its
+    /// next loop if the input ends.
+    fn read_frame(&mut self, frame: &mut [f32; STEREO_FRAME_SIZE]) -> Option<usize> {
+        let len = self.source.try_mix(frame, 1.0)?;
+
+        Some(self.continue_loop(frame, len, 1.0))
+    }
+
+    /// Completes a frame cut short by the end of this track's input from the start
+    /// of the input, if the track has loops remaining, so that loops are
+    /// sample-accurate: with no gap, repeated audio, or fade at the boundary.
+    ///
+    /// Returns the number of bytes of audio now held in `frame`.
+    fn continue_loop(
+        &mut self,
+        frame: &mut [f32; STEREO_FRAME_SIZE],
+        mut len: usize,
+        gain: f32,
+    ) -> usize {
+        const SAMPLE_LEN: usize = std::mem::size_of::<f32>();
+
+        while len < STEREO_FRAME_BYTE_SIZE
+            && (self.loops != LoopState::Finite(0) || self.queue_repeat() == QueueRepeat::Track)
+        {
+            if self.source.seek_time(Duration::ZERO).is_none() {
+                break;
+            }
+            self.do_loop();
+
+            let mixed = self
+                .source
+                .mix_partial(&mut frame[len / SAMPLE_LEN..], gain);
+            self.loop_tail = Some(mixed);
+            len += mixed * SAMPLE_LEN;
+
+            if mixed == 0 {
+                break;
+            }
+        }
+
+        len
+    }
+
+    /// Returns whether this track's audio may be sent without re-encoding.
+    pub(crate) fn supports_passthrough(&self) -> bool {
+        (self.volume - 1.0).abs() < f32::EPSILON
+            && self.auto_gain.is_none()
+            && self.volume_automation.is_none()
+            && self.volume_ramp.is_none()
+            && self.fade_in_duration.is_none()
+            && self.fade_out_duration.is_none()
+            && !self.muted
+            && self.frame_taps.is_empty()
+            && self.equalizer.is_none()
+            && self.pitch.is_none()
+            && self.speed.is_none()
+            && self.route == Route::Both
+            && self.pan == 0.0
+            && self.source.supports_passthrough()
+    }
+
     /// Steps playback location forward by one frame.
     pub(crate) fn step_frame(&mut self) {
-        self.position += TIMESTEP_LENGTH;
+        // Positions after a loop are counted from the sample at which it restarted,
+        // so that no error accumulates over many loops.
+        self.position = match self.loop_tail.take() {
+            Some(samples) =>
+                Duration::from_nanos((samples / 2) as u64 * 1_000_000_000 / SAMPLE_RATE_RAW as u64),
+            None => match &self.speed {
+                Some(speed) => self.position + TIMESTEP_LENGTH.mul_f64(speed.speed().into()),
+                None => self.position + TIMESTEP_LENGTH,
+            },
+        };
         self.play_time += TIMESTEP_LENGTH;
+        self.handle.set_position(self.position);
+
+        if self.started_at.is_none() {
+            let now = Instant::now();
+            self.started_at = Some(now);
+
+            if let Some(first_mix_at) = self.first_mix_at {
+                self.handle
+                    .set_first_frame_latency(now.saturating_duration_since(first_mix_at));
+            }
+        }
+    }
+
+    /// Decodes up to `n` frames of a paused track, advancing its position past them.
+    ///
+    /// Fewer frames are returned if the input ends; the final frame is zero-padded.
+    fn capture_frames(&mut self, n: usize) -> TrackResult<Vec<Vec<f32>>> {
+        if self.playing.is_done() {
+            return Err(TrackError::Finished);
+        }
+
+        if self.playing != PlayMode::Pause {
+            return Err(TrackError::NotPaused);
+        }
+
+        let mut frames = Vec::with_capacity(n);
+
+        for _ in 0..n {
+            let mut frame = [0f32; STEREO_FRAME_SIZE];
+
+            match self.source.try_mix(&mut frame, 1.0) {
+                Some(len) if len > 0 => {
+                    frames.push(frame.to_vec());
+                    self.position += TIMESTEP_LENGTH;
+                },
+                _ => break,
+            }
+        }
+
+        self.handle.set_position(self.position);
+
+        Ok(frames)
+    }
+
+    /// Lists the scheduled actions held by the mixer, rather than the event store.
+    fn scheduled(&self) -> Vec<ScheduledAction> {
+        let start = self.start_at.map(|at| ScheduledAction {
+            id: self.start_id,
+            kind: ScheduledKind::Start(at),
+        });
+
+        let automation = self.volume_automation.as_ref().map(|a| ScheduledAction {
+            id: a.id,
+            kind: ScheduledKind::VolumeAutomation(a.keyframes.clone()),
+        });
+
+        start.into_iter().chain(automation).collect()
     }
 
     /// Receives and acts upon any commands forwarded by TrackHandles.
@@ -256,6 +1109,8 @@ impl Track {
                             ));
                         },
                         Pause => {
+                            // An explicit pause must outlast the driver being alone.
+                            self.paused_alone = false;
                             self.pause();
                             let _ = ic.events.send(EventMessage::ChangeState(
                                 index,
@@ -276,16 +1131,119 @@ impl Track {
                                 TrackStateChange::Volume(self.volume),
                             ));
                         },
-                        Seek(time) =>
-                            if let Ok(new_time) = self.seek_time(time) {
+                        SetBandwidth(bandwidth) => {
+                            self.set_bandwidth(bandwidth);
+                        },
+                        SetDownmix(matrix) => {
+                            self.set_downmix(matrix);
+                        },
+                        FadeIn(duration) => {
+                            self.set_fade_in(duration);
+                        },
+                        FadeOut(duration) => {
+                            self.set_fade_out(duration);
+                        },
+                        SetMuted(muted) => {
+                            self.set_muted(muted);
+                            let _ = ic.events.send(EventMessage::ChangeState(
+                                index,
+                                TrackStateChange::Muted(self.muted),
+                            ));
+                        },
+                        SetRoute(route) => {
+                            self.set_route(route);
+                            let _ = ic.events.send(EventMessage::ChangeState(
+                                index,
+                                TrackStateChange::Route(self.route),
+                            ));
+                        },
+                        Pan(pan) => {
+                            self.set_pan(pan);
+                            let _ = ic.events.send(EventMessage::ChangeState(
+                                index,
+                                TrackStateChange::Pan(self.pan),
+                            ));
+                        },
+                        SetEffectOrder(order) => {
+                            self.set_effect_order(order);
+                        },
+                        Equalizer(bands) => {
+                            self.set_equalizer(&bands);
+                        },
+                        VolumeAutomation(id, keyframes) => {
+                            self.volume_automation = self::VolumeAutomation::new(id, keyframes);
+                            if self.volume_ramp.take().is_some() {
                                 let _ = ic.events.send(EventMessage::ChangeState(
                                     index,
-                                    TrackStateChange::Position(new_time),
+                                    TrackStateChange::Volume(self.volume),
                                 ));
-                            },
+                            }
+                        },
+                        VolumeRamp { target, duration } => {
+                            // A cancelled ramp leaves the volume where it had reached.
+                            if self.volume_ramp.is_some() {
+                                let _ = ic.events.send(EventMessage::ChangeState(
+                                    index,
+                                    TrackStateChange::Volume(self.volume),
+                                ));
+                            }
+                            self.set_volume_over(target, duration);
+                        },
+                        Pitch(semitones) => {
+                            self.set_pitch(semitones);
+                            let _ = ic.events.send(EventMessage::ChangeState(
+                                index,
+                                TrackStateChange::Pitch(self.pitch()),
+                            ));
+                        },
+                        Speed(speed) => {
+                            self.set_playback_speed(speed);
+                            let _ = ic.events.send(EventMessage::ChangeState(
+                                index,
+                                TrackStateChange::Speed(self.playback_speed()),
+                            ));
+                        },
+                        Seek(time) => self.request_seek(index, ic, time, None),
+                        SeekRequest(time, tx) => self.request_seek(index, ic, time, Some(tx)),
+                        Replay => {
+                            // Seeking first means a paused track never mixes a
+                            // frame from its old position.
+                            self.request_seek(index, ic, Duration::ZERO, None);
+                            if self.playing != PlayMode::Play {
+                                self.play();
+                                let _ = ic.events.send(EventMessage::ChangeState(
+                                    index,
+                                    TrackStateChange::Mode(self.playing),
+                                ));
+                            }
+                        },
                         AddEvent(evt) => {
                             let _ = ic.events.send(EventMessage::AddTrackEvent(index, evt));
                         },
+                        RemoveEvent(id) => {
+                            let _ = ic.events.send(EventMessage::RemoveTrackEvent(index, id));
+                        },
+                        AddCue(id, at, name) => {
+                            let _ = ic
+                                .events
+                                .send(EventMessage::AddTrackCue(index, id, at, name));
+                        },
+                        Scheduled(tx) => {
+                            let _ = ic.events.send(EventMessage::ListScheduled(
+                                index,
+                                self.scheduled(),
+                                tx,
+                            ));
+                        },
+                        CancelScheduled(id) => {
+                            if id == self.start_id {
+                                self.start_at = None;
+                            }
+                            if self.volume_automation.as_ref().map(|a| a.id) == Some(id) {
+                                self.volume_automation = None;
+                            }
+                            let _ = ic.events.send(EventMessage::RemoveTrackEvent(index, id));
+                        },
                         Do(action) => {
                             action(self);
                             let _ = ic.events.send(EventMessage::ChangeState(
@@ -296,6 +1254,26 @@ impl Track {
                         Request(tx) => {
                             let _ = tx.send(self.state());
                         },
+                        RefreshMetadata(tx) => {
+                            if self.source.refresh_metadata() {
+                                self.handle.set_metadata((*self.source.metadata).clone());
+                                let _ = ic.events.send(EventMessage::FireTrackEvent(
+                                    index,
+                                    TrackEvent::MetadataUpdate,
+                                ));
+                            }
+                            let _ = tx.send(());
+                        },
+                        CaptureFrames(n, tx) => {
+                            let frames = self.capture_frames(n);
+                            if matches!(&frames, Ok(frames) if !frames.is_empty()) {
+                                let _ = ic.events.send(EventMessage::ChangeState(
+                                    index,
+                                    TrackStateChange::Position(self.position),
+                                ));
+                            }
+                            let _ = tx.send(frames);
+                        },
                         Loop(loops) =>
                             if self.set_loops(loops).is_ok() {
                                 let _ = ic.events.send(EventMessage::ChangeState(
@@ -304,6 +1282,7 @@ impl Track {
                                 ));
                             },
                         MakePlayable => self.make_playable(),
+                        AddFrameTap(tap) => self.frame_taps.push(FrameTap::new(tap)),
                     }
                 },
                 Err(TryRecvError::Disconnected) => {
@@ -315,6 +1294,65 @@ impl Track {
                 },
             }
         }
+
+        // Only playing tracks are mixed, and so can fade out.
+        if self.playing != PlayMode::Play {
+            self.finish_pending_seek(index, ic);
+        }
+    }
+
+    /// Seeks to `time`, first fading out the current frame if seek fades are enabled.
+    fn request_seek(
+        &mut self,
+        index: usize,
+        ic: &Interconnect,
+        time: Duration,
+        reply: Option<Sender<TrackResult<Duration>>>,
+    ) {
+        if self.seek_fade > 0 && self.playing == PlayMode::Play {
+            let pending = self.pending_seek.get_or_insert(PendingSeek {
+                time,
+                replies: vec![],
+            });
+
+            pending.time = time;
+            pending.replies.extend(reply);
+        } else {
+            self.seek_now(index, ic, time, reply);
+        }
+    }
+
+    fn seek_now(
+        &mut self,
+        index: usize,
+        ic: &Interconnect,
+        time: Duration,
+        replies: impl IntoIterator<Item = Sender<TrackResult<Duration>>>,
+    ) {
+        let result = self.seek_time(time);
+
+        if let Ok(new_time) = result {
+            let _ = ic.events.send(EventMessage::ChangeState(
+                index,
+                TrackStateChange::Position(new_time),
+            ));
+        }
+
+        for tx in replies {
+            let _ = tx.send(result);
+        }
+    }
+
+    /// Performs any seek deferred by [`request_seek`], returning whether one took place.
+    ///
+    /// [`request_seek`]: Track::request_seek
+    pub(crate) fn finish_pending_seek(&mut self, index: usize, ic: &Interconnect) -> bool {
+        if let Some(pending) = self.pending_seek.take() {
+            self.seek_now(index, ic, pending.time, pending.replies);
+            true
+        } else {
+            false
+        }
     }
 
     /// Ready a track for playing if it is lazily initialised.
@@ -337,9 +1375,18 @@ impl Track {
         TrackState {
             playing: self.playing,
             volume: self.volume,
+            pitch: self.pitch(),
+            speed: self.playback_speed(),
+            route: self.route,
+            pan: self.pan,
+            muted: self.muted,
+            layout: self.channel_layout(),
             position: self.position,
             play_time: self.play_time,
             loops: self.loops,
+            created_at: self.created_at,
+            started_at: self.started_at,
+            ended_at: self.ended_at,
         }
     }
 
@@ -352,7 +1399,13 @@ impl Track {
     /// [`TrackError::SeekUnsupported`]: TrackError::SeekUnsupported
     pub fn seek_time(&mut self, pos: Duration) -> TrackResult<Duration> {
         if let Some(t) = self.source.seek_time(pos) {
+            if let Some(speed) = &mut self.speed {
+                speed.reset();
+            }
+
             self.position = t;
+            self.handle.set_position(t);
+            self.fade_in = true;
             Ok(t)
         } else {
             Err(TrackError::SeekUnsupported)
@@ -365,6 +1418,37 @@ impl Track {
     }
 }
 
+/// A seek requested while a track is playing, performed once the current frame has faded out.
+#[derive(Debug)]
+pub(crate) struct PendingSeek {
+    time: Duration,
+    replies: Vec<Sender<TrackResult<Duration>>>,
+}
+
+/// Ramps the first (`fade_in`) or last (`fade_out`) `len` interleaved stereo
+/// samples of `frame` from or to silence.
+fn apply_seek_fade(frame: &mut [f32], len: usize, fade_in: bool, fade_out: bool) {
+    let pairs = (len / 2).min(frame.len() / 2);
+    if pairs == 0 {
+        return;
+    }
+
+    if fade_in {
+        for (i, pair) in frame.chunks_exact_mut(2).take(pairs).enumerate() {
+            let gain = i as f32 / pairs as f32;
+            pair.iter_mut().for_each(|s| *s *= gain);
+        }
+    }
+
+    if fade_out {
+        let total = frame.len() / 2;
+        for (i, pair) in frame.chunks_exact_mut(2).skip(total - pairs).enumerate() {
+            let gain = 1.0 - (i + 1) as f32 / pairs as f32;
+            pair.iter_mut().for_each(|s| *s *= gain);
+        }
+    }
+}
+
 /// Creates a [`Track`] object to pass into the audio context, and a [`TrackHandle`]
 /// for safe, lock-free access in external code.
 ///
@@ -386,9 +1470,9 @@ pub fn create_player(source: Input) -> (Track, TrackHandle) {
 /// [`TrackHandle`]: TrackHandle
 pub fn create_player_with_uuid(source: Input, uuid: Uuid) -> (Track, TrackHandle) {
     let (tx, rx) = flume::unbounded();
-    let can_seek = source.is_seekable();
+    let seek_accuracy = source.seek_accuracy();
     let metadata = source.metadata.clone();
-    let handle = TrackHandle::new(tx, can_seek, uuid, metadata);
+    let handle = TrackHandle::new_with_seek_accuracy(tx, seek_accuracy, uuid, metadata);
 
     let player = Track::new_raw(source, rx, handle.clone());
 
@@ -12,12 +12,14 @@ pub enum PlayMode {
     Stop,
     /// The track has naturally ended, and cannot be restarted.
     End,
+    /// The track's input failed while playing, and cannot be restarted.
+    Errored,
 }
 
 impl PlayMode {
     /// Returns whether the track has irreversibly stopped.
     pub fn is_done(self) -> bool {
-        matches!(self, PlayMode::Stop | PlayMode::End)
+        matches!(self, PlayMode::Stop | PlayMode::End | PlayMode::Errored)
     }
 
     pub(crate) fn change_to(self, other: Self) -> PlayMode {
@@ -38,6 +40,7 @@ impl PlayMode {
             Play => TrackEvent::Play,
             Pause => TrackEvent::Pause,
             Stop | End => TrackEvent::End,
+            Errored => TrackEvent::Error,
         }
     }
 }
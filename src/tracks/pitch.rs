@@ -0,0 +1,93 @@
+use std::f32::consts::PI;
+
+/// Length (in samples per channel) of each grain read by the pitch shifter.
+///
+/// At 48kHz, this is 40ms: the shifter delays audio by up to this amount.
+const WINDOW: usize = 1920;
+
+/// Length (in stereo sample pairs) of the shifter's delay line.
+const BUFFER_LEN: usize = WINDOW + 2;
+
+/// Time-domain pitch shifter, which changes the pitch of a [`Track`]
+/// without changing its duration.
+///
+/// Two read heads sweep across a short delay line at a rate set by the pitch
+/// ratio, and are crossfaded so that one is always silent as it wraps around.
+///
+/// [`Track`]: super::Track
+#[derive(Clone, Debug)]
+pub(crate) struct PitchShift {
+    semitones: f32,
+    ratio: f32,
+    buffer: Vec<[f32; 2]>,
+    write_pos: usize,
+    phase: f32,
+}
+
+impl PitchShift {
+    pub(crate) fn new(semitones: f32) -> Self {
+        Self {
+            semitones,
+            ratio: 2f32.powf(semitones / 12.0),
+            buffer: vec![[0.0; 2]; BUFFER_LEN],
+            write_pos: 0,
+            phase: 0.0,
+        }
+    }
+
+    pub(crate) fn semitones(&self) -> f32 {
+        self.semitones
+    }
+
+    /// Shifts the pitch of one frame of interleaved stereo audio, in place.
+    pub(crate) fn process(&mut self, frame: &mut [f32]) {
+        let step = (1.0 - self.ratio) / WINDOW as f32;
+
+        for pair in frame.chunks_exact_mut(2) {
+            self.buffer[self.write_pos] = [pair[0], pair[1]];
+
+            let other_phase = (self.phase + 0.5).fract();
+            let weight = (PI * self.phase).sin().powi(2);
+
+            for (channel, sample) in pair.iter_mut().enumerate() {
+                *sample = weight * self.tap(self.phase, channel)
+                    + (1.0 - weight) * self.tap(other_phase, channel);
+            }
+
+            self.phase = (self.phase + step).rem_euclid(1.0);
+            self.write_pos = (self.write_pos + 1) % BUFFER_LEN;
+        }
+    }
+
+    /// Reads one channel from the delay line, `phase` windows behind the
+    /// latest sample, interpolating between samples.
+    fn tap(&self, phase: f32, channel: usize) -> f32 {
+        let pos = (self.write_pos + BUFFER_LEN) as f32 - phase * WINDOW as f32;
+        let base = pos.floor();
+        let frac = pos - base;
+
+        let i = base as usize % BUFFER_LEN;
+        let j = (i + 1) % BUFFER_LEN;
+
+        self.buffer[i][channel] * (1.0 - frac) + self.buffer[j][channel] * frac
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{input::Input, tracks};
+
+    #[test]
+    fn zero_semitones_removes_pitch_shifter() {
+        let (mut track, _handle) =
+            tracks::create_player(Input::float_pcm(true, Vec::<u8>::new().into()));
+
+        track.set_pitch(3.0);
+        assert!(track.pitch.is_some());
+        assert_eq!(track.pitch(), 3.0);
+
+        track.set_pitch(0.0);
+        assert!(track.pitch.is_none());
+        assert_eq!(track.pitch(), 0.0);
+    }
+}
@@ -1,13 +1,28 @@
 use crate::{
+    constants::TIMESTEP_LENGTH,
     driver::Driver,
     events::{Event, EventContext, EventData, EventHandler, TrackEvent},
+    id::UserId,
     input::Input,
-    tracks::{self, Track, TrackHandle, TrackResult},
+    tracks::{self, LoopState, PlayMode, QueueError, QueueResult, Track, TrackHandle, TrackResult},
 };
 use async_trait::async_trait;
+use flume::{Receiver, Sender, TrySendError};
 use parking_lot::Mutex;
-use std::{collections::VecDeque, ops::Deref, sync::Arc, time::Duration};
+use rand::{seq::SliceRandom, Rng};
+use std::{
+    borrow::Cow,
+    collections::VecDeque,
+    fmt,
+    ops::{Deref, Range},
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 use tracing::{info, warn};
+use uuid::Uuid;
 
 /// A simple queue for several audio sources, designed to
 /// play in sequence.
@@ -49,7 +64,8 @@ use tracing::{info, warn};
 ///     .or_default();
 ///
 /// // Queueing a track is this easy!
-/// queue.add_source(source, &mut driver);
+/// queue.add_source(source, &mut driver)
+///     .expect("Queues have no length limit by default.");
 /// # };
 /// ```
 ///
@@ -65,7 +81,7 @@ pub struct TrackQueue {
 ///
 /// Instances *should not* be moved from one queue to another.
 #[derive(Debug)]
-pub struct Queued(TrackHandle);
+pub struct Queued(TrackHandle, Option<UserId>);
 
 impl Deref for Queued {
     type Target = TrackHandle;
@@ -80,9 +96,68 @@ impl Queued {
     pub fn handle(&self) -> TrackHandle {
         self.0.clone()
     }
+
+    /// Returns the user this track was added on behalf of, if any.
+    ///
+    /// See [`TrackQueue::add_as`].
+    ///
+    /// [`TrackQueue::add_as`]: TrackQueue::add_as
+    pub fn added_by(&self) -> Option<UserId> {
+        self.1
+    }
+}
+
+/// Decides whether a track may be added to a [`TrackQueue`], such as to limit
+/// how many tracks each user may have queued.
+///
+/// The guard is checked while the queue is locked, so that concurrent additions
+/// cannot both pass a limit which only one of them should. Closures of the form
+/// `Fn(&EnqueueRequest) -> Result<(), Cow<'static, str>>` are also guards.
+///
+/// See [`TrackQueue::set_enqueue_guard`].
+///
+/// [`TrackQueue`]: TrackQueue
+/// [`TrackQueue::set_enqueue_guard`]: TrackQueue::set_enqueue_guard
+pub trait EnqueueGuard: Send + Sync {
+    /// Accepts a track to be added to the queue, or rejects it with a reason,
+    /// which is returned to the caller as [`QueueError::Rejected`].
+    ///
+    /// [`QueueError::Rejected`]: QueueError::Rejected
+    fn check(&self, request: &EnqueueRequest) -> Result<(), Cow<'static, str>>;
+}
+
+impl<F> EnqueueGuard for F
+where
+    F: Fn(&EnqueueRequest) -> Result<(), Cow<'static, str>> + Send + Sync,
+{
+    fn check(&self, request: &EnqueueRequest) -> Result<(), Cow<'static, str>> {
+        (self)(request)
+    }
+}
+
+/// An attempt to add a track to a [`TrackQueue`], given to its [`EnqueueGuard`].
+///
+/// [`TrackQueue`]: TrackQueue
+/// [`EnqueueGuard`]: EnqueueGuard
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct EnqueueRequest {
+    /// The user the track is being added on behalf of, if any.
+    ///
+    /// This is only set for tracks added via [`TrackQueue::add_as`] and similar methods.
+    ///
+    /// [`TrackQueue::add_as`]: TrackQueue::add_as
+    pub user_id: Option<UserId>,
+    /// Number of tracks currently in the queue, including the current track.
+    pub len: usize,
+    /// Number of tracks currently in the queue which were added on behalf of
+    /// [`user_id`], or `0` if it is unset.
+    ///
+    /// [`user_id`]: EnqueueRequest::user_id
+    pub user_len: usize,
 }
 
-#[derive(Debug, Default)]
+#[derive(Default)]
 /// Inner portion of a [`TrackQueue`].
 ///
 /// This abstracts away thread-safety from the user,
@@ -91,6 +166,191 @@ impl Queued {
 /// [`TrackQueue`]: TrackQueue
 struct TrackQueueCore {
     tracks: VecDeque<Queued>,
+    on_error: QueueErrorPolicy,
+    max_len: Option<usize>,
+    max_len_includes_current: bool,
+    volume: Option<f32>,
+    repeat: SharedRepeat,
+    crossfade: Option<Duration>,
+    /// The next track, if it has begun to play over the end of the current track.
+    crossfading: Option<Uuid>,
+    subscribers: Vec<Sender<QueueEvent>>,
+    guard: Option<Box<dyn EnqueueGuard>>,
+}
+
+impl fmt::Debug for TrackQueueCore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TrackQueueCore")
+            .field("tracks", &self.tracks)
+            .field("on_error", &self.on_error)
+            .field("max_len", &self.max_len)
+            .field("max_len_includes_current", &self.max_len_includes_current)
+            .field("volume", &self.volume)
+            .field("repeat", &self.repeat.get())
+            .field("crossfade", &self.crossfade)
+            .field("crossfading", &self.crossfading)
+            .field("subscribers", &self.subscribers.len())
+            .field("guard", &self.guard.is_some())
+            .finish()
+    }
+}
+
+/// Number of [`QueueEvent`]s held for each receiver before further events are dropped.
+///
+/// [`QueueEvent`]: QueueEvent
+pub const QUEUE_EVENT_CAPACITY: usize = 64;
+
+/// Changes to a [`TrackQueue`], received via [`TrackQueue::events`].
+///
+/// [`TrackQueue`]: TrackQueue
+/// [`TrackQueue::events`]: TrackQueue::events
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum QueueEvent {
+    /// A track was added to the queue.
+    TrackAdded(TrackHandle),
+    /// A track reached the head of the queue, and began to play.
+    TrackStarted(TrackHandle),
+    /// The track at the head of the queue ended, and was removed.
+    TrackEnded(TrackHandle),
+    /// The last track in the queue ended, or the queue was stopped.
+    QueueEmptied,
+    /// The contents or order of the queue changed.
+    ///
+    /// This is sent alongside the more specific events above, and for every
+    /// other modification, so that a display of the queue can be kept up to date.
+    Changed(QueueChange),
+}
+
+/// A modification of the contents or order of a [`TrackQueue`], carried by
+/// [`QueueEvent::Changed`].
+///
+/// Indices refer to the queue as it was immediately before the change, where
+/// index `0` is the current track.
+///
+/// [`TrackQueue`]: TrackQueue
+/// [`QueueEvent::Changed`]: QueueEvent::Changed
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum QueueChange {
+    /// A track was inserted at `index`.
+    Added {
+        /// Position of the new track.
+        index: usize,
+        /// UUID of the new track.
+        uuid: Uuid,
+    },
+    /// The track at `index` was removed.
+    Removed {
+        /// Former position of the removed track.
+        index: usize,
+        /// UUID of the removed track.
+        uuid: Uuid,
+    },
+    /// A track was moved from `from` to `to`, shifting the tracks between them
+    /// by one place.
+    Moved {
+        /// Former position of the moved track.
+        from: usize,
+        /// New position of the moved track.
+        to: usize,
+        /// UUID of the moved track.
+        uuid: Uuid,
+    },
+    /// Every track after `index` was randomly reordered, via [`TrackQueue::shuffle`]
+    /// or similar methods.
+    ///
+    /// [`TrackQueue::shuffle`]: TrackQueue::shuffle
+    Shuffled {
+        /// Position of the last track which kept its place.
+        index: usize,
+    },
+    /// Every track was removed.
+    Cleared,
+    /// The queue was changed arbitrarily via [`TrackQueue::modify_queue`], and
+    /// should be reread in full.
+    ///
+    /// [`TrackQueue::modify_queue`]: TrackQueue::modify_queue
+    Rewritten,
+}
+
+/// Behaviour of a [`TrackQueue`] when the input of a queued track fails.
+///
+/// [`TrackQueue`]: TrackQueue
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum QueueErrorPolicy {
+    /// Remove the failed track, and play the next track in the queue.
+    ///
+    /// This is the default.
+    Skip,
+    /// Remove the failed track, leaving the next track paused until
+    /// [`TrackQueue::resume`] is called.
+    ///
+    /// [`TrackQueue::resume`]: TrackQueue::resume
+    Pause,
+    /// Try to recreate the failed track's input up to `n` times, resuming from
+    /// where it failed, before skipping it.
+    ///
+    /// Only [`Restartable`] sources can be recovered in this manner: all others
+    /// are skipped immediately. This applies to tracks added after the policy is set.
+    ///
+    /// [`Restartable`]: crate::input::restartable::Restartable
+    Retry(usize),
+}
+
+impl Default for QueueErrorPolicy {
+    fn default() -> Self {
+        Self::Skip
+    }
+}
+
+/// Whether a [`TrackQueue`] repeats its tracks once they finish.
+///
+/// [`TrackQueue`]: TrackQueue
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum QueueRepeat {
+    /// Remove each track once it ends, and play the next track in the queue.
+    ///
+    /// This is the default.
+    Off,
+    /// Move each track to the back of the queue once it ends, so that the whole
+    /// queue plays again from the top.
+    All,
+    /// Loop the current track until it is skipped.
+    Track,
+}
+
+impl Default for QueueRepeat {
+    fn default() -> Self {
+        Self::Off
+    }
+}
+
+/// A queue's [`QueueRepeat`] mode, shared with each of its tracks so that the
+/// mixer can tell whether an ending track should be repeated.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SharedRepeat(Arc<AtomicU8>);
+
+impl SharedRepeat {
+    pub(crate) fn get(&self) -> QueueRepeat {
+        match self.0.load(Ordering::Relaxed) {
+            1 => QueueRepeat::All,
+            2 => QueueRepeat::Track,
+            _ => QueueRepeat::Off,
+        }
+    }
+
+    pub(crate) fn set(&self, repeat: QueueRepeat) {
+        let val = match repeat {
+            QueueRepeat::Off => 0,
+            QueueRepeat::All => 1,
+            QueueRepeat::Track => 2,
+        };
+
+        self.0.store(val, Ordering::Relaxed);
+    }
 }
 
 struct QueueHandler {
@@ -105,34 +365,121 @@ impl EventHandler for QueueHandler {
         // Due to possibility that users might remove, reorder,
         // or dequeue+stop tracks, we need to verify that the FIRST
         // track is the one who has ended.
-        match ctx {
+        let errored = match ctx {
             EventContext::Track(ts) => {
                 // This slice should have exactly one entry.
                 // If the ended track has same id as the queue head, then
                 // we can progress the queue.
-                if inner.tracks.front()?.uuid() != ts.first()?.1.uuid() {
+                let (state, handle) = ts.first()?;
+                if inner.tracks.front()?.uuid() != handle.uuid() {
                     return None;
                 }
+
+                state.playing == PlayMode::Errored
             },
             _ => return None,
-        }
+        };
 
-        let _old = inner.tracks.pop_front();
+        inner.crossfading = None;
+
+        if let Some(old) = inner.tracks.pop_front() {
+            inner.emit(QueueEvent::TrackEnded(old.handle()));
+            inner.emit_removed(0, &old);
+        }
 
         info!("Queued track ended: {:?}.", ctx);
         info!("{} tracks remain.", inner.tracks.len());
 
-        // Keep going until we find one track which works, or we run out.
-        while let Some(new) = inner.tracks.front() {
-            if new.play().is_err() {
-                // Discard files which cannot be used for whatever reason.
-                warn!("Track in Queue couldn't be played...");
-                inner.tracks.pop_front();
-            } else {
-                break;
+        if errored {
+            warn!("Queued track failed: {:?}.", ctx);
+
+            if inner.on_error == QueueErrorPolicy::Pause {
+                info!("Pausing queue after track error.");
+                return None;
             }
         }
 
+        inner.play_next();
+
+        None
+    }
+}
+
+struct QueueRepeater {
+    remote_lock: Arc<Mutex<TrackQueueCore>>,
+}
+
+#[async_trait]
+impl EventHandler for QueueRepeater {
+    async fn act(&self, ctx: &EventContext<'_>) -> Option<Event> {
+        let mut inner = self.remote_lock.lock();
+
+        let (state, handle) = match ctx {
+            EventContext::Track(ts) => ts.first()?,
+            _ => return None,
+        };
+
+        // When repeating all tracks, the mixer rewinds and pauses each track as it
+        // finishes, rather than ending it. Tracks looping by their own
+        // `LoopState`, or repeated under `QueueRepeat::Track`, keep playing.
+        if inner.repeat.get() != QueueRepeat::All
+            || state.playing != PlayMode::Pause
+            || inner.tracks.front()?.uuid() != handle.uuid()
+        {
+            return None;
+        }
+
+        info!("Queued track finished: moving to back of queue.");
+        inner.requeue_current(false);
+
+        None
+    }
+}
+
+struct QueueCrossfader {
+    remote_lock: Arc<Mutex<TrackQueueCore>>,
+}
+
+#[async_trait]
+impl EventHandler for QueueCrossfader {
+    async fn act(&self, ctx: &EventContext<'_>) -> Option<Event> {
+        let mut inner = self.remote_lock.lock();
+
+        let (state, handle) = match ctx {
+            EventContext::Track(ts) => ts.first()?,
+            _ => return None,
+        };
+
+        // Looping or repeated tracks don't end when their input does, and the track
+        // may no longer be at the head of the queue.
+        let crossfade = inner.crossfade?;
+        if state.loops != LoopState::Finite(0)
+            || inner.repeat.get() != QueueRepeat::Off
+            || inner.crossfading.is_some()
+            || inner.tracks.front()?.uuid() != handle.uuid()
+        {
+            return None;
+        }
+
+        let remaining = handle.metadata().duration?.saturating_sub(state.position);
+        let next = inner.tracks.get(1)?;
+
+        let mut window = remaining.min(crossfade);
+        if let Some(next_len) = next.metadata().duration {
+            window = window.min(next_len);
+        }
+
+        // Wait until the current track's end is exactly one window away,
+        // so that both fades finish together.
+        let delay = remaining.saturating_sub(window);
+        if delay >= TIMESTEP_LENGTH {
+            return Some(Event::Delayed(delay));
+        }
+
+        if let Err(e) = inner.start_crossfade(window) {
+            warn!("Couldn't begin crossfade: {:?}.", e);
+        }
+
         None
     }
 }
@@ -160,16 +507,30 @@ impl TrackQueue {
         Self {
             inner: Arc::new(Mutex::new(TrackQueueCore {
                 tracks: VecDeque::new(),
+                on_error: QueueErrorPolicy::Skip,
+                max_len: None,
+                max_len_includes_current: false,
+                volume: None,
+                repeat: SharedRepeat::default(),
+                crossfade: None,
+                crossfading: None,
+                subscribers: vec![],
+                guard: None,
             })),
         }
     }
 
     /// Adds an audio source to the queue, to be played in the channel managed by `handler`.
-    pub fn add_source(&self, source: Input, handler: &mut Driver) -> TrackHandle {
+    ///
+    /// Fails with [`QueueError::Full`] if the queue has reached its [maximum length].
+    ///
+    /// [`QueueError::Full`]: QueueError::Full
+    /// [maximum length]: TrackQueue::set_max_len
+    pub fn add_source(&self, source: Input, handler: &mut Driver) -> QueueResult<TrackHandle> {
         let (track, handle) = tracks::create_player(source);
-        self.add(track, handler);
+        self.add(track, handler)?;
 
-        handle
+        Ok(handle)
     }
 
     /// Adds a [`Track`] object to the queue, to be played in the channel managed by `handler`.
@@ -177,25 +538,185 @@ impl TrackQueue {
     /// This is used with [`create_player`] if additional configuration or event handlers
     /// are required before enqueueing the audio track.
     ///
+    /// Fails with [`QueueError::Full`] if the queue has reached its [maximum length],
+    /// in which case `track` is dropped.
+    ///
     /// [`Track`]: Track
     /// [`create_player`]: super::create_player
-    pub fn add(&self, mut track: Track, handler: &mut Driver) {
-        self.add_raw(&mut track);
+    /// [`QueueError::Full`]: QueueError::Full
+    /// [maximum length]: TrackQueue::set_max_len
+    pub fn add(&self, mut track: Track, handler: &mut Driver) -> QueueResult<()> {
+        self.add_raw(&mut track, None)?;
+        handler.play(track);
+
+        Ok(())
+    }
+
+    /// Adds an audio source to the queue on behalf of a user, to be played in the
+    /// channel managed by `handler`.
+    ///
+    /// The user is passed to the queue's [`EnqueueGuard`], which may reject the
+    /// track with [`QueueError::Rejected`].
+    ///
+    /// [`EnqueueGuard`]: EnqueueGuard
+    /// [`QueueError::Rejected`]: QueueError::Rejected
+    pub fn add_source_as(
+        &self,
+        source: Input,
+        user_id: impl Into<UserId>,
+        handler: &mut Driver,
+    ) -> QueueResult<TrackHandle> {
+        let (track, handle) = tracks::create_player(source);
+        self.add_as(track, user_id, handler)?;
+
+        Ok(handle)
+    }
+
+    /// Adds a [`Track`] object to the queue on behalf of a user, to be played in the
+    /// channel managed by `handler`.
+    ///
+    /// The user is passed to the queue's [`EnqueueGuard`], which may reject the
+    /// track with [`QueueError::Rejected`]. As with [`add`], `track` is dropped
+    /// if it cannot be added.
+    ///
+    /// [`Track`]: Track
+    /// [`EnqueueGuard`]: EnqueueGuard
+    /// [`QueueError::Rejected`]: QueueError::Rejected
+    /// [`add`]: TrackQueue::add
+    pub fn add_as(
+        &self,
+        mut track: Track,
+        user_id: impl Into<UserId>,
+        handler: &mut Driver,
+    ) -> QueueResult<()> {
+        self.add_raw(&mut track, Some(user_id.into()))?;
         handler.play(track);
+
+        Ok(())
     }
 
     #[inline]
-    pub(crate) fn add_raw(&self, track: &mut Track) {
-        info!("Track added to queue.");
-        let remote_lock = self.inner.clone();
+    pub(crate) fn add_raw(&self, track: &mut Track, user_id: Option<UserId>) -> QueueResult<()> {
         let mut inner = self.inner.lock();
 
-        let track_handle = track.handle.clone();
+        if let Some(max_len) = inner.max_len {
+            let counted = if inner.max_len_includes_current {
+                inner.tracks.len()
+            } else {
+                inner.tracks.len().saturating_sub(1)
+            };
+
+            if counted >= max_len {
+                return Err(QueueError::Full { max_len });
+            }
+        }
+
+        if let Some(guard) = &inner.guard {
+            let request = EnqueueRequest {
+                user_id,
+                len: inner.tracks.len(),
+                user_len: user_id.map_or(0, |id| {
+                    inner.tracks.iter().filter(|q| q.1 == Some(id)).count()
+                }),
+            };
+
+            guard.check(&request).map_err(QueueError::Rejected)?;
+        }
+
+        if inner.repeat.get() != QueueRepeat::Off && !track.source.is_seekable() {
+            return Err(QueueError::NotSeekable(track.handle.uuid()));
+        }
+
+        track.queue_repeat = Some(inner.repeat.clone());
 
-        if !inner.tracks.is_empty() {
+        info!("Track added to queue.");
+
+        let first = inner.tracks.is_empty();
+        if !first {
             track.pause();
         }
 
+        let queued = self.register(&inner, track, user_id);
+        let index = inner.tracks.len();
+        inner.emit(QueueEvent::TrackAdded(queued.handle()));
+        inner.emit(QueueEvent::Changed(QueueChange::Added {
+            index,
+            uuid: queued.uuid(),
+        }));
+        inner.tracks.push_back(queued);
+
+        if first {
+            inner.emit_started();
+        }
+
+        Ok(())
+    }
+
+    /// Plays an audio source immediately, ahead of every queued track, returning
+    /// a handle for further control.
+    ///
+    /// The current track is paused and kept at the front of the queue, resuming
+    /// from the same position once the new track ends. This is intended for short
+    /// announcements over music. Tracks which cannot seek (such as live streams) are
+    /// not safe to hold paused, and are instead stopped and removed from the queue.
+    ///
+    /// This ignores the queue's [maximum length].
+    ///
+    /// [maximum length]: TrackQueue::set_max_len
+    pub fn play_now(&self, source: Input, handler: &mut Driver) -> TrackHandle {
+        let (mut track, handle) = tracks::create_player(source);
+
+        {
+            let mut inner = self.inner.lock();
+
+            info!("Track added to front of queue.");
+
+            inner.abandon_crossfade();
+
+            if let Some(current) = inner.tracks.front() {
+                if !current.is_seekable() || current.pause().is_err() {
+                    if let Some(old) = inner.tracks.pop_front() {
+                        // Errors when removing tracks don't really make
+                        // a difference: an error just implies it's already gone.
+                        let _ = old.stop();
+                        inner.emit_removed(0, &old);
+                    }
+                }
+            }
+
+            let queued = self.register(&inner, &mut track, None);
+            inner.emit(QueueEvent::TrackAdded(queued.handle()));
+            inner.emit(QueueEvent::Changed(QueueChange::Added {
+                index: 0,
+                uuid: queued.uuid(),
+            }));
+            inner.tracks.push_front(queued);
+            inner.emit_started();
+        }
+
+        handler.play(track);
+
+        handle
+    }
+
+    /// Applies queue settings to a new track, and attaches the events which
+    /// advance the queue.
+    fn register(
+        &self,
+        inner: &TrackQueueCore,
+        track: &mut Track,
+        user_id: Option<UserId>,
+    ) -> Queued {
+        let remote_lock = self.inner.clone();
+
+        if let Some(volume) = inner.volume {
+            track.set_volume(volume);
+        }
+
+        if let QueueErrorPolicy::Retry(n) = inner.on_error {
+            track.error_retries = n;
+        }
+
         track
             .events
             .as_mut()
@@ -205,11 +726,27 @@ impl TrackQueue {
                 track.position,
             );
 
+        let remote_lock = self.inner.clone();
+        track
+            .events
+            .as_mut()
+            .expect("Queue inspecting EventStore on new Track: did not exist.")
+            .add_event(
+                EventData::new(
+                    Event::Track(TrackEvent::Loop),
+                    QueueRepeater { remote_lock },
+                ),
+                track.position,
+            );
+
         // Attempts to start loading the next track before this one ends.
         // Idea is to provide as close to gapless playback as possible,
         // while minimising memory use.
         if let Some(time) = track.source.metadata.duration {
-            let preload_time = time.checked_sub(Duration::from_secs(5)).unwrap_or_default();
+            let crossfade = inner.crossfade.unwrap_or_default();
+            let preload_time = time
+                .checked_sub(Duration::from_secs(5) + crossfade)
+                .unwrap_or_default();
             let remote_lock = self.inner.clone();
 
             track
@@ -220,25 +757,406 @@ impl TrackQueue {
                     EventData::new(Event::Delayed(preload_time), SongPreloader { remote_lock }),
                     track.position,
                 );
+
+            if let Some(crossfade) = inner.crossfade {
+                let remote_lock = self.inner.clone();
+
+                track
+                    .events
+                    .as_mut()
+                    .expect("Queue inspecting EventStore on new Track: did not exist.")
+                    .add_event(
+                        EventData::new(
+                            Event::Delayed(time.saturating_sub(crossfade)),
+                            QueueCrossfader { remote_lock },
+                        ),
+                        track.position,
+                    );
+            }
+        }
+
+        Queued(track.handle.clone(), user_id)
+    }
+
+    /// Limits the number of tracks which may be waiting in the queue,
+    /// or removes the limit if `None`.
+    ///
+    /// Once reached, adding further tracks fails with [`QueueError::Full`].
+    /// By default, the queue is unlimited, and the limit does not count the
+    /// currently playing track: see [`set_max_len_includes_current`].
+    /// Lowering the limit does not remove any tracks already queued.
+    ///
+    /// [`QueueError::Full`]: QueueError::Full
+    /// [`set_max_len_includes_current`]: TrackQueue::set_max_len_includes_current
+    pub fn set_max_len(&self, max_len: Option<usize>) {
+        let mut inner = self.inner.lock();
+
+        inner.max_len = max_len;
+    }
+
+    /// Sets whether the currently playing track counts towards the queue's
+    /// [maximum length].
+    ///
+    /// Defaults to `false`.
+    ///
+    /// [maximum length]: TrackQueue::set_max_len
+    pub fn set_max_len_includes_current(&self, includes_current: bool) {
+        let mut inner = self.inner.lock();
+
+        inner.max_len_includes_current = includes_current;
+    }
+
+    /// Sets a guard which decides whether each track may be added to the queue,
+    /// replacing any existing guard.
+    ///
+    /// The guard is consulted after the queue's [maximum length], and may reject
+    /// tracks with [`QueueError::Rejected`]. Tracks added via [`play_now`] bypass it.
+    ///
+    /// # Example
+    ///
+    /// Limiting each user to three queued tracks:
+    ///
+    /// ```rust
+    /// use songbird::tracks::{EnqueueRequest, TrackQueue};
+    ///
+    /// let queue = TrackQueue::new();
+    /// queue.set_enqueue_guard(|req: &EnqueueRequest| {
+    ///     if req.user_len >= 3 {
+    ///         Err("you already have 3 tracks queued".into())
+    ///     } else {
+    ///         Ok(())
+    ///     }
+    /// });
+    /// ```
+    ///
+    /// [maximum length]: TrackQueue::set_max_len
+    /// [`QueueError::Rejected`]: QueueError::Rejected
+    /// [`play_now`]: TrackQueue::play_now
+    pub fn set_enqueue_guard(&self, guard: impl EnqueueGuard + 'static) {
+        let mut inner = self.inner.lock();
+
+        inner.guard = Some(Box::new(guard));
+    }
+
+    /// Removes the queue's [`EnqueueGuard`], if any.
+    ///
+    /// [`EnqueueGuard`]: EnqueueGuard
+    pub fn clear_enqueue_guard(&self) {
+        let mut inner = self.inner.lock();
+
+        inner.guard = None;
+    }
+
+    /// Sets the volume of the current track, and of every track subsequently
+    /// played by this queue.
+    ///
+    /// Queued tracks have this volume applied as they begin to play, so calling
+    /// [`TrackHandle::set_volume`] on the current track will override it until
+    /// the next track starts.
+    ///
+    /// [`TrackHandle::set_volume`]: TrackHandle::set_volume
+    pub fn set_volume(&self, volume: f32) -> TrackResult<()> {
+        let mut inner = self.inner.lock();
+
+        inner.volume = Some(volume);
+
+        if let Some(handle) = inner.tracks.front() {
+            handle.set_volume(volume)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns the volume set via [`set_volume`], if any.
+    ///
+    /// [`set_volume`]: TrackQueue::set_volume
+    pub fn volume(&self) -> Option<f32> {
+        let inner = self.inner.lock();
+
+        inner.volume
+    }
+
+    /// Overlaps the end of each track with the start of the next, fading the
+    /// outgoing track down while the incoming track fades up over `crossfade`,
+    /// or plays tracks strictly in sequence if `crossfade` is zero.
+    ///
+    /// The overlap is shortened if either track is shorter than `crossfade`.
+    /// Tracks whose length is unknown, or which are looping or [repeated], end without
+    /// a crossfade.
+    /// [Skipping] a track mid-crossfade cuts straight to the incoming track at full
+    /// volume. This applies to tracks added after the crossfade is set.
+    ///
+    /// [repeated]: TrackQueue::set_repeat
+    /// [Skipping]: TrackQueue::skip
+    pub fn set_crossfade(&self, crossfade: Duration) {
+        let mut inner = self.inner.lock();
+
+        inner.crossfade = Some(crossfade).filter(|d| !d.is_zero());
+    }
+
+    /// Returns the crossfade set via [`set_crossfade`], if any.
+    ///
+    /// [`set_crossfade`]: TrackQueue::set_crossfade
+    pub fn crossfade(&self) -> Option<Duration> {
+        let inner = self.inner.lock();
+
+        inner.crossfade
+    }
+
+    /// Sets whether the queue repeats its tracks once they finish.
+    ///
+    /// A track is only repeated by the queue once it has used up any loops of its
+    /// own, set via [`TrackHandle::enable_loop`] and similar, which are left unchanged.
+    /// Repeating a track rewinds it, so every track must support seeking.
+    /// If any queued track cannot seek, this fails with [`QueueError::NotSeekable`]
+    /// and the mode is unchanged. While repeating, adding such tracks fails in the
+    /// same way, although tracks added via [`play_now`] always play once.
+    ///
+    /// In [`QueueRepeat::All`] mode, [skipping] a track moves it to the back of the
+    /// queue rather than removing it. Changes take effect from the end of the
+    /// current track.
+    ///
+    /// [`TrackHandle::enable_loop`]: TrackHandle::enable_loop
+    /// [`QueueError::NotSeekable`]: QueueError::NotSeekable
+    /// [`play_now`]: TrackQueue::play_now
+    /// [`QueueRepeat::All`]: QueueRepeat::All
+    /// [skipping]: TrackQueue::skip
+    pub fn set_repeat(&self, repeat: QueueRepeat) -> QueueResult<()> {
+        let inner = self.inner.lock();
+
+        if repeat != QueueRepeat::Off {
+            if let Some(track) = inner.tracks.iter().find(|q| !q.is_seekable()) {
+                return Err(QueueError::NotSeekable(track.uuid()));
+            }
         }
 
-        inner.tracks.push_back(Queued(track_handle));
+        inner.repeat.set(repeat);
+
+        Ok(())
+    }
+
+    /// Returns the queue's repeat mode.
+    pub fn repeat(&self) -> QueueRepeat {
+        let inner = self.inner.lock();
+
+        inner.repeat.get()
+    }
+
+    /// Sets how the queue reacts when a queued track's input fails.
+    ///
+    /// Handlers for [`TrackEvent::Error`] still receive the failing track's state
+    /// and handle, regardless of the policy chosen.
+    ///
+    /// [`TrackEvent::Error`]: crate::events::TrackEvent::Error
+    pub fn set_on_error(&self, policy: QueueErrorPolicy) {
+        let mut inner = self.inner.lock();
+
+        inner.on_error = policy;
     }
 
-    /// Returns a handle to the currently playing track.
+    /// Returns a handle to the currently playing track, or `None` if the queue is empty.
+    ///
+    /// This is the track at [`current_index`]. Handles are cheap to clone, so this
+    /// avoids taking a snapshot of the whole queue via [`current_queue`].
+    ///
+    /// [`current_index`]: TrackQueue::current_index
+    /// [`current_queue`]: TrackQueue::current_queue
     pub fn current(&self) -> Option<TrackHandle> {
         let inner = self.inner.lock();
 
         inner.tracks.front().map(|h| h.handle())
     }
 
+    /// Returns the index of the currently playing track, if the queue is not empty.
+    ///
+    /// The active track is always held at the head of the queue, so this is
+    /// currently always `Some(0)` for a non-empty queue.
+    pub fn current_index(&self) -> Option<usize> {
+        let inner = self.inner.lock();
+
+        if inner.tracks.is_empty() {
+            None
+        } else {
+            Some(0)
+        }
+    }
+
+    /// Stops the current track and begins playing the track at `index`,
+    /// discarding all tracks queued before it.
+    ///
+    /// Every discarded track is stopped, firing its [`TrackEvent::End`] handlers.
+    /// Jumping to index `0` (the current track) has no effect, and indices outside
+    /// the queue fail with [`QueueError::IndexOutOfRange`]. Unlike [`skip`], this
+    /// ignores the queue's [repeat mode].
+    ///
+    /// [`TrackEvent::End`]: crate::events::TrackEvent::End
+    /// [`QueueError::IndexOutOfRange`]: QueueError::IndexOutOfRange
+    /// [`skip`]: TrackQueue::skip
+    /// [repeat mode]: TrackQueue::set_repeat
+    pub fn jump_to(&self, index: usize) -> QueueResult<()> {
+        let mut inner = self.inner.lock();
+
+        let len = inner.tracks.len();
+        if index >= len {
+            return Err(QueueError::IndexOutOfRange { index, len });
+        }
+
+        if index != 0 {
+            inner.cut_crossfade();
+        }
+
+        let skipped: Vec<Queued> = inner.tracks.drain(..index).collect();
+        for track in &skipped {
+            // Errors when removing tracks don't really make
+            // a difference: an error just implies it's already gone.
+            let _ = track.stop();
+
+            // Each removal shifts the remaining tracks to the front.
+            inner.emit_removed(0, track);
+        }
+
+        if index != 0 {
+            inner.play_current()?;
+            inner.emit_started();
+        }
+
+        Ok(())
+    }
+
+    /// Skips ahead to the track at `index`, as with [`jump_to`].
+    ///
+    /// [`jump_to`]: TrackQueue::jump_to
+    pub fn skip_to(&self, index: usize) -> QueueResult<()> {
+        self.jump_to(index)
+    }
+
     /// Attempts to remove a track from the specified index.
     ///
     /// The returned entry can be readded to *this* queue via [`modify_queue`].
     ///
     /// [`modify_queue`]: TrackQueue::modify_queue
     pub fn dequeue(&self, index: usize) -> Option<Queued> {
-        self.modify_queue(|vq| vq.remove(index))
+        let mut inner = self.inner.lock();
+
+        if inner.tracks.get(index).map(|q| q.uuid()) == inner.crossfading {
+            inner.abandon_crossfade();
+        }
+
+        let removed = inner.tracks.remove(index);
+        if let Some(track) = &removed {
+            inner.emit_removed(index, track);
+        }
+
+        removed
+    }
+
+    /// Moves the track at `from` to `to`, preserving the order of every other track.
+    ///
+    /// Fails with [`QueueError::IndexOutOfRange`] if either index is outside the
+    /// queue, or with [`QueueError::CurrentTrack`] if either is `0`, as the current
+    /// track must stay at the head of the queue.
+    ///
+    /// [`QueueError::IndexOutOfRange`]: QueueError::IndexOutOfRange
+    /// [`QueueError::CurrentTrack`]: QueueError::CurrentTrack
+    pub fn move_track(&self, from: usize, to: usize) -> QueueResult<()> {
+        let mut inner = self.inner.lock();
+
+        let len = inner.tracks.len();
+        if let Some(&index) = [from, to].iter().find(|i| **i >= len) {
+            return Err(QueueError::IndexOutOfRange { index, len });
+        }
+
+        if from == 0 || to == 0 {
+            return Err(QueueError::CurrentTrack);
+        }
+
+        if from == to {
+            return Ok(());
+        }
+
+        if from == 1 || to == 1 {
+            inner.abandon_crossfade();
+        }
+
+        let track = inner
+            .tracks
+            .remove(from)
+            .expect("Index was checked to be within the queue.");
+        let uuid = track.uuid();
+        inner.tracks.insert(to, track);
+        inner.emit(QueueEvent::Changed(QueueChange::Moved { from, to, uuid }));
+
+        Ok(())
+    }
+
+    /// Randomly reorders every track waiting in the queue.
+    ///
+    /// The current track keeps playing, and stays at the head of the queue.
+    pub fn shuffle(&self) {
+        self.shuffle_from(0);
+    }
+
+    /// Randomly reorders every track after `index`, leaving the tracks up to and
+    /// including `index` in place.
+    ///
+    /// This has no effect if there are no tracks after `index`.
+    pub fn shuffle_from(&self, index: usize) {
+        self.shuffle_with(index, &mut rand::thread_rng());
+    }
+
+    /// Randomly reorders every track after `index` using the given random number
+    /// generator, such as a seeded RNG for a reproducible order.
+    ///
+    /// See [`shuffle_from`].
+    ///
+    /// [`shuffle_from`]: TrackQueue::shuffle_from
+    pub fn shuffle_with<R: Rng + ?Sized>(&self, index: usize, rng: &mut R) {
+        let mut inner = self.inner.lock();
+
+        let start = index.saturating_add(1);
+        if start >= inner.tracks.len() {
+            return;
+        }
+
+        if index == 0 {
+            inner.abandon_crossfade();
+        }
+
+        inner.tracks.make_contiguous()[start..].shuffle(rng);
+        inner.emit(QueueEvent::Changed(QueueChange::Shuffled { index }));
+    }
+
+    /// Stops and removes every track in `range`, returning their handles.
+    ///
+    /// The current track (index `0`) is never removed, and the range is clamped to
+    /// the length of the queue. Each removed track is stopped, firing its
+    /// [`TrackEvent::End`] handlers.
+    ///
+    /// [`TrackEvent::End`]: crate::events::TrackEvent::End
+    pub fn dequeue_range(&self, range: Range<usize>) -> Vec<TrackHandle> {
+        let mut inner = self.inner.lock();
+
+        let start = range.start.max(1);
+        let end = range.end.min(inner.tracks.len());
+        if start >= end {
+            return vec![];
+        }
+
+        if start == 1 {
+            inner.abandon_crossfade();
+        }
+
+        let removed: Vec<Queued> = inner.tracks.drain(start..end).collect();
+        for track in &removed {
+            // Errors when removing tracks don't really make
+            // a difference: an error just implies it's already gone.
+            let _ = track.stop();
+
+            // Each removal shifts the remaining tracks down.
+            inner.emit_removed(start, track);
+        }
+
+        removed.iter().map(Queued::handle).collect()
     }
 
     /// Returns the number of tracks currently in the queue.
@@ -259,12 +1177,23 @@ impl TrackQueue {
     ///
     /// Users must be careful to `stop` removed tracks, so as to prevent
     /// resource leaks.
+    ///
+    /// As any change may have been made, this sends [`QueueChange::Rewritten`]
+    /// to [`events`] receivers.
+    ///
+    /// [`QueueChange::Rewritten`]: QueueChange::Rewritten
+    /// [`events`]: TrackQueue::events
     pub fn modify_queue<F, O>(&self, func: F) -> O
     where
         F: FnOnce(&mut VecDeque<Queued>) -> O,
     {
         let mut inner = self.inner.lock();
-        func(&mut inner.tracks)
+        inner.abandon_crossfade();
+        let out = func(&mut inner.tracks);
+
+        inner.emit(QueueEvent::Changed(QueueChange::Rewritten));
+
+        out
     }
 
     /// Pause the track at the head of the queue.
@@ -293,18 +1222,41 @@ impl TrackQueue {
     pub fn stop(&self) {
         let mut inner = self.inner.lock();
 
+        if inner.tracks.is_empty() {
+            return;
+        }
+
+        inner.crossfading = None;
+
         for track in inner.tracks.drain(..) {
             // Errors when removing tracks don't really make
             // a difference: an error just implies it's already gone.
             let _ = track.stop();
         }
+
+        inner.emit(QueueEvent::Changed(QueueChange::Cleared));
+        inner.emit(QueueEvent::QueueEmptied);
     }
 
     /// Skip to the next track in the queue, if it exists.
+    ///
+    /// If the queue [repeats] all tracks, the current track is moved to the back
+    /// of the queue. If the next track is already fading in over a [crossfade], it immediately
+    /// plays at full volume.
+    ///
+    /// [repeats]: TrackQueue::set_repeat
+    /// [crossfade]: TrackQueue::set_crossfade
     pub fn skip(&self) -> TrackResult<()> {
-        let inner = self.inner.lock();
+        let mut inner = self.inner.lock();
+
+        inner.cut_crossfade();
 
-        inner.stop_current()
+        if inner.repeat.get() == QueueRepeat::All {
+            inner.requeue_current(true);
+            Ok(())
+        } else {
+            inner.stop_current()
+        }
     }
 
     /// Returns a list of currently queued tracks.
@@ -319,9 +1271,168 @@ impl TrackQueue {
 
         inner.tracks.iter().map(|q| q.handle()).collect()
     }
+
+    /// Returns a receiver for changes to this queue, as an alternative to
+    /// attaching [`TrackEvent`] handlers to each queued track.
+    ///
+    /// Each call creates a new receiver, which sees every event from that point on.
+    /// The channel is bounded to [`QUEUE_EVENT_CAPACITY`] events: a receiver which
+    /// falls this far behind misses any further events until it catches up.
+    /// Dropped receivers are cleaned up as the next event is sent.
+    ///
+    /// Every change to the queue's contents or order is described by a
+    /// [`QueueEvent::Changed`] event, allowing displays of the queue to be
+    /// updated incrementally.
+    ///
+    /// [`TrackEvent`]: crate::events::TrackEvent
+    /// [`QUEUE_EVENT_CAPACITY`]: QUEUE_EVENT_CAPACITY
+    /// [`QueueEvent::Changed`]: QueueEvent::Changed
+    pub fn events(&self) -> Receiver<QueueEvent> {
+        let mut inner = self.inner.lock();
+        let (tx, rx) = flume::bounded(QUEUE_EVENT_CAPACITY);
+
+        inner.subscribers.push(tx);
+
+        rx
+    }
 }
 
 impl TrackQueueCore {
+    /// Sends an event to every live receiver, dropping it for any which are full.
+    fn emit(&mut self, event: QueueEvent) {
+        self.subscribers.retain(|tx| {
+            !matches!(
+                tx.try_send(event.clone()),
+                Err(TrySendError::Disconnected(_))
+            )
+        });
+    }
+
+    /// Announces that `track` was removed from `index`.
+    fn emit_removed(&mut self, index: usize, track: &Queued) {
+        self.emit(QueueEvent::Changed(QueueChange::Removed {
+            index,
+            uuid: track.uuid(),
+        }));
+    }
+
+    /// Announces that the track at the head of the queue has started.
+    fn emit_started(&mut self) {
+        if let Some(handle) = self.tracks.front().map(Queued::handle) {
+            self.emit(QueueEvent::TrackStarted(handle));
+        }
+    }
+
+    /// Start playing the track at the head of the queue, if it exists,
+    /// applying the queue's volume.
+    fn play_current(&self) -> TrackResult<()> {
+        if let Some(handle) = self.tracks.front() {
+            if let Some(volume) = self.volume {
+                handle.set_volume(volume)?;
+            }
+
+            handle.play()
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Plays the track at the head of the queue, discarding any which fail
+    /// to play until one succeeds or the queue is empty.
+    fn play_next(&mut self) {
+        while !self.tracks.is_empty() {
+            if self.play_current().is_err() {
+                // Discard files which cannot be used for whatever reason.
+                warn!("Track in Queue couldn't be played...");
+                if let Some(old) = self.tracks.pop_front() {
+                    self.emit_removed(0, &old);
+                }
+            } else {
+                self.emit_started();
+                break;
+            }
+        }
+
+        if self.tracks.is_empty() {
+            self.emit(QueueEvent::QueueEmptied);
+        }
+    }
+
+    /// Moves the current track to the back of the queue, then plays the next track.
+    ///
+    /// If `rewind` is set, the track is first paused and returned to its start.
+    fn requeue_current(&mut self, rewind: bool) {
+        let old = match self.tracks.pop_front() {
+            Some(old) => old,
+            None => return,
+        };
+
+        self.emit_removed(0, &old);
+
+        // A track which cannot be rewound has most likely already ended.
+        if !rewind
+            || old
+                .pause()
+                .and_then(|_| old.seek_time(Duration::ZERO))
+                .is_ok()
+        {
+            let index = self.tracks.len();
+            self.emit(QueueEvent::Changed(QueueChange::Added {
+                index,
+                uuid: old.uuid(),
+            }));
+            self.tracks.push_back(old);
+        }
+
+        self.play_next();
+    }
+
+    /// Start playing the next track over the end of the current track, fading
+    /// between them over `window`.
+    fn start_crossfade(&mut self, window: Duration) -> TrackResult<()> {
+        let (current, next) = match (self.tracks.front(), self.tracks.get(1)) {
+            (Some(current), Some(next)) => (current, next),
+            _ => return Ok(()),
+        };
+
+        if let Some(volume) = self.volume {
+            next.set_volume(volume)?;
+        }
+
+        next.set_fade_in(window)?;
+        current.set_fade_out(window)?;
+        next.play()?;
+
+        info!("Crossfading into next queued track over {:?}.", window);
+        self.crossfading = Some(next.uuid());
+
+        Ok(())
+    }
+
+    /// Brings any track fading in over a crossfade to full volume.
+    fn cut_crossfade(&mut self) {
+        if let Some(uuid) = self.crossfading.take() {
+            if let Some(next) = self.tracks.iter().find(|q| q.uuid() == uuid) {
+                let _ = next.set_fade_in(Duration::ZERO);
+            }
+        }
+    }
+
+    /// Pauses any track fading in over a crossfade, so that it plays in full
+    /// once it reaches the head of the queue, and restores the current track.
+    fn abandon_crossfade(&mut self) {
+        if let Some(uuid) = self.crossfading.take() {
+            if let Some(next) = self.tracks.iter().find(|q| q.uuid() == uuid) {
+                let _ = next.pause();
+                let _ = next.set_fade_in(Duration::ZERO);
+            }
+
+            if let Some(current) = self.tracks.front() {
+                let _ = current.set_fade_out(Duration::ZERO);
+            }
+        }
+    }
+
     /// Skip to the next track in the queue, if it exists.
     fn stop_current(&self) -> TrackResult<()> {
         if let Some(handle) = self.tracks.front() {
@@ -331,3 +1442,346 @@ impl TrackQueueCore {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        input::{Codec, Container},
+        tracks::TrackState,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    /// Creates a queue of `len` empty tracks, returning their UUIDs in order
+    /// alongside the tracks, which accept commands while held.
+    fn queue_of(len: usize) -> (TrackQueue, Vec<Uuid>, Vec<Track>) {
+        let queue = TrackQueue::new();
+        let mut tracks = vec![];
+
+        for _ in 0..len {
+            let source = Input::new(
+                false,
+                Vec::<u8>::new().into(),
+                Codec::FloatPcm,
+                Container::Raw,
+                None,
+            );
+            let (mut track, _handle) = tracks::create_player(source);
+            queue.add_raw(&mut track, None).unwrap();
+            tracks.push(track);
+        }
+
+        let uuids = queue
+            .current_queue()
+            .iter()
+            .map(TrackHandle::uuid)
+            .collect();
+        (queue, uuids, tracks)
+    }
+
+    fn order(queue: &TrackQueue, uuids: &[Uuid]) -> Vec<usize> {
+        queue
+            .current_queue()
+            .iter()
+            .map(|h| uuids.iter().position(|u| *u == h.uuid()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn seeded_shuffle_is_reproducible_and_keeps_prefix() {
+        let (first, first_uuids, _) = queue_of(10);
+        let (second, second_uuids, _) = queue_of(10);
+
+        first.shuffle_with(2, &mut StdRng::seed_from_u64(0x5eed));
+        second.shuffle_with(2, &mut StdRng::seed_from_u64(0x5eed));
+
+        let shuffled = order(&first, &first_uuids);
+        assert_eq!(shuffled, order(&second, &second_uuids));
+        assert_eq!(shuffled[..3], [0, 1, 2]);
+
+        let mut sorted = shuffled.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..10).collect::<Vec<_>>());
+        assert_ne!(shuffled, sorted);
+    }
+
+    #[test]
+    fn guard_rejects_with_its_reason() {
+        let (queue, ..) = queue_of(0);
+        queue.set_enqueue_guard(|req: &EnqueueRequest| {
+            if req.user_len >= 2 {
+                Err(format!("user already has {} tracks", req.user_len).into())
+            } else {
+                Ok(())
+            }
+        });
+
+        let user = UserId(1);
+        let add = |user_id| {
+            let source = Input::float_pcm(false, Vec::<u8>::new().into());
+            let (mut track, _handle) = tracks::create_player(source);
+            queue.add_raw(&mut track, user_id)
+        };
+
+        assert!(add(Some(user)).is_ok());
+        assert!(add(Some(user)).is_ok());
+        assert_eq!(
+            add(Some(user)),
+            Err(QueueError::Rejected("user already has 2 tracks".into()))
+        );
+        assert!(add(None).is_ok());
+        assert_eq!(queue.len(), 3);
+    }
+
+    #[test]
+    fn move_track_preserves_other_tracks() {
+        let (queue, uuids, _) = queue_of(5);
+
+        queue.move_track(1, 3).unwrap();
+        assert_eq!(order(&queue, &uuids), [0, 2, 3, 1, 4]);
+
+        queue.move_track(4, 2).unwrap();
+        assert_eq!(order(&queue, &uuids), [0, 2, 4, 3, 1]);
+
+        assert_eq!(queue.move_track(0, 2), Err(QueueError::CurrentTrack));
+        assert_eq!(queue.move_track(2, 0), Err(QueueError::CurrentTrack));
+        assert_eq!(
+            queue.move_track(1, 5),
+            Err(QueueError::IndexOutOfRange { index: 5, len: 5 })
+        );
+    }
+
+    /// Reports that the head of `queue` fired `event`, while in the given mode.
+    fn finish_current(queue: &TrackQueue, event: TrackEvent, playing: PlayMode) {
+        let handle = queue.current().unwrap();
+        let state = TrackState {
+            playing,
+            ..Default::default()
+        };
+        let ctx = EventContext::Track(&[(&state, &handle)]);
+        let remote_lock = queue.inner.clone();
+
+        futures::executor::block_on(async {
+            match event {
+                TrackEvent::Loop => QueueRepeater { remote_lock }.act(&ctx).await,
+                _ => QueueHandler { remote_lock }.act(&ctx).await,
+            }
+        });
+    }
+
+    #[test]
+    fn repeat_mode_decides_what_follows_a_finished_track() {
+        let (queue, uuids, tracks) = queue_of(3);
+        assert!(tracks.iter().all(|t| t.queue_repeat() == QueueRepeat::Off));
+
+        // Repeating all tracks moves each to the back once the mixer pauses it,
+        // but not as it loops by its own `LoopState`.
+        queue.set_repeat(QueueRepeat::All).unwrap();
+        assert!(tracks.iter().all(|t| t.queue_repeat() == QueueRepeat::All));
+        finish_current(&queue, TrackEvent::Loop, PlayMode::Play);
+        assert_eq!(order(&queue, &uuids), [0, 1, 2]);
+        finish_current(&queue, TrackEvent::Loop, PlayMode::Pause);
+        assert_eq!(order(&queue, &uuids), [1, 2, 0]);
+
+        queue.set_repeat(QueueRepeat::Track).unwrap();
+        finish_current(&queue, TrackEvent::Loop, PlayMode::Pause);
+        assert_eq!(order(&queue, &uuids), [1, 2, 0]);
+
+        queue.set_repeat(QueueRepeat::Off).unwrap();
+        finish_current(&queue, TrackEvent::End, PlayMode::End);
+        assert_eq!(order(&queue, &uuids), [2, 0]);
+
+        assert!(tracks.iter().all(|t| t.loops == LoopState::Finite(0)));
+    }
+
+    #[test]
+    fn skip_to_discards_earlier_tracks() {
+        let (queue, uuids, _tracks) = queue_of(4);
+
+        queue.skip_to(2).unwrap();
+        assert_eq!(order(&queue, &uuids), [2, 3]);
+
+        assert_eq!(
+            queue.skip_to(2),
+            Err(QueueError::IndexOutOfRange { index: 2, len: 2 })
+        );
+        assert_eq!(order(&queue, &uuids), [2, 3]);
+    }
+
+    /// Creates a driver whose tasks never run, so that tracks given to it stay
+    /// alive (and accept commands) without being played.
+    fn test_driver() -> (Driver, tokio::runtime::Runtime) {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let driver = {
+            let _guard = rt.enter();
+            Driver::new(Default::default())
+        };
+
+        (driver, rt)
+    }
+
+    fn empty_source() -> Input {
+        Input::float_pcm(false, Vec::<u8>::new().into())
+    }
+
+    #[test]
+    fn max_len_rejects_tracks_once_full() {
+        let (mut driver, _rt) = test_driver();
+        let queue = TrackQueue::new();
+        queue.set_max_len(Some(1));
+
+        // The current track isn't counted by default.
+        assert!(queue.add_source(empty_source(), &mut driver).is_ok());
+        assert!(queue.add_source(empty_source(), &mut driver).is_ok());
+        assert!(matches!(
+            queue.add_source(empty_source(), &mut driver),
+            Err(QueueError::Full { max_len: 1 })
+        ));
+
+        queue.set_max_len(Some(2));
+        queue.set_max_len_includes_current(true);
+        assert!(matches!(
+            queue.add_source(empty_source(), &mut driver),
+            Err(QueueError::Full { max_len: 2 })
+        ));
+
+        queue.set_max_len(None);
+        assert!(queue.add_source(empty_source(), &mut driver).is_ok());
+        assert_eq!(queue.len(), 3);
+    }
+
+    #[test]
+    fn queue_volume_applies_to_later_tracks() {
+        let (queue, _, _tracks) = queue_of(1);
+        assert_eq!(queue.volume(), None);
+
+        queue.set_volume(0.5).unwrap();
+        assert_eq!(queue.volume(), Some(0.5));
+
+        let (mut track, _handle) = tracks::create_player(empty_source());
+        queue.add_raw(&mut track, None).unwrap();
+        assert_eq!(track.volume, 0.5);
+    }
+
+    #[test]
+    fn play_now_holds_current_track_behind_new_one() {
+        let (mut driver, _rt) = test_driver();
+        let (queue, uuids, _tracks) = queue_of(2);
+        queue.set_max_len(Some(0));
+
+        let handle = queue.play_now(empty_source(), &mut driver);
+
+        let queued: Vec<Uuid> = queue
+            .current_queue()
+            .iter()
+            .map(TrackHandle::uuid)
+            .collect();
+        assert_eq!(queued, [handle.uuid(), uuids[0], uuids[1]]);
+        assert_eq!(queue.current().map(|h| h.uuid()), Some(handle.uuid()));
+    }
+
+    #[test]
+    fn events_announce_added_and_started_tracks() {
+        let (queue, ..) = queue_of(0);
+        let events = queue.events();
+
+        let (mut track, handle) = tracks::create_player(empty_source());
+        queue.add_raw(&mut track, None).unwrap();
+
+        let received: Vec<QueueEvent> = events.try_iter().collect();
+        assert_eq!(received.len(), 3);
+        assert!(matches!(&received[0], QueueEvent::TrackAdded(h) if h.uuid() == handle.uuid()));
+        assert!(matches!(
+            received[1],
+            QueueEvent::Changed(QueueChange::Added { index: 0, uuid }) if uuid == handle.uuid()
+        ));
+        assert!(matches!(&received[2], QueueEvent::TrackStarted(h) if h.uuid() == handle.uuid()));
+    }
+
+    #[test]
+    fn every_modification_is_announced() {
+        let (queue, uuids, _tracks) = queue_of(4);
+        let events = queue.events();
+        let changes = || -> Vec<QueueChange> {
+            events
+                .try_iter()
+                .filter_map(|e| match e {
+                    QueueEvent::Changed(change) => Some(change),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        queue.dequeue(3).unwrap();
+        assert_eq!(
+            changes(),
+            [QueueChange::Removed {
+                index: 3,
+                uuid: uuids[3]
+            }]
+        );
+
+        queue.move_track(2, 1).unwrap();
+        assert_eq!(
+            changes(),
+            [QueueChange::Moved {
+                from: 2,
+                to: 1,
+                uuid: uuids[2]
+            }]
+        );
+
+        queue.modify_queue(|q| q.truncate(2));
+        assert_eq!(changes(), [QueueChange::Rewritten]);
+
+        queue.stop();
+        assert_eq!(changes(), [QueueChange::Cleared]);
+    }
+
+    #[test]
+    fn crossfade_is_disabled_by_zero() {
+        let (queue, ..) = queue_of(0);
+        assert_eq!(queue.crossfade(), None);
+
+        queue.set_crossfade(Duration::from_secs(3));
+        assert_eq!(queue.crossfade(), Some(Duration::from_secs(3)));
+
+        queue.set_crossfade(Duration::ZERO);
+        assert_eq!(queue.crossfade(), None);
+    }
+
+    #[test]
+    fn crossfade_plays_next_track_over_current() {
+        let (queue, uuids, _tracks) = queue_of(2);
+
+        queue
+            .inner
+            .lock()
+            .start_crossfade(Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(queue.inner.lock().crossfading, Some(uuids[1]));
+
+        // Skipping cuts to the incoming track, which is already playing.
+        queue.skip().unwrap();
+        assert_eq!(queue.inner.lock().crossfading, None);
+    }
+
+    #[test]
+    fn dequeue_range_keeps_current_track() {
+        let (queue, uuids, _tracks) = queue_of(5);
+
+        let removed: Vec<Uuid> = queue
+            .dequeue_range(0..3)
+            .iter()
+            .map(TrackHandle::uuid)
+            .collect();
+        assert_eq!(removed, [uuids[1], uuids[2]]);
+        assert_eq!(order(&queue, &uuids), [0, 3, 4]);
+
+        assert_eq!(queue.dequeue_range(2..10).len(), 1);
+        assert!(queue.dequeue_range(1..1).is_empty());
+        assert_eq!(order(&queue, &uuids), [0, 3]);
+    }
+}
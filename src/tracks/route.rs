@@ -0,0 +1,38 @@
+/// Output channels which a [`Track`] is mixed into.
+///
+/// [`Track`]: super::Track
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum Route {
+    /// Track audio is mixed into both output channels as normal.
+    ///
+    /// This is the default.
+    Both,
+    /// Track audio is downmixed to mono, and played only in the left channel.
+    Left,
+    /// Track audio is downmixed to mono, and played only in the right channel.
+    Right,
+}
+
+impl Route {
+    /// Rewrites one frame of interleaved stereo audio to follow this route.
+    pub(crate) fn apply(self, frame: &mut [f32]) {
+        let keep = match self {
+            Route::Both => return,
+            Route::Left => 0,
+            Route::Right => 1,
+        };
+
+        for pair in frame.chunks_exact_mut(2) {
+            let mono = 0.5 * (pair[0] + pair[1]);
+            pair[keep] = mono;
+            pair[1 - keep] = 0.0;
+        }
+    }
+}
+
+impl Default for Route {
+    fn default() -> Self {
+        Self::Both
+    }
+}
@@ -0,0 +1,58 @@
+use crate::events::{Event, EventId};
+use std::time::{Duration, Instant};
+
+/// An action which will take place on a track at some later time, as listed
+/// by [`TrackHandle::scheduled`].
+///
+/// [`TrackHandle::scheduled`]: super::TrackHandle::scheduled
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct ScheduledAction {
+    /// Identifier which may be passed to [`TrackHandle::cancel_scheduled`].
+    ///
+    /// [`TrackHandle::cancel_scheduled`]: super::TrackHandle::cancel_scheduled
+    pub id: EventId,
+    /// What will take place, and when.
+    pub kind: ScheduledKind,
+}
+
+/// Kinds of [`ScheduledAction`].
+///
+/// [`ScheduledAction`]: ScheduledAction
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum ScheduledKind {
+    /// A [`Delayed`] or [`Periodic`] event handler, added with [`TrackHandle::add_event`].
+    ///
+    /// [`Delayed`]: Event::Delayed
+    /// [`Periodic`]: Event::Periodic
+    /// [`TrackHandle::add_event`]: super::TrackHandle::add_event
+    Event {
+        /// The event which the handler was registered for.
+        event: Event,
+        /// Track position at which the handler will next fire, if known.
+        fire_time: Option<Duration>,
+    },
+    /// A cue point, added with [`TrackHandle::add_cue`] or [`Track::cue_chapters`].
+    ///
+    /// [`TrackHandle::add_cue`]: super::TrackHandle::add_cue
+    /// [`Track::cue_chapters`]: super::Track::cue_chapters
+    Cue {
+        /// Track position at which the cue fires.
+        at: Duration,
+        /// Name of the cue.
+        name: String,
+    },
+    /// A delayed start, set by [`Driver::play_at`].
+    ///
+    /// Cancelling this starts the track on the next frame.
+    ///
+    /// [`Driver::play_at`]: crate::driver::Driver::play_at
+    Start(Instant),
+    /// Volume keyframes, set by [`TrackHandle::set_volume_automation`].
+    ///
+    /// Cancelling these keeps the track at its current volume.
+    ///
+    /// [`TrackHandle::set_volume_automation`]: super::TrackHandle::set_volume_automation
+    VolumeAutomation(Vec<(Duration, f32)>),
+}
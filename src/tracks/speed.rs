@@ -0,0 +1,99 @@
+use crate::constants::*;
+use std::mem;
+
+/// Number of stereo sample pairs in each frame.
+const FRAME_PAIRS: usize = STEREO_FRAME_SIZE / 2;
+
+/// Resampler which changes the playback speed (and so the pitch) of a [`Track`],
+/// reading more or less of its input for each frame played.
+///
+/// Output samples are linearly interpolated from the input, which is read one
+/// frame at a time as needed.
+///
+/// [`Track`]: super::Track
+#[derive(Clone, Debug)]
+pub(crate) struct SpeedChange {
+    speed: f32,
+    /// Stereo sample pairs read from the input, but not yet played past.
+    buffer: Vec<[f32; 2]>,
+    /// Position of the next output sample within `buffer`, in sample pairs.
+    phase: f64,
+}
+
+impl SpeedChange {
+    pub(crate) fn new(speed: f32) -> Self {
+        Self {
+            speed,
+            buffer: Vec::with_capacity(3 * FRAME_PAIRS),
+            phase: 0.0,
+        }
+    }
+
+    pub(crate) fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Discards any buffered input, such as after a seek.
+    pub(crate) fn reset(&mut self) {
+        self.buffer.clear();
+        self.phase = 0.0;
+    }
+
+    /// Fills `frame` with one frame of resampled audio, reading frames of input
+    /// via `read` until enough are buffered.
+    ///
+    /// `read` returns the number of bytes of audio it wrote, where less than a whole
+    /// frame marks the end of the input. Returns the number of bytes of audio written
+    /// to `frame` in turn, or `None` if the input failed.
+    pub(crate) fn process<F>(
+        &mut self,
+        frame: &mut [f32; STEREO_FRAME_SIZE],
+        mut read: F,
+    ) -> Option<usize>
+    where
+        F: FnMut(&mut [f32; STEREO_FRAME_SIZE]) -> Option<usize>,
+    {
+        let step = f64::from(self.speed);
+
+        // Interpolating the last sample of the frame needs the pair after it.
+        let needed = (self.phase + step * (FRAME_PAIRS - 1) as f64) as usize + 2;
+        let mut ended = false;
+
+        while self.buffer.len() < needed && !ended {
+            let mut chunk = [0f32; STEREO_FRAME_SIZE];
+            let len = read(&mut chunk)?;
+            let pairs = (len / (2 * mem::size_of::<f32>())).min(FRAME_PAIRS);
+
+            self.buffer.extend(
+                chunk[..2 * pairs]
+                    .chunks_exact(2)
+                    .map(|pair| [pair[0], pair[1]]),
+            );
+            ended = pairs < FRAME_PAIRS;
+        }
+
+        let mut written = 0;
+        for (i, out) in frame.chunks_exact_mut(2).enumerate() {
+            let pos = self.phase + step * i as f64;
+            let base = pos as usize;
+
+            let a = match self.buffer.get(base) {
+                Some(a) => *a,
+                None => break,
+            };
+            let b = self.buffer.get(base + 1).copied().unwrap_or(a);
+            let frac = (pos - base as f64) as f32;
+
+            out[0] = a[0] + (b[0] - a[0]) * frac;
+            out[1] = a[1] + (b[1] - a[1]) * frac;
+            written += 1;
+        }
+
+        let next = self.phase + step * FRAME_PAIRS as f64;
+        let consumed = (next as usize).min(self.buffer.len());
+        self.buffer.drain(..consumed);
+        self.phase = next - consumed as f64;
+
+        Some(written * 2 * mem::size_of::<f32>())
+    }
+}
@@ -1,16 +1,37 @@
 use super::*;
+use std::time::Instant;
 
 /// State of an [`Track`] object, designed to be passed to event handlers
 /// and retrieved remotely via [`TrackHandle::get_info`].
 ///
 /// [`Track`]: Track
 /// [`TrackHandle::get_info`]: TrackHandle::get_info
-#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[non_exhaustive]
 pub struct TrackState {
     /// Play status (e.g., active, paused, stopped) of this track.
     pub playing: PlayMode,
     /// Current volume of this track.
     pub volume: f32,
+    /// Current pitch shift of this track, in semitones.
+    pub pitch: f32,
+    /// Current playback speed of this track, where `1.0` is normal speed.
+    pub speed: f32,
+    /// Output channels which this track is played through.
+    pub route: Route,
+    /// Position of this track in the stereo field, from `-1.0` (full left)
+    /// to `1.0` (full right).
+    pub pan: f32,
+    /// Whether this track is muted, such as while pre-started with [`Track::unmute_at`].
+    ///
+    /// [`Track::unmute_at`]: Track::unmute_at
+    pub muted: bool,
+    /// Channel layout of this track's source.
+    ///
+    /// Surround layouts are downmixed to stereo, as set by [`Track::set_downmix`].
+    ///
+    /// [`Track::set_downmix`]: Track::set_downmix
+    pub layout: ChannelLayout,
     /// Current playback position in the source.
     ///
     /// This is altered by loops and seeks, and represents this track's
@@ -20,11 +41,54 @@ pub struct TrackState {
     pub play_time: Duration,
     /// Remaining loops on this track.
     pub loops: LoopState,
+    /// Time at which this track was created.
+    pub created_at: Instant,
+    /// Time at which this track first played a frame of audio, if it has.
+    pub started_at: Option<Instant>,
+    /// Time at which this track was stopped, ended, or errored, if it has been.
+    ///
+    /// Unlike [`play_time`], the span between [`started_at`] and this point
+    /// includes any time spent paused.
+    ///
+    /// [`play_time`]: TrackState::play_time
+    /// [`started_at`]: TrackState::started_at
+    pub ended_at: Option<Instant>,
+}
+
+impl Default for TrackState {
+    fn default() -> Self {
+        Self {
+            playing: Default::default(),
+            volume: Default::default(),
+            pitch: Default::default(),
+            speed: 1.0,
+            route: Default::default(),
+            pan: 0.0,
+            muted: false,
+            layout: Default::default(),
+            position: Default::default(),
+            play_time: Default::default(),
+            loops: Default::default(),
+            created_at: Instant::now(),
+            started_at: None,
+            ended_at: None,
+        }
+    }
 }
 
 impl TrackState {
     pub(crate) fn step_frame(&mut self) {
-        self.position += TIMESTEP_LENGTH;
+        // Position follows the source, which is read faster or slower than
+        // real time when the playback speed is changed.
+        self.position += TIMESTEP_LENGTH.mul_f64(f64::from(self.speed));
         self.play_time += TIMESTEP_LENGTH;
+        self.started_at.get_or_insert_with(Instant::now);
+    }
+
+    pub(crate) fn set_mode(&mut self, mode: PlayMode) {
+        self.playing = mode;
+        if mode.is_done() {
+            self.ended_at.get_or_insert_with(Instant::now);
+        }
     }
 }
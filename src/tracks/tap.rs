@@ -0,0 +1,24 @@
+use super::FrameTapFn;
+use std::fmt;
+
+/// Callback receiving each frame of a track's decoded audio, before
+/// volume or any other processing is applied.
+///
+/// Audio is interleaved stereo, 16-bit PCM at 48kHz.
+pub(crate) struct FrameTap(Box<FrameTapFn>);
+
+impl FrameTap {
+    pub(crate) fn new(tap: Box<FrameTapFn>) -> Self {
+        Self(tap)
+    }
+
+    pub(crate) fn call(&mut self, frame: &[i16]) {
+        (self.0)(frame)
+    }
+}
+
+impl fmt::Debug for FrameTap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("FrameTap").field(&"<fn>").finish()
+    }
+}